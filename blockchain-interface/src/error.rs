@@ -55,10 +55,16 @@ pub enum BlockchainError {
     BlockBodyNotFound,
     #[error("Block is not a macro block")]
     BlockIsNotMacro,
+    #[error("Block is not an election block")]
+    BlockIsNotElection,
+    #[error("The database already contains a chain")]
+    ChainAlreadyInitialized,
     #[error("No validators found")]
     NoValidatorsFound,
     #[error("Invalid epoch ID")]
     InvalidEpoch,
+    #[error("Accounts state not available at block {0}, the earliest available block is {1}")]
+    AccountsStateNotAvailable(u32, u32),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -1,12 +1,15 @@
 use crate::blockchain_state::BlockchainState;
 use crate::history::ExtendedTransaction;
 use crate::Blockchain;
-use nimiq_account::Accounts;
 use nimiq_account::BlockLog;
+use nimiq_account::{Account, Accounts};
 use nimiq_block::{Block, BlockError::TransactionExecutionMismatch, SkipBlockInfo};
-use nimiq_blockchain_interface::PushError;
-use nimiq_database::WriteTransaction;
+use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainError, PushError};
+use nimiq_database::{ReadTransaction, Transaction, WriteTransaction};
+use nimiq_hash::Blake2bHash;
 use nimiq_primitives::policy::Policy;
+use nimiq_trie::key_nibbles::KeyNibbles;
+use nimiq_trie::trie_proof::TrieProof;
 
 /// Implements methods to handle the accounts.
 impl Blockchain {
@@ -143,6 +146,143 @@ impl Blockchain {
         }
     }
 
+    /// Reconstructs the state of the accounts right after the block at the given block number and
+    /// calls `f` with it. The state is reconstructed by reverting the blocks after the given block
+    /// number in an overlay transaction, which keeps the changes in memory and never writes to
+    /// the database.
+    ///
+    /// Macro blocks can't be reverted and the receipts needed to revert micro blocks are discarded
+    /// at each macro block. Therefore, only the state at the last macro block and the blocks after
+    /// it can be reconstructed, i.e. only within the current batch, even though the blocks of the
+    /// retained epochs are still stored. Older block numbers fail with `AccountsStateNotAvailable`,
+    /// which contains the earliest available block number.
+    pub fn with_accounts_at<F, R>(&self, block_number: u32, f: F) -> Result<R, BlockchainError>
+    where
+        F: FnOnce(&Accounts, &Transaction) -> R,
+    {
+        let head = self.head();
+        let head_number = head.block_number();
+
+        if block_number > head_number {
+            return Err(BlockchainError::BlockNotFound);
+        }
+
+        let earliest_block_number = Policy::last_macro_block(head_number);
+        if block_number < earliest_block_number {
+            return Err(BlockchainError::AccountsStateNotAvailable(
+                block_number,
+                earliest_block_number,
+            ));
+        }
+
+        let accounts = &self.state.accounts;
+        let mut txn = self.overlay_transaction();
+
+        // A block might have been committed to the database, but not yet to the in-memory state.
+        // In that case we can't revert starting from our head.
+        if accounts.get_root(Some(&txn)) != *head.state_root() {
+            return Err(BlockchainError::InconsistentState);
+        }
+
+        for number in (block_number + 1..=head_number).rev() {
+            let block = self.chain_store.get_block_at(number, true, Some(&txn))?;
+            // There can't be a macro block after the last one, unless our state is inconsistent.
+            let micro_block = match block {
+                Block::Micro(micro_block) => micro_block,
+                Block::Macro(_) => return Err(BlockchainError::InconsistentState),
+            };
+            let body = micro_block
+                .body
+                .as_ref()
+                .ok_or(BlockchainError::BlockBodyNotFound)?;
+
+            let skip_block_info = if micro_block.is_skip_block() {
+                Some(SkipBlockInfo {
+                    block_number: micro_block.header.block_number,
+                    vrf_entropy: micro_block.header.seed.entropy(),
+                })
+            } else {
+                None
+            };
+            let inherents =
+                self.create_slash_inherents(&body.fork_proofs, skip_block_info, Some(&txn));
+
+            let receipts = self.chain_store.get_receipts(number, Some(&txn)).ok_or(
+                BlockchainError::AccountsStateNotAvailable(block_number, earliest_block_number),
+            )?;
+
+            // Only the accounts are reverted, the history is not needed to query them.
+            accounts
+                .revert(
+                    &mut txn,
+                    &body.transactions,
+                    &inherents,
+                    number,
+                    micro_block.header.timestamp,
+                    &receipts,
+                )
+                .map_err(|_| BlockchainError::InconsistentState)?;
+        }
+
+        Ok(f(accounts, &txn))
+    }
+
+    /// Takes a snapshot of the current state of the accounts in `txn` at the given election
+    /// block, removing the oldest snapshot if there are more than the configured number. Does
+    /// nothing if snapshots are disabled.
+    pub(crate) fn take_accounts_snapshot(
+        &self,
+        block_number: u32,
+        block_hash: &Blake2bHash,
+        txn: &mut WriteTransaction,
+    ) {
+        if !self.config.keep_accounts_snapshot {
+            return;
+        }
+
+        self.state.accounts.tree.take_snapshot(txn, block_number);
+        self.chain_store.set_accounts_snapshot(txn, block_hash);
+    }
+
+    /// Returns a chunk of the accounts snapshot at the given election block together with a proof
+    /// for it against the state root of that block. The chunk starts at the key `start` and
+    /// contains at most `size` accounts. Returns None if there is no snapshot at that block.
+    pub fn get_accounts_snapshot_chunk(
+        &self,
+        block_hash: &Blake2bHash,
+        start: &KeyNibbles,
+        size: usize,
+        txn_option: Option<&Transaction>,
+    ) -> Option<(Vec<Account>, TrieProof<Account>)> {
+        let read_txn: ReadTransaction;
+        let txn = match txn_option {
+            Some(txn) => txn,
+            None => {
+                read_txn = self.read_transaction();
+                &read_txn
+            }
+        };
+
+        // Snapshots are identified by the number of their election block. The state root of the
+        // block must match the snapshot, so that blocks of other chains aren't served from it.
+        let chain_info = self
+            .chain_store
+            .get_chain_info(block_hash, false, Some(txn))
+            .ok()?;
+        let block_number = chain_info.head.block_number();
+
+        let tree = &self.state.accounts.tree;
+        if tree.snapshot_root_hash(txn, block_number).as_ref() != Some(chain_info.head.state_root())
+        {
+            return None;
+        }
+
+        let chunk = tree.get_snapshot_chunk(txn, block_number, start, size)?;
+        let proof = tree.get_snapshot_chunk_proof(txn, block_number, start, size)?;
+
+        Some((chunk, proof))
+    }
+
     /// Reverts the accounts given a block. This only applies to micro blocks and skip blocks, since macro blocks
     /// are final and can't be reverted.
     pub(crate) fn revert_accounts(
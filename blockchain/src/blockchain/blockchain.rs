@@ -2,7 +2,7 @@ use std::sync::Arc;
 use tokio::sync::broadcast::{channel as broadcast, Sender as BroadcastSender};
 
 use nimiq_account::{Account, Accounts, BlockLog};
-use nimiq_block::Block;
+use nimiq_block::{Block, MacroBlock};
 use nimiq_blockchain_interface::{BlockchainError, BlockchainEvent, ChainInfo, ForkEvent};
 use nimiq_database::{Environment, ReadTransaction, WriteTransaction};
 use nimiq_genesis::NetworkInfo;
@@ -65,6 +65,13 @@ pub struct BlockchainConfig {
     /// Maximum number of epochs (other than the current one) that the ChainStore will store fully.
     /// Epochs older than this number will be pruned.
     pub max_epochs_stored: u32,
+    /// Flag indicating if snapshots of the accounts trie should be kept at the latest election
+    /// blocks. The snapshots are maintained copy-on-write, so they only cost the storage of the
+    /// nodes that changed since the oldest kept election block.
+    pub keep_accounts_snapshot: bool,
+    /// Maximum number of election blocks at which snapshots of the accounts trie are kept, if
+    /// snapshots are enabled.
+    pub max_accounts_snapshots: usize,
 }
 
 impl Default for BlockchainConfig {
@@ -72,6 +79,8 @@ impl Default for BlockchainConfig {
         Self {
             keep_history: true,
             max_epochs_stored: Policy::MIN_EPOCHS_STORED,
+            keep_accounts_snapshot: false,
+            max_accounts_snapshots: 2,
         }
     }
 }
@@ -164,7 +173,25 @@ impl Blockchain {
             .map_err(|_| BlockchainError::FailedLoadingMainChain)?;
 
         // Check that chain/accounts state is consistent.
-        let accounts = Accounts::new(env.clone());
+        let accounts = if config.keep_accounts_snapshot {
+            let accounts = Accounts::new_with_snapshots(env.clone(), config.max_accounts_snapshots);
+
+            // Snapshots that were taken before they were disabled are inconsistent.
+            if chain_store.get_accounts_snapshot(None).is_none() {
+                let mut txn = WriteTransaction::new(&env);
+                accounts.tree.clear_snapshots(&mut txn);
+                txn.commit();
+            }
+
+            accounts
+        } else {
+            // Changes to the accounts are no longer tracked, so previously taken snapshots
+            // would become inconsistent.
+            let mut txn = WriteTransaction::new(&env);
+            chain_store.clear_accounts_snapshot(&mut txn);
+            txn.commit();
+            Accounts::new(env.clone())
+        };
 
         if main_chain.head.state_root() != &accounts.get_root(None) {
             log::error!(
@@ -284,10 +311,22 @@ impl Blockchain {
         let main_chain = ChainInfo::new(genesis_block, true);
 
         // Initialize accounts.
-        let accounts = Accounts::new(env.clone());
+        let accounts = if config.keep_accounts_snapshot {
+            Accounts::new_with_snapshots(env.clone(), config.max_accounts_snapshots)
+        } else {
+            Accounts::new(env.clone())
+        };
         let mut txn = WriteTransaction::new(&env);
         accounts.init(&mut txn, genesis_accounts);
 
+        // The genesis block is an election block, so it is the first snapshot point.
+        if config.keep_accounts_snapshot {
+            accounts
+                .tree
+                .take_snapshot(&mut txn, genesis_macro_block.block_number());
+            chain_store.set_accounts_snapshot(&mut txn, &head_hash);
+        }
+
         // Store genesis block.
         chain_store.put_chain_info(&mut txn, &head_hash, &main_chain, true);
         chain_store.set_head(&mut txn, &head_hash);
@@ -326,10 +365,58 @@ impl Blockchain {
         })
     }
 
+    /// Prepares an empty database to load a blockchain from the accounts state at the given
+    /// election block instead of syncing it from the genesis block. The accounts state at the
+    /// election block must already have been written to `txn`, e.g. from a state snapshot.
+    /// Only the genesis block and the election block are stored, so the resulting chain has no
+    /// history before the election block.
+    pub fn init_with_election_block(
+        env: Environment,
+        txn: &mut WriteTransaction,
+        network_id: NetworkId,
+        election_block: MacroBlock,
+    ) -> Result<(), BlockchainError> {
+        if !election_block.is_election_block() {
+            return Err(BlockchainError::BlockIsNotElection);
+        }
+
+        let chain_store = ChainStore::new(env.clone());
+        if chain_store.get_head(Some(txn)).is_some() {
+            return Err(BlockchainError::ChainAlreadyInitialized);
+        }
+
+        let accounts = Accounts::new(env);
+        if election_block.header.state_root != accounts.get_root(Some(txn)) {
+            return Err(BlockchainError::InconsistentState);
+        }
+
+        let genesis_block = NetworkInfo::from_network_id(network_id).genesis_block::<Block>();
+        let genesis_hash = genesis_block.hash();
+        chain_store.put_chain_info(
+            txn,
+            &genesis_hash,
+            &ChainInfo::new(genesis_block, true),
+            true,
+        );
+
+        let head_hash = election_block.hash();
+        if head_hash != genesis_hash {
+            let chain_info = ChainInfo::new(Block::Macro(election_block), true);
+            chain_store.put_chain_info(txn, &head_hash, &chain_info, true);
+        }
+        chain_store.set_head(txn, &head_hash);
+
+        Ok(())
+    }
+
     pub fn get_genesis_parameters(&self) -> (Coin, u64) {
         (self.genesis_supply, self.genesis_timestamp)
     }
 
+    pub fn env(&self) -> &Environment {
+        &self.env
+    }
+
     pub fn read_transaction(&self) -> ReadTransaction {
         ReadTransaction::new(&self.env)
     }
@@ -337,6 +424,11 @@ impl Blockchain {
     pub fn write_transaction(&self) -> WriteTransaction {
         WriteTransaction::new(&self.env)
     }
+
+    /// Returns a write transaction that keeps its changes in memory and can't be committed.
+    pub fn overlay_transaction(&self) -> WriteTransaction {
+        WriteTransaction::overlay(&self.env)
+    }
 }
 
 pub trait TransactionVerificationCache: Send + Sync {
@@ -280,6 +280,10 @@ impl Blockchain {
             return Err(PushError::InvalidBlock(BlockError::InvalidHistoryRoot));
         }
 
+        if macro_block.is_election_block() {
+            this.take_accounts_snapshot(macro_block.block_number(), &block_hash, &mut txn);
+        }
+
         // Give up database transactions and push lock before creating notifications.
         txn.commit();
 
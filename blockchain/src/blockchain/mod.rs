@@ -6,5 +6,6 @@ pub mod history_sync;
 pub mod inherents;
 pub mod push;
 pub mod slots;
+pub mod state_sync;
 pub mod verify;
 pub mod wrappers;
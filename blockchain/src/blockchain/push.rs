@@ -223,6 +223,8 @@ impl Blockchain {
                 // Prune the Chain Store.
                 this.chain_store.prune_epoch(pruned_epoch, &mut txn);
             }
+
+            this.take_accounts_snapshot(block_number, &block_hash, &mut txn);
         }
 
         txn.commit();
@@ -0,0 +1,117 @@
+use parking_lot::{RwLockUpgradableReadGuard, RwLockWriteGuard};
+
+use nimiq_account::AccountsTrie;
+use nimiq_block::{Block, MacroBlock};
+use nimiq_blockchain_interface::{BlockchainError, BlockchainEvent, ChainInfo};
+use nimiq_trie::key_nibbles::KeyNibbles;
+
+use crate::Blockchain;
+
+/// The number of accounts that are copied at once when adopting a synced accounts state.
+const ACCOUNTS_COPY_CHUNK_SIZE: usize = 1000;
+
+/// Implements methods to adopt an accounts state that was downloaded from other peers. This type
+/// of syncing is called state syncing and it is used by full nodes that don't keep the history.
+/// It works by having the node get the latest election block (verified through the zk proof and
+/// the macro chain) and download the accounts state at that block in chunks, each of them proven
+/// against the state root of the election block. When the state is complete, it replaces the
+/// state of the blockchain and the blocks produced in the meantime are pushed normally.
+impl Blockchain {
+    /// Replaces the state of the blockchain with the accounts stored in `accounts`, which must be
+    /// the complete accounts state at the given election block. The election block becomes the
+    /// new head of the chain. Since we don't replay the blocks before it, the resulting chain has
+    /// no history before the election block.
+    pub fn adopt_state_sync(
+        this: RwLockUpgradableReadGuard<Self>,
+        election_block: MacroBlock,
+        accounts: &AccountsTrie,
+    ) -> Result<(), BlockchainError> {
+        if !election_block.is_election_block() {
+            return Err(BlockchainError::BlockIsNotElection);
+        }
+
+        // Create a new database write transaction.
+        let mut txn = this.write_transaction();
+
+        // Replace our accounts with the synced ones.
+        let tree = &this.state.accounts.tree;
+        tree.clear(&mut txn);
+
+        let mut start = KeyNibbles::root();
+        loop {
+            // Chunks start at (and include) the given key, so consecutive chunks overlap by
+            // one account. Putting the same account twice is harmless.
+            let chunk = accounts.get_chunk_with_keys(&txn, &start, ACCOUNTS_COPY_CHUNK_SIZE);
+            let is_last_chunk = chunk.len() < ACCOUNTS_COPY_CHUNK_SIZE;
+
+            for (key, account) in chunk {
+                tree.put(&mut txn, &key, account);
+                start = key;
+            }
+
+            if is_last_chunk {
+                break;
+            }
+        }
+        tree.update_root(&mut txn);
+
+        // Check the state_root hash against the one in the block.
+        let state_root = this.state.accounts.get_root(Some(&txn));
+        if election_block.header.state_root != state_root {
+            warn!(
+                block = %election_block,
+                reason = "header accounts hash doesn't match synced accounts hash",
+                wanted_state_root = %election_block.header.state_root,
+                %state_root,
+                "Rejecting synced state",
+            );
+            txn.abort();
+            return Err(BlockchainError::InconsistentState);
+        }
+
+        // Store the election block as the new head of the chain.
+        let block_hash = election_block.hash();
+        let chain_info = ChainInfo::new(Block::Macro(election_block.clone()), true);
+
+        this.chain_store
+            .put_chain_info(&mut txn, &block_hash, &chain_info, true);
+        this.chain_store.set_head(&mut txn, &block_hash);
+
+        // Receipts of our previous chain can't be used to revert the synced state.
+        this.chain_store.clear_receipts(&mut txn);
+
+        this.take_accounts_snapshot(election_block.block_number(), &block_hash, &mut txn);
+
+        txn.commit();
+
+        // Update the blockchain state.
+        let mut this = RwLockUpgradableReadGuard::upgrade(this);
+        this.state.main_chain = chain_info.clone();
+        this.state.head_hash = block_hash.clone();
+        this.state.macro_info = chain_info;
+        this.state.macro_head_hash = block_hash.clone();
+        this.state.election_head_hash = block_hash.clone();
+        this.state.current_slots = election_block.get_validators();
+        // The previous election block is not part of the synced chain.
+        this.state.previous_slots = None;
+        this.state.election_head = election_block;
+
+        let this = RwLockWriteGuard::downgrade(this);
+
+        info!(
+            block_number = this.state.election_head.block_number(),
+            %block_hash,
+            "Adopted synced accounts state",
+        );
+
+        // If there are no listeners we do not log errors
+        _ = this
+            .notifier
+            .send(BlockchainEvent::HistoryAdopted(block_hash.clone()));
+        _ = this
+            .notifier
+            .send(BlockchainEvent::EpochFinalized(block_hash));
+
+        Ok(())
+    }
+}
@@ -116,14 +116,19 @@ impl Blockchain {
     }
 
     pub fn get_account(&self, address: &Address) -> Option<Account> {
+        self.state
+            .accounts
+            .get(&Self::get_account_key(address), None)
+    }
+
+    /// Returns the key of the account with the given address in the Accounts Tree.
+    pub fn get_account_key(address: &Address) -> KeyNibbles {
         // TODO: Find a better place for this differentiation, it should be in a more general location.
-        let key = if *address == Policy::STAKING_CONTRACT_ADDRESS {
+        if *address == Policy::STAKING_CONTRACT_ADDRESS {
             StakingContract::get_key_staking_contract()
         } else {
             KeyNibbles::from(address)
-        };
-
-        self.state.accounts.get(&key, None)
+        }
     }
 
     /// Checks if we have seen some transaction with this hash inside the a validity window.
@@ -28,6 +28,7 @@ impl ChainStore {
     const RECEIPT_DB_NAME: &'static str = "Receipts";
 
     const HEAD_KEY: &'static str = "head";
+    const ACCOUNTS_SNAPSHOT_KEY: &'static str = "accounts_snapshot";
 
     pub fn new(env: Environment) -> Self {
         let chain_db = env.open_database(Self::CHAIN_DB_NAME.to_string());
@@ -60,6 +61,23 @@ impl ChainStore {
         txn.put(&self.chain_db, ChainStore::HEAD_KEY, hash);
     }
 
+    /// Returns the hash of the election block at which the snapshot of the accounts was taken.
+    pub fn get_accounts_snapshot(&self, txn_option: Option<&Transaction>) -> Option<Blake2bHash> {
+        match txn_option {
+            Some(txn) => txn.get(&self.chain_db, ChainStore::ACCOUNTS_SNAPSHOT_KEY),
+            None => ReadTransaction::new(&self.env)
+                .get(&self.chain_db, ChainStore::ACCOUNTS_SNAPSHOT_KEY),
+        }
+    }
+
+    pub fn set_accounts_snapshot(&self, txn: &mut WriteTransaction, hash: &Blake2bHash) {
+        txn.put(&self.chain_db, ChainStore::ACCOUNTS_SNAPSHOT_KEY, hash);
+    }
+
+    pub fn clear_accounts_snapshot(&self, txn: &mut WriteTransaction) {
+        txn.remove(&self.chain_db, ChainStore::ACCOUNTS_SNAPSHOT_KEY);
+    }
+
     pub fn get_chain_info(
         &self,
         hash: &Blake2bHash,
@@ -23,6 +23,15 @@ use crate::history::ordered_hash::OrderedHash;
 use crate::history::{ExtendedTransaction, HistoryTreeChunk, HistoryTreeProof};
 use crate::ExtTxData;
 
+/// The order in which the history of an address is traversed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryOrder {
+    /// From the least recent to the most recent transaction.
+    Ascending,
+    /// From the most recent to the least recent transaction.
+    Descending,
+}
+
 /// A struct that contains databases to store history trees (which are Merkle Mountain Ranges
 /// constructed from the list of extended transactions in an epoch) and extended transactions (which
 /// are representations of transactions).
@@ -624,6 +633,30 @@ impl HistoryStore {
         address: &Address,
         max: u16,
         txn_option: Option<&Transaction>,
+    ) -> Vec<Blake2bHash> {
+        self.get_tx_hashes_by_address_paginated(
+            address,
+            max,
+            None,
+            HistoryOrder::Descending,
+            txn_option,
+        )
+    }
+
+    /// Returns a vector containing the transaction (and reward inherents) hashes corresponding to
+    /// the given address, up to the maximum number given. The hashes are returned in the given
+    /// order (`Descending` starts with the most recent transaction).
+    /// If `start_at` is given, only the hashes that come strictly after that transaction hash
+    /// (in the requested order) are returned. This allows to resume walking the history of an
+    /// address by passing the last hash of the previous page. If `start_at` is not part of the
+    /// history of the address, an empty vector is returned.
+    pub fn get_tx_hashes_by_address_paginated(
+        &self,
+        address: &Address,
+        max: u16,
+        start_at: Option<&Blake2bHash>,
+        order: HistoryOrder,
+        txn_option: Option<&Transaction>,
     ) -> Vec<Blake2bHash> {
         if max == 0 {
             return vec![];
@@ -647,16 +680,34 @@ impl HistoryStore {
             return tx_hashes;
         }
 
-        // Then go to the last transaction hash at the given address and add it to the transaction
-        // hashes list.
-        tx_hashes.push(cursor.last_duplicate::<OrderedHash>().expect("This shouldn't panic since we already verified before that there is at least one transactions at this address!").hash);
+        // Then go to the first transaction hash in the requested order.
+        let mut current = match order {
+            HistoryOrder::Ascending => cursor.first_duplicate::<OrderedHash>(),
+            HistoryOrder::Descending => cursor.last_duplicate::<OrderedHash>(),
+        };
 
-        while tx_hashes.len() < max as usize {
-            // Get previous transaction hash.
-            match cursor.prev_duplicate::<Address, OrderedHash>() {
-                Some((_, v)) => tx_hashes.push(v.hash),
-                None => break,
-            };
+        // If a starting point was given, skip all transaction hashes up to and including it.
+        // The same hash can be stored twice in a row (when the address is both the sender and
+        // the recipient of the transaction), so we skip all consecutive occurrences.
+        if let Some(start_hash) = start_at {
+            let mut found = false;
+            while let Some(ordered_hash) = current {
+                if ordered_hash.hash == *start_hash {
+                    found = true;
+                } else if found {
+                    current = Some(ordered_hash);
+                    break;
+                }
+                current = Self::step_duplicate(&mut cursor, &order);
+            }
+        }
+
+        while let Some(ordered_hash) = current {
+            if tx_hashes.len() >= max as usize {
+                break;
+            }
+            tx_hashes.push(ordered_hash.hash);
+            current = Self::step_duplicate(&mut cursor, &order);
         }
 
         tx_hashes
@@ -683,15 +734,72 @@ impl HistoryStore {
             positions.append(&mut indices)
         }
 
-        self.prove_with_position(epoch_number, positions, txn_option)
+        self.prove_with_position(epoch_number, positions, None, txn_option)
+    }
+
+    /// Returns a proof for the transactions with the given hashes, as they were included in the
+    /// History Tree at the given verifier block. This means that the proof can be verified against
+    /// the history root of the verifier block. Transactions that are not part of the epoch of the
+    /// verifier block or that happened after it are ignored. The proof also includes the extended
+    /// transactions.
+    pub fn prove_at_block(
+        &self,
+        verifier_block_number: u32,
+        hashes: Vec<&Blake2bHash>,
+        txn_option: Option<&Transaction>,
+    ) -> Option<HistoryTreeProof> {
+        let read_txn: ReadTransaction;
+        let txn = match txn_option {
+            Some(txn) => txn,
+            None => {
+                read_txn = ReadTransaction::new(&self.env);
+                &read_txn
+            }
+        };
+
+        let epoch_number = Policy::epoch_at(verifier_block_number);
+
+        // Calculate number of leaves in the verifier's history tree.
+        let leaf_count = self.length_at(verifier_block_number, Some(txn)) as usize;
+
+        // Get the leaf indexes of the transactions that are part of the verifier's history tree.
+        let mut positions = vec![];
+
+        for hash in hashes {
+            for leaf in self.get_leaves_by_tx_hash(hash, Some(txn)) {
+                let ext_tx = match self.get_extended_tx(&leaf.hash, Some(txn)) {
+                    Some(ext_tx) => ext_tx,
+                    None => continue,
+                };
+
+                if Policy::epoch_at(ext_tx.block_number) == epoch_number
+                    && ext_tx.block_number <= verifier_block_number
+                    && (leaf.index as usize) < leaf_count
+                {
+                    positions.push(leaf.index as usize);
+                }
+            }
+        }
+
+        positions.sort_unstable();
+        positions.dedup();
+
+        self.prove_with_position(
+            epoch_number,
+            positions,
+            Some(leaf_number_to_index(leaf_count)),
+            Some(txn),
+        )
     }
 
     /// Returns a proof for all the extended transactions at the given positions (leaf indexes). The
-    /// proof also includes the extended transactions.
+    /// proof also includes the extended transactions. If `verifier_state` is given, the proof is
+    /// made for the history tree as it was when it had that many nodes.
     fn prove_with_position(
         &self,
         epoch_number: u32,
         positions: Vec<usize>,
+        verifier_state: Option<usize>,
         txn_option: Option<&Transaction>,
     ) -> Option<HistoryTreeProof> {
         let read_txn: ReadTransaction;
@@ -711,7 +819,7 @@ impl HistoryStore {
         ));
 
         // Create Merkle proof.
-        let proof = tree.prove(&positions, None).ok()?;
+        let proof = tree.prove(&positions, verifier_state).ok()?;
 
         // Get each extended transaction from the tree.
         let mut ext_txs = vec![];
@@ -1028,6 +1136,15 @@ impl HistoryStore {
             Some(v) => v.index,
         }
     }
+
+    /// Moves the cursor to the next duplicate of the current key in the given order.
+    fn step_duplicate<C: ReadCursor>(cursor: &mut C, order: &HistoryOrder) -> Option<OrderedHash> {
+        match order {
+            HistoryOrder::Ascending => cursor.next_duplicate::<Address, OrderedHash>(),
+            HistoryOrder::Descending => cursor.prev_duplicate::<Address, OrderedHash>(),
+        }
+        .map(|(_, v)| v)
+    }
 }
 
 #[cfg(test)]
@@ -1455,6 +1572,86 @@ mod tests {
         assert_eq!(query_4.len(), 0);
     }
 
+    #[test]
+    fn get_tx_hashes_by_address_paginated_works() {
+        // Initialize History Store.
+        let env = VolatileEnvironment::new(10).unwrap();
+        let history_store = HistoryStore::new(env.clone());
+
+        // Create extended transactions.
+        let ext_txs = gen_ext_txs();
+
+        // Add extended transactions to History Store.
+        let mut txn = WriteTransaction::new(&env);
+        history_store.add_to_history(&mut txn, 0, &ext_txs[..3]);
+        history_store.add_to_history(&mut txn, 1, &ext_txs[3..]);
+
+        let address =
+            Address::from_user_friendly_address("NQ09 VF5Y 1PKV MRM4 5LE1 55KV P6R2 GXYJ XYQF")
+                .unwrap();
+
+        // Walk the history in ascending order.
+        let query_1 = history_store.get_tx_hashes_by_address_paginated(
+            &address,
+            2,
+            None,
+            HistoryOrder::Ascending,
+            Some(&txn),
+        );
+
+        assert_eq!(query_1.len(), 2);
+        assert_eq!(query_1[0], ext_txs[0].tx_hash());
+        assert_eq!(query_1[1], ext_txs[1].tx_hash());
+
+        let query_2 = history_store.get_tx_hashes_by_address_paginated(
+            &address,
+            2,
+            Some(&query_1[1]),
+            HistoryOrder::Ascending,
+            Some(&txn),
+        );
+
+        assert_eq!(query_2.len(), 2);
+        assert_eq!(query_2[0], ext_txs[3].tx_hash());
+        assert_eq!(query_2[1], ext_txs[5].tx_hash());
+
+        let query_3 = history_store.get_tx_hashes_by_address_paginated(
+            &address,
+            2,
+            Some(&query_2[1]),
+            HistoryOrder::Ascending,
+            Some(&txn),
+        );
+
+        assert_eq!(query_3.len(), 1);
+        assert_eq!(query_3[0], ext_txs[6].tx_hash());
+
+        // Walk the history in descending order.
+        let query_4 = history_store.get_tx_hashes_by_address_paginated(
+            &address,
+            3,
+            Some(&ext_txs[5].tx_hash()),
+            HistoryOrder::Descending,
+            Some(&txn),
+        );
+
+        assert_eq!(query_4.len(), 3);
+        assert_eq!(query_4[0], ext_txs[3].tx_hash());
+        assert_eq!(query_4[1], ext_txs[1].tx_hash());
+        assert_eq!(query_4[2], ext_txs[0].tx_hash());
+
+        // An unknown starting point yields no results.
+        let query_5 = history_store.get_tx_hashes_by_address_paginated(
+            &address,
+            99,
+            Some(&ext_txs[7].tx_hash()),
+            HistoryOrder::Descending,
+            Some(&txn),
+        );
+
+        assert_eq!(query_5.len(), 0);
+    }
+
     #[test]
     fn prove_works() {
         // Initialize History Store.
@@ -1517,6 +1714,59 @@ mod tests {
         assert!(proof.verify(root).unwrap());
     }
 
+    #[test]
+    fn prove_at_block_works() {
+        // Initialize History Store.
+        let env = VolatileEnvironment::new(10).unwrap();
+        let history_store = HistoryStore::new(env.clone());
+
+        // Create extended transactions.
+        let ext_txs = gen_ext_txs();
+
+        // Add extended transactions to History Store.
+        let mut txn = WriteTransaction::new(&env);
+        history_store.add_to_history(&mut txn, 0, &ext_txs[..3]);
+        history_store.add_to_history(&mut txn, 1, &ext_txs[3..]);
+
+        // Prove at block 1, the transactions of block 2 and of epoch 0 must be ignored.
+        let root = HistoryStore::root_from_ext_txs(&ext_txs[3..5]).unwrap();
+
+        let proof = history_store
+            .prove_at_block(
+                1,
+                vec![
+                    &ext_txs[0].tx_hash(),
+                    &ext_txs[3].tx_hash(),
+                    &ext_txs[5].tx_hash(),
+                ],
+                Some(&txn),
+            )
+            .unwrap();
+
+        assert_eq!(proof.positions, vec![0]);
+        assert_eq!(proof.history.len(), 1);
+        assert_eq!(proof.history[0].tx_hash(), ext_txs[3].tx_hash());
+
+        assert!(proof.verify(root).unwrap());
+
+        // Prove at block 2, which is the last block of the history tree.
+        let root = history_store.get_history_tree_root(1, Some(&txn)).unwrap();
+
+        let proof = history_store
+            .prove_at_block(
+                2,
+                vec![&ext_txs[6].tx_hash(), &ext_txs[3].tx_hash()],
+                Some(&txn),
+            )
+            .unwrap();
+
+        assert_eq!(proof.positions, vec![0, 3]);
+        assert_eq!(proof.history[0].tx_hash(), ext_txs[3].tx_hash());
+        assert_eq!(proof.history[1].tx_hash(), ext_txs[6].tx_hash());
+
+        assert!(proof.verify(root).unwrap());
+    }
+
     #[test]
     fn prove_empty_tree_works() {
         // Initialize History Store.
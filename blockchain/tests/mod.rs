@@ -6,7 +6,7 @@ use tokio_stream::StreamExt;
 use nimiq_block::Block;
 use nimiq_block_production::BlockProducer;
 use nimiq_blockchain::{Blockchain, BlockchainConfig};
-use nimiq_blockchain_interface::{AbstractBlockchain, PushResult};
+use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainError, PushResult};
 use nimiq_database::volatile::VolatileEnvironment;
 use nimiq_genesis::NetworkId;
 use nimiq_primitives::policy::Policy;
@@ -202,6 +202,52 @@ fn micro_block_works_after_macro_block() {
     );
 }
 
+#[test]
+fn it_can_reconstruct_accounts_at_block() {
+    let temp_producer = TemporaryBlockProducer::new();
+
+    // Apply an entire batch followed by a couple of micro blocks and a skip block.
+    for _ in 0..Policy::blocks_per_batch() {
+        temp_producer.next_block(vec![], false);
+    }
+    temp_producer.next_block(vec![], false);
+    temp_producer.next_block(vec![], false);
+    temp_producer.next_block(vec![], true);
+
+    let blockchain = temp_producer.blockchain.read();
+    let head_number = blockchain.block_number();
+    let macro_number = Policy::blocks_per_batch();
+    assert_eq!(head_number, macro_number + 3);
+
+    // The reconstructed state must match the state root of each block since the last macro block.
+    for block_number in macro_number..=head_number {
+        let state_root = blockchain
+            .with_accounts_at(block_number, |accounts, txn| accounts.get_root(Some(txn)))
+            .unwrap();
+        let block = blockchain.get_block_at(block_number, false, None).unwrap();
+        assert_eq!(&state_root, block.state_root());
+    }
+
+    // The current state is left untouched.
+    assert_eq!(
+        &blockchain.state().accounts.get_root(None),
+        blockchain.head().state_root()
+    );
+
+    // Blocks before the last macro block and future blocks are rejected.
+    assert_eq!(
+        blockchain.with_accounts_at(macro_number - 1, |_, _| ()),
+        Err(BlockchainError::AccountsStateNotAvailable(
+            macro_number - 1,
+            macro_number
+        ))
+    );
+    assert_eq!(
+        blockchain.with_accounts_at(head_number + 1, |_, _| ()),
+        Err(BlockchainError::BlockNotFound)
+    );
+}
+
 #[test]
 fn it_can_rebranch_forks() {
     let temp_producer1 = TemporaryBlockProducer::new();
@@ -45,7 +45,7 @@ async fn main_inner() -> Result<(), Error> {
     initialize_panic_reporting();
 
     // Initialize signal handler
-    initialize_signal_handler();
+    let shutdown_handle = initialize_signal_handler();
 
     // Early return in case of a proving process.
     if command_line.prove {
@@ -68,11 +68,13 @@ async fn main_inner() -> Result<(), Error> {
     let rpc_config = config.rpc_server.clone();
     let metrics_config = config.metrics_server.clone();
     let metrics_enabled = metrics_config.is_some();
+    let min_peers = config.consensus.min_peers;
 
     // Create client from config.
     log::info!("Initializing client");
     let mut client: Client = Client::from_config(config).await?;
     log::info!("Client initialized");
+    shutdown_handle.set_network(client.network());
 
     // Initialize RPC server
     if let Some(rpc_config) = rpc_config {
@@ -136,6 +138,8 @@ async fn main_inner() -> Result<(), Error> {
             mempool,
             client.consensus_proxy(),
             client.network(),
+            client.validator_proxy(),
+            min_peers,
             &nimiq_task_metric,
         )
     }
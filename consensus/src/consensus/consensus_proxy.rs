@@ -1,16 +1,27 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use tokio::sync::broadcast::Sender as BroadcastSender;
 use tokio_stream::wrappers::BroadcastStream;
 
+use nimiq_account::Account;
+use nimiq_block::Block;
+use nimiq_blockchain::{Blockchain, ExtendedTransaction};
+use nimiq_blockchain_interface::AbstractBlockchain;
 use nimiq_blockchain_proxy::BlockchainProxy;
+use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
 use nimiq_mempool::mempool::{ControlTransactionTopic, TransactionTopic};
 use nimiq_network_interface::network::Network;
-use nimiq_primitives::account::AccountType;
+use nimiq_primitives::{account::AccountType, policy::Policy};
 use nimiq_transaction::Transaction;
 
-use crate::ConsensusEvent;
+use crate::messages::{
+    RequestTransactionReceiptsByAddress, RequestTransactionsProof, RequestTrieProof,
+    TransactionReceipt, MAX_TRANSACTIONS_PROOF_HASHES, MAX_TRIE_PROOF_KEYS,
+};
+use crate::{ConsensusEvent, ProofError};
 
 pub struct ConsensusProxy<N: Network> {
     pub blockchain: BlockchainProxy,
@@ -45,4 +56,217 @@ impl<N: Network> ConsensusProxy<N> {
     pub fn subscribe_events(&self) -> BroadcastStream<ConsensusEvent> {
         BroadcastStream::new(self.events.subscribe())
     }
+
+    /// Requests the accounts with the given addresses from our peers. The accounts are verified
+    /// against the state root of the block at which they were proven, which must be known to our
+    /// blockchain. Returns that block together with the accounts, in the same order as the given
+    /// addresses. Accounts that don't exist are `None`.
+    pub async fn request_accounts_by_addresses(
+        &self,
+        addresses: Vec<Address>,
+    ) -> Result<(Block, Vec<Option<Account>>), ProofError> {
+        if addresses.len() > MAX_TRIE_PROOF_KEYS as usize {
+            return Err(ProofError::TooManyItems);
+        }
+
+        let keys: Vec<_> = addresses.iter().map(Blockchain::get_account_key).collect();
+
+        for peer_id in self.proof_peers() {
+            let request = RequestTrieProof { keys: keys.clone() };
+            let response = match self.network.request(request, peer_id).await {
+                Ok(response) => response,
+                Err(error) => {
+                    debug!(%peer_id, ?error, "Failed trie proof request");
+                    continue;
+                }
+            };
+
+            let proof = match response.proof {
+                Some(proof) => proof,
+                None => continue,
+            };
+
+            // We can only verify the proof if we know the block it was made at.
+            let block = match self
+                .blockchain
+                .read()
+                .get_block(&response.block_hash, false)
+            {
+                Ok(block) => block,
+                Err(_) => {
+                    trace!(
+                        %peer_id,
+                        block_hash = %response.block_hash,
+                        "Received a trie proof at an unknown block",
+                    );
+                    continue;
+                }
+            };
+
+            match proof.verify_values(&keys.iter().collect::<Vec<_>>(), block.state_root()) {
+                Ok(accounts) => return Ok((block, accounts)),
+                Err(error) => {
+                    debug!(%peer_id, ?error, "Received an invalid trie proof");
+                }
+            }
+        }
+
+        Err(ProofError::NoValidProof)
+    }
+
+    /// Requests the most recent transactions of the given address from our peers, most recent
+    /// first. Each transaction is verified against the history root of a block known to our
+    /// blockchain: the head for transactions of the current epoch and the election block of the
+    /// epoch otherwise. Transactions in epochs for which we don't know the election block can't be
+    /// verified and are left out.
+    pub async fn request_transactions_by_address(
+        &self,
+        address: Address,
+        max: u16,
+    ) -> Result<Vec<ExtendedTransaction>, ProofError> {
+        let receipts = self.request_transaction_receipts(address, max).await?;
+
+        // Group the receipts by the block that they can be verified against.
+        let mut receipts_by_verifier: BTreeMap<(u32, Blake2bHash), Vec<Blake2bHash>> =
+            BTreeMap::new();
+        for receipt in receipts.iter() {
+            match self.history_verifier_at(receipt.block_number) {
+                Some(verifier) => receipts_by_verifier
+                    .entry(verifier)
+                    .or_default()
+                    .push(receipt.hash.clone()),
+                None => {
+                    trace!(
+                        hash = %receipt.hash,
+                        block_number = receipt.block_number,
+                        "Can't verify transaction without a known block of its epoch",
+                    );
+                }
+            }
+        }
+
+        let mut transactions = HashMap::new();
+        for ((block_number, history_root), hashes) in receipts_by_verifier {
+            for hashes in hashes.chunks(MAX_TRANSACTIONS_PROOF_HASHES as usize) {
+                for ext_tx in self
+                    .request_transactions_proof(hashes.to_vec(), block_number, &history_root)
+                    .await?
+                {
+                    transactions.insert(ext_tx.tx_hash(), ext_tx);
+                }
+            }
+        }
+
+        // Return the transactions in the same order as their receipts.
+        Ok(receipts
+            .iter()
+            .filter_map(|receipt| transactions.remove(&receipt.hash))
+            .collect())
+    }
+
+    /// Requests the transaction receipts of the given address from the first peer that keeps a
+    /// transaction index.
+    async fn request_transaction_receipts(
+        &self,
+        address: Address,
+        max: u16,
+    ) -> Result<Vec<TransactionReceipt>, ProofError> {
+        for peer_id in self.proof_peers() {
+            let request = RequestTransactionReceiptsByAddress {
+                address: address.clone(),
+                max: Some(max),
+            };
+            match self.network.request(request, peer_id).await {
+                Ok(response) => {
+                    if let Some(receipts) = response.receipts {
+                        return Ok(receipts);
+                    }
+                }
+                Err(error) => {
+                    debug!(%peer_id, ?error, "Failed transaction receipts request");
+                }
+            }
+        }
+
+        Err(ProofError::NoValidProof)
+    }
+
+    /// Requests a proof for the transactions with the given hashes at the given block and verifies
+    /// it against the history root of that block. All of the proven transactions must have been
+    /// requested.
+    async fn request_transactions_proof(
+        &self,
+        hashes: Vec<Blake2bHash>,
+        block_number: u32,
+        history_root: &Blake2bHash,
+    ) -> Result<Vec<ExtendedTransaction>, ProofError> {
+        let requested: HashSet<_> = hashes.iter().cloned().collect();
+
+        for peer_id in self.proof_peers() {
+            let request = RequestTransactionsProof {
+                hashes: hashes.clone(),
+                block_number,
+            };
+            let proof = match self.network.request(request, peer_id).await {
+                Ok(response) => match response.proof {
+                    Some(proof) => proof,
+                    None => continue,
+                },
+                Err(error) => {
+                    debug!(%peer_id, ?error, "Failed transactions proof request");
+                    continue;
+                }
+            };
+
+            let is_requested = proof
+                .history
+                .iter()
+                .all(|ext_tx| requested.contains(&ext_tx.tx_hash()));
+            if is_requested && proof.verify(history_root.clone()) == Some(true) {
+                return Ok(proof.history);
+            }
+
+            debug!(%peer_id, block_number, "Received an invalid transactions proof");
+        }
+
+        Err(ProofError::NoValidProof)
+    }
+
+    /// Returns the number and history root of a block known to our blockchain, against which the
+    /// transactions of the given block can be verified.
+    fn history_verifier_at(&self, block_number: u32) -> Option<(u32, Blake2bHash)> {
+        let epoch_number = Policy::epoch_at(block_number);
+
+        let blockchain = self.blockchain.read();
+        let head = blockchain.head();
+        if Policy::epoch_at(head.block_number()) == epoch_number
+            && block_number <= head.block_number()
+        {
+            return Some((head.block_number(), head.history_root().clone()));
+        }
+        drop(blockchain);
+
+        match &self.blockchain {
+            #[cfg(not(target_family = "wasm"))]
+            BlockchainProxy::Full(blockchain) => blockchain
+                .read()
+                .get_block_at(Policy::election_block_of(epoch_number), false)
+                .ok()
+                .map(|block| (block.block_number(), block.history_root().clone())),
+            BlockchainProxy::Light(blockchain) => blockchain
+                .read()
+                .chain_store
+                .get_election(epoch_number)
+                .map(|header| (header.block_number, header.history_root.clone())),
+        }
+    }
+
+    /// Returns the peers that we can request proofs from.
+    fn proof_peers(&self) -> Vec<N::PeerId> {
+        self.network
+            .get_peers()
+            .into_iter()
+            .filter(|peer_id| self.network.peer_provides_required_services(*peer_id))
+            .collect()
+    }
 }
@@ -18,8 +18,9 @@ use nimiq_zkp_component::zkp_component::ZKPComponentProxy;
 
 use crate::consensus::head_requests::{HeadRequests, HeadRequestsResult};
 use crate::messages::{
-    RequestBatchSet, RequestBlock, RequestHead, RequestHistoryChunk, RequestMacroChain,
-    RequestMissingBlocks,
+    RequestAccountsChunk, RequestBatchSet, RequestBlock, RequestHead, RequestHistoryChunk,
+    RequestMacroChain, RequestMissingBlocks, RequestTransactionReceiptsByAddress,
+    RequestTransactionsProof, RequestTrieProof,
 };
 use crate::sync::{syncer::LiveSyncPushEvent, syncer_proxy::SyncerProxy};
 
@@ -141,6 +142,18 @@ impl<N: Network> Consensus<N> {
 
                 let stream = network.receive_requests::<RequestHistoryChunk>();
                 tokio::spawn(request_handler(network, stream, blockchain));
+
+                let stream = network.receive_requests::<RequestAccountsChunk>();
+                tokio::spawn(request_handler(network, stream, blockchain));
+
+                let stream = network.receive_requests::<RequestTrieProof>();
+                tokio::spawn(request_handler(network, stream, blockchain));
+
+                let stream = network.receive_requests::<RequestTransactionReceiptsByAddress>();
+                tokio::spawn(request_handler(network, stream, blockchain));
+
+                let stream = network.receive_requests::<RequestTransactionsProof>();
+                tokio::spawn(request_handler(network, stream, blockchain));
             }
             BlockchainProxy::Light(_) => {}
         }
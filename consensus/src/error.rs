@@ -15,3 +15,11 @@ pub enum SyncError {
     #[error("No valid sync target found")]
     NoValidSyncTarget,
 }
+
+#[derive(Debug, Error)]
+pub enum ProofError {
+    #[error("Too many items requested")]
+    TooManyItems,
+    #[error("No peer provided a valid proof")]
+    NoValidProof,
+}
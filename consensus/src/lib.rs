@@ -3,7 +3,7 @@ extern crate log;
 
 pub use consensus::consensus_proxy::ConsensusProxy;
 pub use consensus::{Consensus, ConsensusEvent};
-pub use error::Error;
+pub use error::{Error, ProofError};
 
 pub mod consensus;
 pub mod error;
@@ -266,3 +266,121 @@ impl Handle<Blake2bHash, BlockchainProxy> for RequestHead {
         blockchain.read().head_hash()
     }
 }
+
+impl Handle<AccountsChunk, Arc<RwLock<Blockchain>>> for RequestAccountsChunk {
+    fn handle(&self, blockchain: &Arc<RwLock<Blockchain>>) -> AccountsChunk {
+        // An empty chunk can't be proven.
+        if self.limit == 0 {
+            return AccountsChunk { chunk: None };
+        }
+
+        let blockchain = blockchain.read();
+        let txn = blockchain.read_transaction();
+        let size = self.limit.min(MAX_ACCOUNTS_CHUNK_SIZE) as usize;
+
+        // If the requested block is our head, we serve the chunk from our current accounts trie.
+        // Otherwise, we can only serve it from an accounts snapshot, which are taken at our latest
+        // election blocks. If there are no accounts at or after the start key, there is no chunk.
+        let chunk = if blockchain.head_hash() == self.block_hash {
+            blockchain
+                .state()
+                .accounts
+                .tree
+                .get_chunk_proof(&txn, &self.start_key, size)
+        } else {
+            blockchain
+                .get_accounts_snapshot_chunk(&self.block_hash, &self.start_key, size, Some(&txn))
+                .map(|(_, proof)| proof)
+        };
+
+        AccountsChunk { chunk }
+    }
+}
+
+impl Handle<ResponseTrieProof, Arc<RwLock<Blockchain>>> for RequestTrieProof {
+    fn handle(&self, blockchain: &Arc<RwLock<Blockchain>>) -> ResponseTrieProof {
+        let blockchain = blockchain.read();
+        let block_hash = blockchain.head_hash();
+
+        // We only prove a limited number of keys at once.
+        if self.keys.len() > MAX_TRIE_PROOF_KEYS as usize {
+            return ResponseTrieProof {
+                block_hash,
+                proof: None,
+            };
+        }
+
+        let txn = blockchain.read_transaction();
+        let proof = blockchain
+            .state()
+            .accounts
+            .tree
+            .get_proof_with_exclusion(&txn, self.keys.iter().collect());
+
+        ResponseTrieProof {
+            block_hash,
+            proof: Some(proof),
+        }
+    }
+}
+
+impl Handle<ResponseTransactionReceipts, Arc<RwLock<Blockchain>>>
+    for RequestTransactionReceiptsByAddress
+{
+    fn handle(&self, blockchain: &Arc<RwLock<Blockchain>>) -> ResponseTransactionReceipts {
+        let blockchain = blockchain.read();
+
+        // Only nodes that keep the history have a complete transaction index.
+        if !blockchain.config.keep_history {
+            return ResponseTransactionReceipts { receipts: None };
+        }
+
+        let max = self
+            .max
+            .unwrap_or(MAX_TRANSACTION_RECEIPTS)
+            .min(MAX_TRANSACTION_RECEIPTS);
+        let txn = blockchain.read_transaction();
+
+        let receipts = blockchain
+            .history_store
+            .get_tx_hashes_by_address(&self.address, max, Some(&txn))
+            .into_iter()
+            .filter_map(|hash| {
+                // A hash might belong to several extended transactions, we use the most recent one.
+                let block_number = blockchain
+                    .history_store
+                    .get_ext_tx_by_hash(&hash, Some(&txn))
+                    .iter()
+                    .map(|ext_tx| ext_tx.block_number)
+                    .max()?;
+                Some(TransactionReceipt { hash, block_number })
+            })
+            .collect();
+
+        ResponseTransactionReceipts {
+            receipts: Some(receipts),
+        }
+    }
+}
+
+impl Handle<ResponseTransactionsProof, Arc<RwLock<Blockchain>>> for RequestTransactionsProof {
+    fn handle(&self, blockchain: &Arc<RwLock<Blockchain>>) -> ResponseTransactionsProof {
+        let blockchain = blockchain.read();
+
+        // We can't prove transactions at blocks that we don't have yet, nor a large number of
+        // transactions at once.
+        if self.block_number > blockchain.block_number()
+            || self.hashes.len() > MAX_TRANSACTIONS_PROOF_HASHES as usize
+        {
+            return ResponseTransactionsProof { proof: None };
+        }
+
+        let proof = blockchain.history_store.prove_at_block(
+            self.block_number,
+            self.hashes.iter().collect(),
+            None,
+        );
+
+        ResponseTransactionsProof { proof }
+    }
+}
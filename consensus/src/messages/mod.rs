@@ -1,10 +1,13 @@
 use std::fmt::{Debug, Formatter};
 
 use beserial::{Deserialize, Serialize};
+use nimiq_account::Account;
 use nimiq_block::{Block, MacroBlock};
-use nimiq_blockchain::HistoryTreeChunk;
+use nimiq_blockchain::{HistoryTreeChunk, HistoryTreeProof};
 use nimiq_hash::Blake2bHash;
-use nimiq_network_interface::request::{RequestCommon, RequestMarker};
+use nimiq_keys::Address;
+use nimiq_network_interface::request::{request_type_name, RequestCommon, RequestMarker};
+use nimiq_trie::{key_nibbles::KeyNibbles, trie_proof::TrieProof};
 
 mod handlers;
 
@@ -28,6 +31,40 @@ pub const MAX_REQUEST_RESPONSE_BLOCK: u32 = 1000;
 pub const MAX_REQUEST_RESPONSE_MISSING_BLOCKS: u32 = 1000;
 /// The max number of RequestHead requests per peer.
 pub const MAX_REQUEST_RESPONSE_HEAD: u32 = 1000;
+/// The max number of AccountsChunk requests per peer.
+pub const MAX_REQUEST_RESPONSE_ACCOUNTS_CHUNK: u32 = 1000;
+/// The max number of TrieProof requests per peer.
+pub const MAX_REQUEST_RESPONSE_TRIE_PROOF: u32 = 1000;
+/// The max number of TransactionReceipts requests per peer.
+pub const MAX_REQUEST_RESPONSE_TRANSACTION_RECEIPTS: u32 = 1000;
+/// The max number of TransactionsProof requests per peer.
+pub const MAX_REQUEST_RESPONSE_TRANSACTIONS_PROOF: u32 = 1000;
+
+/// The max number of accounts in a chunk of the accounts trie.
+pub const MAX_ACCOUNTS_CHUNK_SIZE: u16 = 1000;
+/// The max number of accounts that can be proven in a single TrieProof request.
+pub const MAX_TRIE_PROOF_KEYS: u16 = 128;
+/// The max number of transaction receipts that are returned for an address.
+pub const MAX_TRANSACTION_RECEIPTS: u16 = 500;
+/// The max number of transactions that can be proven in a single TransactionsProof request.
+pub const MAX_TRANSACTIONS_PROOF_HASHES: u16 = 128;
+
+/// Returns the names of the request types that the consensus handles (see
+/// `Consensus::init_network_request_receivers`). Their rate limits are configured by these names.
+pub fn request_type_names() -> Vec<&'static str> {
+    vec![
+        request_type_name::<RequestMacroChain>(),
+        request_type_name::<RequestBlock>(),
+        request_type_name::<RequestMissingBlocks>(),
+        request_type_name::<RequestHead>(),
+        request_type_name::<RequestBatchSet>(),
+        request_type_name::<RequestHistoryChunk>(),
+        request_type_name::<RequestAccountsChunk>(),
+        request_type_name::<RequestTrieProof>(),
+        request_type_name::<RequestTransactionReceiptsByAddress>(),
+        request_type_name::<RequestTransactionsProof>(),
+    ]
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Checkpoint {
@@ -198,3 +235,104 @@ impl RequestCommon for RequestHead {
     type Response = Blake2bHash;
     const MAX_REQUESTS: u32 = MAX_REQUEST_RESPONSE_HEAD;
 }
+
+/// This message requests a chunk of the accounts trie as it was at the given election block. The
+/// chunk starts at the key `start_key` (included if it is part of the trie) and contains at most
+/// `limit` accounts.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RequestAccountsChunk {
+    pub block_hash: Blake2bHash,
+    pub start_key: KeyNibbles,
+    pub limit: u16,
+}
+
+impl RequestCommon for RequestAccountsChunk {
+    type Kind = RequestMarker;
+    const TYPE_ID: u16 = 212;
+    type Response = AccountsChunk;
+    const MAX_REQUESTS: u32 = MAX_REQUEST_RESPONSE_ACCOUNTS_CHUNK;
+}
+
+/// This message contains a chunk of the accounts trie together with its proof against the state
+/// root of the requested block. The chunk is `None` if the peer doesn't have the accounts state
+/// at that block.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountsChunk {
+    pub chunk: Option<TrieProof<Account>>,
+}
+
+/// This message requests a proof for the accounts with the given keys at the head of the peer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RequestTrieProof {
+    #[beserial(len_type(u16, limit = 128))]
+    pub keys: Vec<KeyNibbles>,
+}
+
+impl RequestCommon for RequestTrieProof {
+    type Kind = RequestMarker;
+    const TYPE_ID: u16 = 213;
+    type Response = ResponseTrieProof;
+    const MAX_REQUESTS: u32 = MAX_REQUEST_RESPONSE_TRIE_PROOF;
+}
+
+/// This message contains a proof of the inclusion or exclusion of the requested accounts against
+/// the state root of the block with the given hash. The proof is `None` if the peer couldn't
+/// create it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResponseTrieProof {
+    pub block_hash: Blake2bHash,
+    pub proof: Option<TrieProof<Account>>,
+}
+
+/// This message requests the hashes of the most recent transactions of the given address, together
+/// with the numbers of the blocks that include them.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RequestTransactionReceiptsByAddress {
+    pub address: Address,
+    pub max: Option<u16>,
+}
+
+impl RequestCommon for RequestTransactionReceiptsByAddress {
+    type Kind = RequestMarker;
+    const TYPE_ID: u16 = 214;
+    type Response = ResponseTransactionReceipts;
+    const MAX_REQUESTS: u32 = MAX_REQUEST_RESPONSE_TRANSACTION_RECEIPTS;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionReceipt {
+    pub hash: Blake2bHash,
+    pub block_number: u32,
+}
+
+/// This message contains the transaction receipts of an address, most recent first. The receipts
+/// are `None` if the peer doesn't keep a transaction index.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResponseTransactionReceipts {
+    #[beserial(len_type(u16, limit = 500))]
+    pub receipts: Option<Vec<TransactionReceipt>>,
+}
+
+/// This message requests a proof for the transactions with the given hashes against the history
+/// root of the block at `block_number`. Only transactions of the epoch of that block that happened
+/// at or before it can be proven.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RequestTransactionsProof {
+    #[beserial(len_type(u16, limit = 128))]
+    pub hashes: Vec<Blake2bHash>,
+    pub block_number: u32,
+}
+
+impl RequestCommon for RequestTransactionsProof {
+    type Kind = RequestMarker;
+    const TYPE_ID: u16 = 215;
+    type Response = ResponseTransactionsProof;
+    const MAX_REQUESTS: u32 = MAX_REQUEST_RESPONSE_TRANSACTIONS_PROOF;
+}
+
+/// This message contains the proven transactions. The proof is `None` if the peer doesn't have the
+/// history at the requested block.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResponseTransactionsProof {
+    pub proof: Option<HistoryTreeProof>,
+}
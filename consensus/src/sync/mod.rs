@@ -1,6 +1,7 @@
 pub mod history;
 pub mod light;
 pub mod live;
+pub mod state;
 mod sync_queue;
 pub mod syncer;
 pub mod syncer_proxy;
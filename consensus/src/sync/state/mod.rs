@@ -0,0 +1,4 @@
+mod sync;
+mod sync_stream;
+
+pub use sync::StateMacroSync;
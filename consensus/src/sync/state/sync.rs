@@ -0,0 +1,332 @@
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+
+use futures::{future::BoxFuture, FutureExt};
+use parking_lot::RwLock;
+
+use nimiq_account::{Account, AccountsTrie};
+use nimiq_block::MacroBlock;
+use nimiq_blockchain::Blockchain;
+use nimiq_blockchain_interface::AbstractBlockchain;
+use nimiq_database::{Environment, WriteTransaction};
+use nimiq_light_blockchain::LightBlockchain;
+use nimiq_network_interface::{
+    network::{Network, SubscribeEvents},
+    peer::CloseReason,
+    request::RequestError,
+};
+use nimiq_trie::{key_nibbles::KeyNibbles, trie_proof::TrieProof};
+use nimiq_zkp_component::zkp_component::ZKPComponentProxy;
+
+use crate::{
+    messages::{AccountsChunk, RequestAccountsChunk, MAX_ACCOUNTS_CHUNK_SIZE},
+    sync::{
+        light::LightMacroSync,
+        syncer::{MacroSync, MacroSyncReturn},
+    },
+};
+
+/// This struct tracks the download of the accounts state at an election block.
+pub(crate) struct StateSyncTarget<TPeerId> {
+    /// The election block whose accounts state is being downloaded
+    pub block: MacroBlock,
+    /// The key at which the next chunk starts
+    pub start_key: KeyNibbles,
+    /// The peers that couldn't provide the accounts state at the election block
+    pub failed_peers: HashSet<TPeerId>,
+}
+
+/// The StateMacroSync is the MacroSync used by full nodes that don't keep the history. It is
+/// essentially a stream that emits peers either as Outdated or Good. To do this, it will:
+///   1. Sync the macro chain with the peer using a LightMacroSync, which pushes the ZKP and the
+///      latest election or checkpoint blocks into a separate light blockchain
+///   2. If our blockchain is behind the latest election block of the light blockchain, request
+///      the accounts state at that election block in chunks from the synced peers. Each chunk is
+///      verified against the state root of the election block
+///   3. Once the state is complete, adopt it into our blockchain
+/// Peers are only emitted as Good once our blockchain is at the latest election block, the
+/// blocks produced since then are caught up by the live sync.
+pub struct StateMacroSync<TNetwork: Network> {
+    /// The blockchain whose accounts state is synced
+    pub(crate) blockchain: Arc<RwLock<Blockchain>>,
+    /// Reference to the network
+    pub(crate) network: Arc<TNetwork>,
+    /// The light blockchain used to sync the macro chain
+    pub(crate) light_blockchain: Arc<RwLock<LightBlockchain>>,
+    /// The macro sync that syncs the light blockchain
+    pub(crate) light_sync: LightMacroSync<TNetwork>,
+    /// The environment of the blockchain, which also stores the downloaded accounts
+    pub(crate) env: Environment,
+    /// The trie in which the downloaded accounts are stored until the state is complete
+    pub(crate) accounts: AccountsTrie,
+    /// The accounts state that is currently being downloaded (if any)
+    pub(crate) target: Option<StateSyncTarget<TNetwork::PeerId>>,
+    /// The peers whose macro chain is synced and that are waiting for the state sync to finish
+    pub(crate) synced_peers: Vec<TNetwork::PeerId>,
+    /// The pending accounts chunk request
+    pub(crate) chunk_request:
+        Option<BoxFuture<'static, (Result<AccountsChunk, RequestError>, TNetwork::PeerId)>>,
+    /// The peers that are ready to be emitted
+    pub(crate) results: VecDeque<MacroSyncReturn<TNetwork::PeerId>>,
+}
+
+impl<TNetwork: Network> StateMacroSync<TNetwork> {
+    /// The name of the database in which the downloaded accounts are stored.
+    const ACCOUNTS_DB_NAME: &'static str = "AccountsTrieSync";
+
+    pub fn new(
+        blockchain: Arc<RwLock<Blockchain>>,
+        light_blockchain: Arc<RwLock<LightBlockchain>>,
+        network: Arc<TNetwork>,
+        network_event_rx: SubscribeEvents<TNetwork::PeerId>,
+        zkp_component_proxy: Arc<ZKPComponentProxy<TNetwork>>,
+    ) -> Self {
+        let env = blockchain.read().env().clone();
+        let accounts = AccountsTrie::new(env.clone(), Self::ACCOUNTS_DB_NAME);
+
+        let light_sync = LightMacroSync::new(
+            Arc::clone(&light_blockchain),
+            Arc::clone(&network),
+            network_event_rx,
+            zkp_component_proxy,
+        );
+
+        Self {
+            blockchain,
+            network,
+            light_blockchain,
+            light_sync,
+            env,
+            accounts,
+            target: None,
+            synced_peers: vec![],
+            chunk_request: None,
+            results: VecDeque::new(),
+        }
+    }
+
+    pub fn peers(&self) -> impl Iterator<Item = &TNetwork::PeerId> {
+        self.light_sync.peers().chain(self.synced_peers.iter())
+    }
+
+    pub fn remove_peer(&mut self, peer_id: TNetwork::PeerId) {
+        self.synced_peers
+            .retain(|synced_peer| *synced_peer != peer_id);
+    }
+
+    pub fn disconnect_peer(&mut self, peer_id: TNetwork::PeerId, close_reason: CloseReason) {
+        self.remove_peer(peer_id);
+
+        // We disconnect from this peer
+        tokio::spawn({
+            let network = Arc::clone(&self.network);
+
+            async move {
+                network.disconnect_peer(peer_id, close_reason).await;
+            }
+        });
+    }
+
+    /// Returns the election block whose accounts state we need to download, if our blockchain is
+    /// behind the latest election block of the light blockchain.
+    pub(crate) fn required_state(&self) -> Option<MacroBlock> {
+        let election_head = self.light_blockchain.read().election_head();
+
+        if election_head.block_number() > self.blockchain.read().election_head().block_number() {
+            Some(election_head)
+        } else {
+            None
+        }
+    }
+
+    /// Called when the macro chain of a peer has been synced. Either the peer is emitted as Good
+    /// right away or it is used to download the accounts state.
+    pub(crate) fn on_peer_synced(&mut self, peer_id: TNetwork::PeerId) {
+        let election_block = match self.required_state() {
+            Some(election_block) => election_block,
+            None => {
+                self.results.push_back(MacroSyncReturn::Good(peer_id));
+                return;
+            }
+        };
+
+        if !self.synced_peers.contains(&peer_id) {
+            self.synced_peers.push(peer_id);
+        }
+
+        // Start a new state sync if we are not downloading the state at this election block yet.
+        let is_current_target = self
+            .target
+            .as_ref()
+            .map(|target| target.block.hash() == election_block.hash())
+            .unwrap_or(false);
+        if !is_current_target {
+            self.start_state_sync(election_block);
+        }
+    }
+
+    /// Starts downloading the accounts state at the given election block, discarding any state
+    /// downloaded so far.
+    pub(crate) fn start_state_sync(&mut self, election_block: MacroBlock) {
+        info!(
+            block_number = election_block.block_number(),
+            "Starting to sync the accounts state",
+        );
+
+        self.clear_accounts();
+        self.chunk_request = None;
+        self.target = Some(StateSyncTarget {
+            block: election_block,
+            start_key: KeyNibbles::root(),
+            failed_peers: HashSet::new(),
+        });
+    }
+
+    /// Aborts the current state sync. The synced peers are emitted as Outdated, so that they are
+    /// added to the macro sync again after some time.
+    pub(crate) fn abort_state_sync(&mut self) {
+        self.clear_accounts();
+        self.chunk_request = None;
+        self.target = None;
+
+        for peer_id in self.synced_peers.drain(..) {
+            self.results.push_back(MacroSyncReturn::Outdated(peer_id));
+        }
+    }
+
+    /// Requests the next chunk of the accounts state from a synced peer that didn't fail to
+    /// provide it yet. If all synced peers failed, the state sync is aborted.
+    pub(crate) fn request_next_chunk(&mut self) {
+        let target = match &self.target {
+            Some(target) if self.chunk_request.is_none() => target,
+            _ => return,
+        };
+
+        let peer_id = match self
+            .synced_peers
+            .iter()
+            .find(|peer_id| !target.failed_peers.contains(peer_id))
+        {
+            Some(peer_id) => *peer_id,
+            None => {
+                if !self.synced_peers.is_empty() {
+                    debug!("None of the synced peers could provide the accounts state");
+                    self.abort_state_sync();
+                }
+                return;
+            }
+        };
+
+        let request = RequestAccountsChunk {
+            block_hash: target.block.hash(),
+            start_key: target.start_key.clone(),
+            limit: MAX_ACCOUNTS_CHUNK_SIZE,
+        };
+
+        let network = Arc::clone(&self.network);
+        self.chunk_request =
+            Some(async move { (network.request(request, peer_id).await, peer_id) }.boxed());
+    }
+
+    /// Verifies the given chunk against the state root of the election block and stores its
+    /// accounts. Each chunk must start at the last key of the previous one and can't leave out any
+    /// accounts in between. Returns whether this was the last chunk of the accounts state, or an
+    /// error if the chunk is invalid.
+    pub(crate) fn apply_chunk(&mut self, chunk: TrieProof<Account>) -> Result<bool, ()> {
+        let target = self
+            .target
+            .as_mut()
+            .expect("Received a chunk without a target");
+
+        let leaves = chunk.leaf_nodes();
+        if leaves.len() > MAX_ACCOUNTS_CHUNK_SIZE as usize {
+            return Err(());
+        }
+
+        // Chunks are inclusive, so every chunk but the first starts with the last account of the
+        // previous one.
+        let starts_at_key = match leaves.first() {
+            Some(leaf) => target.start_key.is_empty() || *leaf.key() == target.start_key,
+            None => target.start_key.is_empty(),
+        };
+        if !starts_at_key {
+            return Err(());
+        }
+
+        // A chunk with less accounts than requested is the last one, so it must prove that there
+        // are no accounts after it.
+        let is_last_chunk = leaves.len() < MAX_ACCOUNTS_CHUNK_SIZE as usize;
+        if !chunk.verify_chunk(
+            &target.block.header.state_root,
+            &target.start_key,
+            is_last_chunk,
+        ) {
+            return Err(());
+        }
+
+        let mut txn = WriteTransaction::new(&self.env);
+        for leaf in leaves.iter() {
+            let account = leaf.value().map_err(|_| ())?;
+            self.accounts.put(&mut txn, leaf.key(), account);
+        }
+        txn.commit();
+
+        // The next chunk starts at the last key we received. This account is received twice,
+        // which is harmless.
+        if let Some(leaf) = leaves.last() {
+            target.start_key = leaf.key().clone();
+        }
+
+        Ok(is_last_chunk)
+    }
+
+    /// Adopts the downloaded accounts state into our blockchain. On success, all synced peers
+    /// are emitted as Good.
+    pub(crate) fn finish_state_sync(&mut self) {
+        let target = self
+            .target
+            .take()
+            .expect("Finished a state sync without a target");
+        let block_number = target.block.block_number();
+
+        let result = {
+            let blockchain = self.blockchain.upgradable_read();
+            Blockchain::adopt_state_sync(blockchain, target.block, &self.accounts)
+        };
+
+        match result {
+            Ok(()) => {
+                info!(block_number, "Finished syncing the accounts state");
+                self.clear_accounts();
+
+                for peer_id in self.synced_peers.drain(..) {
+                    self.results.push_back(MacroSyncReturn::Good(peer_id));
+                }
+            }
+            Err(error) => {
+                warn!(
+                    block_number,
+                    ?error,
+                    "Failed to adopt the synced accounts state"
+                );
+                self.abort_state_sync();
+            }
+        }
+    }
+
+    /// Removes all downloaded accounts.
+    fn clear_accounts(&self) {
+        let mut txn = WriteTransaction::new(&self.env);
+        self.accounts.clear(&mut txn);
+        txn.commit();
+    }
+}
+
+impl<TNetwork: Network> MacroSync<TNetwork::PeerId> for StateMacroSync<TNetwork> {
+    fn add_peer(&self, peer_id: TNetwork::PeerId) {
+        trace!("Adding peer to the state macro sync: {:?}", peer_id);
+
+        // The macro chain of the peer is synced first.
+        self.light_sync.add_peer(peer_id);
+    }
+}
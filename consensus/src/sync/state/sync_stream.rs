@@ -0,0 +1,278 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::{FutureExt, Stream, StreamExt};
+
+use nimiq_network_interface::{network::Network, peer::CloseReason};
+
+use crate::{
+    messages::AccountsChunk,
+    sync::{state::StateMacroSync, syncer::MacroSyncReturn},
+};
+
+impl<TNetwork: Network> StateMacroSync<TNetwork> {
+    // Removes peers from the internal data structures when they leave. The network events are
+    // consumed by the light macro sync, so we check the synced peers against the network.
+    fn remove_left_peers(&mut self) {
+        let network = &self.network;
+        self.synced_peers
+            .retain(|peer_id| network.has_peer(*peer_id));
+    }
+
+    // Polls the light macro sync. Outdated peers are emitted right away, while the synced ones
+    // might first be used to download the accounts state.
+    fn poll_light_sync(&mut self, cx: &mut Context<'_>) {
+        while let Poll::Ready(result) = self.light_sync.poll_next_unpin(cx) {
+            match result {
+                Some(MacroSyncReturn::Good(peer_id)) => {
+                    self.on_peer_synced(peer_id);
+                }
+                Some(MacroSyncReturn::Outdated(peer_id)) => {
+                    self.remove_peer(peer_id);
+                    self.results.push_back(MacroSyncReturn::Outdated(peer_id));
+                }
+                None => {}
+            }
+        }
+    }
+
+    // Function that drives the download of the accounts state, there can be several cases to be
+    // taken into consideration:
+    //   A) The peer sends a valid chunk:
+    //         We store it and request the next one, or adopt the state if it was the last chunk
+    //   B) The peer sends an invalid chunk:
+    //         In this case we ban the peer and request the chunk from another peer
+    //   C) The peer doesn't have the accounts state or the request fails:
+    //         In this case we request the chunk from another peer
+    fn poll_accounts_chunks(&mut self, cx: &mut Context<'_>) {
+        loop {
+            self.request_next_chunk();
+
+            let result = match self.chunk_request.as_mut() {
+                Some(request) => match request.poll_unpin(cx) {
+                    Poll::Ready(result) => result,
+                    Poll::Pending => return,
+                },
+                None => return,
+            };
+            self.chunk_request = None;
+
+            match result {
+                (Ok(AccountsChunk { chunk: Some(chunk) }), peer_id) => {
+                    match self.apply_chunk(chunk) {
+                        Ok(true) => self.finish_state_sync(),
+                        Ok(false) => {}
+                        Err(()) => {
+                            debug!(%peer_id, "Received an invalid accounts chunk");
+                            if let Some(target) = self.target.as_mut() {
+                                target.failed_peers.insert(peer_id);
+                            }
+                            self.disconnect_peer(peer_id, CloseReason::MaliciousPeer);
+                        }
+                    }
+                }
+                (Ok(AccountsChunk { chunk: None }), peer_id) => {
+                    trace!(%peer_id, "Peer doesn't have the requested accounts state");
+                    if let Some(target) = self.target.as_mut() {
+                        target.failed_peers.insert(peer_id);
+                    }
+                }
+                (Err(error), peer_id) => {
+                    debug!(%peer_id, ?error, "Failed accounts chunk request");
+                    if let Some(target) = self.target.as_mut() {
+                        target.failed_peers.insert(peer_id);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<TNetwork: Network> Stream for StateMacroSync<TNetwork> {
+    type Item = MacroSyncReturn<TNetwork::PeerId>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.remove_left_peers();
+
+        self.poll_light_sync(cx);
+
+        self.poll_accounts_chunks(cx);
+
+        if let Some(result) = self.results.pop_front() {
+            return Poll::Ready(Some(result));
+        }
+
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use futures::StreamExt;
+    use parking_lot::RwLock;
+
+    use nimiq_block_production::BlockProducer;
+    use nimiq_blockchain::{Blockchain, BlockchainConfig};
+    use nimiq_blockchain_interface::AbstractBlockchain;
+    use nimiq_blockchain_proxy::BlockchainProxy;
+    use nimiq_database::{volatile::VolatileEnvironment, ReadTransaction};
+    use nimiq_light_blockchain::LightBlockchain;
+    use nimiq_network_interface::{network::Network, request::request_handler};
+    use nimiq_network_mock::{MockHub, MockNetwork};
+    use nimiq_primitives::{networks::NetworkId, policy::Policy};
+    use nimiq_test_log::test;
+    use nimiq_test_utils::blockchain::{produce_macro_blocks_with_txns, signing_key, voting_key};
+    use nimiq_utils::time::OffsetTime;
+    use nimiq_zkp_component::ZKPComponent;
+
+    use crate::messages::{RequestAccountsChunk, RequestBlock, RequestMacroChain};
+    use crate::sync::state::StateMacroSync;
+    use crate::sync::syncer::MacroSyncReturn;
+    pub const KEYS_PATH: &str = "../.zkp";
+
+    fn blockchain(keep_accounts_snapshot: bool) -> Arc<RwLock<Blockchain>> {
+        let time = Arc::new(OffsetTime::new());
+        let env = VolatileEnvironment::new(14).unwrap();
+        let config = BlockchainConfig {
+            keep_history: false,
+            keep_accounts_snapshot,
+            ..Default::default()
+        };
+        Arc::new(RwLock::new(
+            Blockchain::new(env, config, NetworkId::UnitAlbatross, time).unwrap(),
+        ))
+    }
+
+    fn spawn_request_handlers<TNetwork: Network>(
+        network: &Arc<TNetwork>,
+        blockchain: &Arc<RwLock<Blockchain>>,
+    ) {
+        let blockchain_proxy = BlockchainProxy::from(blockchain);
+
+        tokio::spawn(request_handler(
+            network,
+            network.receive_requests::<RequestMacroChain>(),
+            &blockchain_proxy,
+        ));
+
+        tokio::spawn(request_handler(
+            network,
+            network.receive_requests::<RequestBlock>(),
+            &blockchain_proxy,
+        ));
+
+        tokio::spawn(request_handler(
+            network,
+            network.receive_requests::<RequestAccountsChunk>(),
+            blockchain,
+        ));
+    }
+
+    async fn spawn_zkp_component<TNetwork: Network>(
+        network: &Arc<TNetwork>,
+        blockchain: &Arc<RwLock<Blockchain>>,
+    ) -> ZKPComponent<TNetwork> {
+        ZKPComponent::new(
+            BlockchainProxy::from(blockchain),
+            Arc::clone(network),
+            false,
+            None,
+            VolatileEnvironment::new(10).unwrap(),
+            PathBuf::from(KEYS_PATH),
+        )
+        .await
+    }
+
+    #[test(tokio::test)]
+    async fn it_syncs_the_accounts_state_at_the_latest_election_block() {
+        let mut hub = MockHub::default();
+        let net1 = Arc::new(hub.new_network());
+        let net2 = Arc::new(hub.new_network());
+
+        let chain1 = blockchain(false);
+        let chain2 = blockchain(true);
+
+        // Produce a full epoch and one more batch, so the head of the peer is past the election
+        // block whose state is synced.
+        let producer = BlockProducer::new(signing_key(), voting_key());
+        produce_macro_blocks_with_txns(
+            &producer,
+            &chain2,
+            (Policy::batches_per_epoch() + 1) as usize,
+            1,
+            0,
+        );
+        let election_head = chain2.read().election_head();
+        assert_eq!(election_head.block_number(), Policy::blocks_per_epoch());
+
+        let zkp_component = spawn_zkp_component(&net1, &chain1).await;
+        let zkp_component_proxy = Arc::new(zkp_component.proxy());
+        tokio::spawn(zkp_component);
+
+        let mut sync = StateMacroSync::<MockNetwork>::new(
+            Arc::clone(&chain1),
+            Arc::new(RwLock::new(LightBlockchain::new(NetworkId::UnitAlbatross))),
+            Arc::clone(&net1),
+            net1.subscribe_events(),
+            zkp_component_proxy,
+        );
+
+        tokio::spawn(spawn_zkp_component(&net2, &chain2).await);
+        spawn_request_handlers(&net2, &chain2);
+        net1.dial_mock(&net2);
+
+        match sync.next().await {
+            Some(MacroSyncReturn::Good(_)) => {
+                let chain1 = chain1.read();
+                assert_eq!(chain1.head_hash(), election_head.hash());
+                assert_eq!(chain1.election_head(), election_head);
+                assert_eq!(
+                    chain1.state().accounts.get_root(None),
+                    election_head.header.state_root
+                );
+            }
+            res => panic!("Unexpected MacroSyncReturn: {:?}", res),
+        }
+
+        // The downloaded accounts were moved into the blockchain.
+        let txn = ReadTransaction::new(&sync.env);
+        assert_eq!(sync.accounts.size(&txn), 0);
+    }
+
+    #[test(tokio::test)]
+    async fn it_does_not_sync_the_state_if_the_blockchain_is_up_to_date() {
+        let mut hub = MockHub::default();
+        let net1 = Arc::new(hub.new_network());
+        let net2 = Arc::new(hub.new_network());
+
+        let chain1 = blockchain(false);
+        let chain2 = blockchain(true);
+
+        let zkp_component = spawn_zkp_component(&net1, &chain1).await;
+        let zkp_component_proxy = Arc::new(zkp_component.proxy());
+        tokio::spawn(zkp_component);
+
+        let mut sync = StateMacroSync::<MockNetwork>::new(
+            Arc::clone(&chain1),
+            Arc::new(RwLock::new(LightBlockchain::new(NetworkId::UnitAlbatross))),
+            Arc::clone(&net1),
+            net1.subscribe_events(),
+            zkp_component_proxy,
+        );
+
+        tokio::spawn(spawn_zkp_component(&net2, &chain2).await);
+        spawn_request_handlers(&net2, &chain2);
+        net1.dial_mock(&net2);
+
+        match sync.next().await {
+            Some(MacroSyncReturn::Good(_)) => {
+                assert_eq!(chain1.read().head(), chain2.read().head());
+                assert!(sync.target.is_none());
+            }
+            res => panic!("Unexpected MacroSyncReturn: {:?}", res),
+        }
+    }
+}
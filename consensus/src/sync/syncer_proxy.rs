@@ -4,11 +4,12 @@ use std::task::{Context, Poll};
 
 use futures::{Stream, StreamExt};
 use nimiq_zkp_component::zkp_component::ZKPComponentProxy;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 
 use nimiq_block::Block;
 use nimiq_blockchain_proxy::BlockchainProxy;
 use nimiq_bls::cache::PublicKeyCache;
+use nimiq_light_blockchain::LightBlockchain;
 use nimiq_network_interface::network::{Network, SubscribeEvents};
 use pin_project::pin_project;
 
@@ -19,6 +20,7 @@ use crate::sync::{
         request_component::BlockRequestComponent,
         BlockLiveSync,
     },
+    state::StateMacroSync,
     syncer::{LiveSyncPushEvent, Syncer},
 };
 
@@ -29,6 +31,7 @@ macro_rules! gen_syncer_match {
         match $self {
             SyncerProxy::History(syncer) => syncer.$f($( $arg ),*),
             SyncerProxy::Light(syncer) => syncer.$f($( $arg ),*),
+            SyncerProxy::Full(syncer) => syncer.$f($( $arg ),*),
         }
     };
 }
@@ -40,6 +43,8 @@ pub enum SyncerProxy<N: Network> {
     History(Syncer<N, HistoryMacroSync<N>, BlockLiveSync<N, BlockRequestComponent<N>>>),
     /// Light Syncer, uses light macro sync for macro sync and block live sync.
     Light(Syncer<N, LightMacroSync<N>, BlockLiveSync<N, BlockRequestComponent<N>>>),
+    /// Full Syncer, uses state macro sync for macro sync and block live sync.
+    Full(Syncer<N, StateMacroSync<N>, BlockLiveSync<N, BlockRequestComponent<N>>>),
 }
 
 impl<N: Network> SyncerProxy<N> {
@@ -142,6 +147,62 @@ impl<N: Network> SyncerProxy<N> {
         }
     }
 
+    #[cfg(not(target_family = "wasm"))]
+    /// Creates a new instance of a `SyncerProxy` for the `Full` variant
+    pub async fn new_full(
+        blockchain_proxy: BlockchainProxy,
+        network: Arc<N>,
+        bls_cache: Arc<Mutex<PublicKeyCache>>,
+        zkp_component_proxy: Arc<ZKPComponentProxy<N>>,
+        network_event_rx: SubscribeEvents<N::PeerId>,
+    ) -> Self {
+        assert!(
+            matches!(blockchain_proxy, BlockchainProxy::Full(_)),
+            "Full Syncer can only be created for a full blockchain"
+        );
+
+        match blockchain_proxy {
+            BlockchainProxy::Full(ref blockchain) => {
+                let request_component = BlockRequestComponent::new(
+                    network.subscribe_events(),
+                    Arc::clone(&network),
+                    true,
+                );
+
+                let block_queue = BlockQueue::new(
+                    Arc::clone(&network),
+                    blockchain_proxy.clone(),
+                    request_component,
+                    BlockQueueConfig::default(),
+                )
+                .await;
+
+                let live_sync = BlockLiveSync::new(
+                    blockchain_proxy.clone(),
+                    Arc::clone(&network),
+                    block_queue,
+                    bls_cache,
+                );
+
+                // The macro chain is synced into a separate light blockchain, which provides the
+                // election block whose accounts state is downloaded.
+                let network_id = blockchain.read().network_id;
+                let light_blockchain = Arc::new(RwLock::new(LightBlockchain::new(network_id)));
+
+                let macro_sync = StateMacroSync::new(
+                    Arc::clone(blockchain),
+                    light_blockchain,
+                    network,
+                    network_event_rx,
+                    zkp_component_proxy,
+                );
+
+                Self::Full(Syncer::new(live_sync, macro_sync))
+            }
+            BlockchainProxy::Light(_) => unreachable!(),
+        }
+    }
+
     /// Pushes a block for the live sync method
     pub fn push_block(&mut self, block: Block, peer_id: N::PeerId, pubsub_id: Option<N::PubsubId>) {
         gen_syncer_match!(self, push_block, block, peer_id, pubsub_id)
@@ -170,6 +231,7 @@ impl<N: Network> Stream for SyncerProxy<N> {
         match self.project() {
             SyncerProxyProj::History(syncer) => syncer.poll_next_unpin(cx),
             SyncerProxyProj::Light(syncer) => syncer.poll_next_unpin(cx),
+            SyncerProxyProj::Full(syncer) => syncer.poll_next_unpin(cx),
         }
     }
 }
@@ -0,0 +1,185 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use nimiq_block_production::BlockProducer;
+use nimiq_blockchain::{Blockchain, BlockchainConfig};
+use nimiq_blockchain_interface::AbstractBlockchain;
+use nimiq_consensus::messages::{
+    RequestAccountsChunk, RequestTransactionReceiptsByAddress, RequestTransactionsProof,
+    RequestTrieProof,
+};
+use nimiq_database::volatile::VolatileEnvironment;
+use nimiq_genesis::NetworkId;
+use nimiq_keys::{Address, KeyPair, PrivateKey};
+use nimiq_network_interface::{
+    network::Network,
+    request::{request_handler, Handle},
+};
+use nimiq_network_mock::{MockHub, MockNetwork};
+use nimiq_primitives::policy::Policy;
+use nimiq_test_log::test;
+use nimiq_test_utils::blockchain::{
+    produce_macro_blocks_with_txns, signing_key, voting_key, UNIT_KEY,
+};
+use nimiq_utils::time::OffsetTime;
+
+fn blockchain() -> Arc<RwLock<Blockchain>> {
+    let time = Arc::new(OffsetTime::new());
+    let env = VolatileEnvironment::new(12).unwrap();
+    Arc::new(RwLock::new(
+        Blockchain::new(
+            env,
+            BlockchainConfig::default(),
+            NetworkId::UnitAlbatross,
+            time,
+        )
+        .unwrap(),
+    ))
+}
+
+fn spawn_request_handlers(network: &Arc<MockNetwork>, blockchain: &Arc<RwLock<Blockchain>>) {
+    tokio::spawn(request_handler(
+        network,
+        network.receive_requests::<RequestTrieProof>(),
+        blockchain,
+    ));
+
+    tokio::spawn(request_handler(
+        network,
+        network.receive_requests::<RequestTransactionReceiptsByAddress>(),
+        blockchain,
+    ));
+
+    tokio::spawn(request_handler(
+        network,
+        network.receive_requests::<RequestTransactionsProof>(),
+        blockchain,
+    ));
+}
+
+#[test(tokio::test)]
+async fn light_clients_can_request_verified_accounts_and_transactions() {
+    let mut hub = MockHub::default();
+    let net1 = Arc::new(hub.new_network());
+    let net2 = Arc::new(hub.new_network());
+
+    // Produce a batch with one transaction per micro block, all sent from the same account.
+    let chain = blockchain();
+    let producer = BlockProducer::new(signing_key(), voting_key());
+    produce_macro_blocks_with_txns(&producer, &chain, 1, 1, 0);
+
+    spawn_request_handlers(&net2, &chain);
+    net1.dial_mock(&net2);
+    let peer_id = net2.get_local_peer_id();
+
+    let sender = Address::from(&KeyPair::from(PrivateKey::from_str(UNIT_KEY).unwrap()).public);
+    let unknown = Address::from([0x42; 20]);
+    let head = chain.read().head();
+
+    // The accounts are proven against the state root of the head.
+    let keys = vec![
+        Blockchain::get_account_key(&sender),
+        Blockchain::get_account_key(&unknown),
+    ];
+    let response = net1
+        .request(RequestTrieProof { keys: keys.clone() }, peer_id)
+        .await
+        .unwrap();
+    assert_eq!(response.block_hash, head.hash());
+
+    let accounts = response
+        .proof
+        .unwrap()
+        .verify_values(&keys.iter().collect::<Vec<_>>(), head.state_root())
+        .unwrap();
+    assert_eq!(accounts[0], chain.read().get_account(&sender));
+    assert!(accounts[0].is_some());
+    assert!(accounts[1].is_none());
+
+    // The transactions are proven against the history root of the head.
+    let receipts = net1
+        .request(
+            RequestTransactionReceiptsByAddress {
+                address: sender,
+                max: None,
+            },
+            peer_id,
+        )
+        .await
+        .unwrap()
+        .receipts
+        .unwrap();
+    assert_eq!(receipts.len(), (Policy::blocks_per_batch() - 1) as usize);
+
+    let hashes: Vec<_> = receipts
+        .iter()
+        .map(|receipt| receipt.hash.clone())
+        .collect();
+    let proof = net1
+        .request(
+            RequestTransactionsProof {
+                hashes: hashes.clone(),
+                block_number: head.block_number(),
+            },
+            peer_id,
+        )
+        .await
+        .unwrap()
+        .proof
+        .unwrap();
+    assert_eq!(proof.verify(head.history_root().clone()), Some(true));
+    assert_eq!(proof.history.len(), hashes.len());
+    assert!(proof
+        .history
+        .iter()
+        .all(|ext_tx| hashes.contains(&ext_tx.tx_hash())));
+
+    // A proof at an earlier block doesn't include the later transactions.
+    let proof = net1
+        .request(
+            RequestTransactionsProof {
+                hashes,
+                block_number: 1,
+            },
+            peer_id,
+        )
+        .await
+        .unwrap()
+        .proof
+        .unwrap();
+    let block = chain.read().get_block_at(1, false, None).unwrap();
+    assert_eq!(proof.verify(block.history_root().clone()), Some(true));
+    assert_eq!(proof.history.len(), 1);
+}
+
+#[test]
+fn it_refuses_empty_accounts_chunks() {
+    let chain = blockchain();
+    let head_hash = chain.read().head_hash();
+
+    // A chunk with at least one account can be proven.
+    let request = RequestAccountsChunk {
+        block_hash: head_hash.clone(),
+        start_key: Blockchain::get_account_key(&Address::from([0; 20])),
+        limit: 1,
+    };
+    assert!(request.handle(&chain).chunk.is_some());
+
+    // A limit of zero accounts is refused.
+    let request = RequestAccountsChunk {
+        block_hash: head_hash.clone(),
+        start_key: Blockchain::get_account_key(&Address::from([0; 20])),
+        limit: 0,
+    };
+    assert!(request.handle(&chain).chunk.is_none());
+
+    // There are no accounts after the last possible address.
+    let request = RequestAccountsChunk {
+        block_hash: head_hash,
+        start_key: Blockchain::get_account_key(&Address::from([0xff; 20])),
+        limit: 100,
+    };
+    assert!(request.handle(&chain).chunk.is_none());
+}
@@ -14,6 +14,7 @@ use crate::cursor::{ReadCursor, WriteCursor as WriteCursorTrait};
 #[macro_use]
 pub mod cursor;
 pub mod mdbx;
+pub mod overlay;
 pub mod volatile;
 
 use nimiq_database_value::{AsDatabaseBytes, FromDatabaseValue, IntoDatabaseValue};
@@ -114,6 +115,14 @@ impl Database {
             Database::Volatile(ref db) => Some(db.as_mdbx()),
         }
     }
+
+    fn name(&self) -> &str {
+        self.persistent().unwrap().name()
+    }
+
+    fn has_duplicate_keys(&self) -> bool {
+        self.persistent().unwrap().has_duplicate_keys()
+    }
 }
 
 #[derive(Debug)]
@@ -122,6 +131,7 @@ pub enum Transaction<'env> {
     VolatileWrite(volatile::VolatileWriteTransaction<'env>),
     PersistentRead(mdbx::MdbxReadTransaction<'env>),
     PersistentWrite(mdbx::MdbxWriteTransaction<'env>),
+    Overlay(overlay::OverlayTransaction<'env>),
 }
 
 impl<'env> Transaction<'env> {
@@ -135,6 +145,7 @@ impl<'env> Transaction<'env> {
             Transaction::VolatileWrite(ref txn) => txn.get(db.volatile().unwrap(), key),
             Transaction::PersistentRead(ref txn) => txn.get(db.persistent().unwrap(), key),
             Transaction::PersistentWrite(ref txn) => txn.get(db.persistent().unwrap(), key),
+            Transaction::Overlay(ref txn) => txn.get(db, key),
         }
     }
 
@@ -144,6 +155,7 @@ impl<'env> Transaction<'env> {
             Transaction::VolatileWrite(ref txn) => Cursor::VolatileCursor(txn.cursor(db)),
             Transaction::PersistentRead(ref txn) => Cursor::PersistentCursor(txn.cursor(db)),
             Transaction::PersistentWrite(ref txn) => Cursor::PersistentCursor(txn.cursor(db)),
+            Transaction::Overlay(ref txn) => txn.cursor(db),
         }
     }
 }
@@ -201,6 +213,13 @@ impl<'env> WriteTransaction<'env> {
         }
     }
 
+    /// Creates a write transaction that keeps its changes in memory and can't be committed. It
+    /// doesn't support write cursors or databases with duplicate keys.
+    /// See [`overlay::OverlayTransaction`].
+    pub fn overlay(env: &'env Environment) -> Self {
+        WriteTransaction(Transaction::Overlay(overlay::OverlayTransaction::new(env)))
+    }
+
     pub fn get<K, V>(&self, db: &Database, key: &K) -> Option<V>
     where
         K: AsDatabaseBytes + ?Sized,
@@ -224,6 +243,7 @@ impl<'env> WriteTransaction<'env> {
             Transaction::PersistentWrite(ref mut txn) => {
                 txn.put_reserve(db.persistent().unwrap(), key, value)
             }
+            Transaction::Overlay(ref mut txn) => txn.put_reserve(db, key, value),
             _ => {
                 unreachable!();
             }
@@ -244,6 +264,7 @@ impl<'env> WriteTransaction<'env> {
             Transaction::PersistentWrite(ref mut txn) => {
                 txn.put(db.persistent().unwrap(), key, value)
             }
+            Transaction::Overlay(ref mut txn) => txn.put(db, key, value),
             _ => {
                 unreachable!();
             }
@@ -257,6 +278,7 @@ impl<'env> WriteTransaction<'env> {
         match self.0 {
             Transaction::VolatileWrite(ref mut txn) => txn.remove(db.volatile().unwrap(), key),
             Transaction::PersistentWrite(ref mut txn) => txn.remove(db.persistent().unwrap(), key),
+            Transaction::Overlay(ref mut txn) => txn.remove(db, key),
             _ => {
                 unreachable!();
             }
@@ -275,6 +297,9 @@ impl<'env> WriteTransaction<'env> {
             Transaction::PersistentWrite(ref mut txn) => {
                 txn.remove_item(db.persistent().unwrap(), key, value)
             }
+            Transaction::Overlay(_) => {
+                panic!("Overlay transactions don't support databases with duplicate keys")
+            }
             _ => {
                 unreachable!();
             }
@@ -285,6 +310,7 @@ impl<'env> WriteTransaction<'env> {
         match self.0 {
             Transaction::VolatileWrite(txn) => txn.commit(),
             Transaction::PersistentWrite(txn) => txn.commit(),
+            Transaction::Overlay(_) => panic!("Overlay transactions can't be committed"),
             _ => {
                 unreachable!();
             }
@@ -305,6 +331,7 @@ impl<'env> WriteTransaction<'env> {
             Transaction::PersistentWrite(ref txn) => {
                 WriteCursor::PersistentCursor(txn.write_cursor(db))
             }
+            Transaction::Overlay(_) => panic!("Overlay transactions don't support write cursors"),
             _ => unreachable!(),
         }
     }
@@ -152,6 +152,16 @@ pub struct MdbxDatabase {
     flags: libmdbx::DatabaseFlags,
 }
 
+impl MdbxDatabase {
+    pub(super) fn name(&self) -> &str {
+        &self.db
+    }
+
+    pub(super) fn has_duplicate_keys(&self) -> bool {
+        self.flags.contains(libmdbx::DatabaseFlags::DUP_SORT)
+    }
+}
+
 pub struct MdbxReadTransaction<'env> {
     txn: mdbx::Transaction<'env, RO, NoWriteMap>,
 }
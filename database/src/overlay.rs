@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use super::*;
+
+/// A transaction that keeps its changes in memory on top of a read transaction. It can't be
+/// committed and doesn't take the write lock of the environment, so other readers and writers
+/// aren't blocked while it is used to compute a temporary state.
+///
+/// Databases with duplicate keys can't be written to, and cursors can only be opened on databases
+/// that the transaction didn't change, since they wouldn't see the changes. Both panic instead of
+/// silently working on inconsistent data.
+#[derive(Debug)]
+pub struct OverlayTransaction<'env> {
+    txn: Box<Transaction<'env>>,
+    /// The changed values by database name and key. `None` marks a removed key.
+    changes: HashMap<String, HashMap<Vec<u8>, Option<Vec<u8>>>>,
+}
+
+impl<'env> OverlayTransaction<'env> {
+    pub(super) fn new(env: &'env Environment) -> Self {
+        let ReadTransaction(txn) = ReadTransaction::new(env);
+        OverlayTransaction {
+            txn: Box::new(txn),
+            changes: HashMap::new(),
+        }
+    }
+
+    pub(super) fn get<K, V>(&self, db: &Database, key: &K) -> Option<V>
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: FromDatabaseValue,
+    {
+        let change = self
+            .changes
+            .get(db.name())
+            .and_then(|changes| changes.get(AsDatabaseBytes::as_database_bytes(key).as_ref()));
+
+        match change {
+            Some(value) => Some(FromDatabaseValue::copy_from_database(value.as_ref()?).unwrap()),
+            None => self.txn.get(db, key),
+        }
+    }
+
+    pub(super) fn put_reserve<K, V>(&mut self, db: &Database, key: &K, value: &V)
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: IntoDatabaseValue + ?Sized,
+    {
+        let mut bytes = vec![0; IntoDatabaseValue::database_byte_size(value)];
+        IntoDatabaseValue::copy_into_database(value, &mut bytes);
+        self.set(db, key, Some(bytes));
+    }
+
+    pub(super) fn put<K, V>(&mut self, db: &Database, key: &K, value: &V)
+    where
+        K: AsDatabaseBytes + ?Sized,
+        V: AsDatabaseBytes + ?Sized,
+    {
+        let bytes = AsDatabaseBytes::as_database_bytes(value).into_owned();
+        self.set(db, key, Some(bytes));
+    }
+
+    pub(super) fn remove<K>(&mut self, db: &Database, key: &K)
+    where
+        K: AsDatabaseBytes + ?Sized,
+    {
+        self.set(db, key, None);
+    }
+
+    pub(super) fn cursor<'txn, 'db>(&'txn self, db: &'db Database) -> Cursor<'txn> {
+        assert!(
+            !self.changes.contains_key(db.name()),
+            "Cursors of overlay transactions don't see the changes of the transaction"
+        );
+        self.txn.cursor(db)
+    }
+
+    fn set<K>(&mut self, db: &Database, key: &K, value: Option<Vec<u8>>)
+    where
+        K: AsDatabaseBytes + ?Sized,
+    {
+        assert!(
+            !db.has_duplicate_keys(),
+            "Overlay transactions don't support databases with duplicate keys"
+        );
+
+        self.changes
+            .entry(db.name().to_string())
+            .or_default()
+            .insert(AsDatabaseBytes::as_database_bytes(key).into_owned(), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nimiq_test_log::test;
+
+    use super::*;
+    use crate::volatile::VolatileEnvironment;
+
+    #[test]
+    fn it_keeps_changes_in_memory() {
+        let env = VolatileEnvironment::new(1).unwrap();
+        let db = env.open_database("test".to_string());
+
+        let mut txn = WriteTransaction::new(&env);
+        txn.put_reserve(&db, "one", "1");
+        txn.put_reserve(&db, "two", "2");
+        txn.commit();
+
+        let mut overlay = WriteTransaction::overlay(&env);
+        overlay.put_reserve(&db, "one", "one");
+        overlay.remove(&db, "two");
+        overlay.put_reserve(&db, "three", "3");
+        assert_eq!(
+            overlay.get::<str, String>(&db, "one"),
+            Some("one".to_string())
+        );
+        assert!(overlay.get::<str, String>(&db, "two").is_none());
+        assert_eq!(
+            overlay.get::<str, String>(&db, "three"),
+            Some("3".to_string())
+        );
+
+        // The overlay doesn't block writers and doesn't see their changes.
+        let mut txn = WriteTransaction::new(&env);
+        txn.put_reserve(&db, "four", "4");
+        txn.commit();
+        assert!(overlay.get::<str, String>(&db, "four").is_none());
+        overlay.abort();
+
+        let txn = ReadTransaction::new(&env);
+        assert_eq!(txn.get::<str, String>(&db, "one"), Some("1".to_string()));
+        assert_eq!(txn.get::<str, String>(&db, "two"), Some("2".to_string()));
+        assert!(txn.get::<str, String>(&db, "three").is_none());
+        assert_eq!(txn.get::<str, String>(&db, "four"), Some("4".to_string()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_refuses_cursors_on_changed_databases() {
+        let env = VolatileEnvironment::new(1).unwrap();
+        let db = env.open_database("test".to_string());
+
+        let mut overlay = WriteTransaction::overlay(&env);
+        overlay.put_reserve(&db, "one", "1");
+        overlay.cursor(&db);
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_refuses_writes_to_databases_with_duplicate_keys() {
+        let env = VolatileEnvironment::new(1).unwrap();
+        let db = env.open_database_with_flags("test".to_string(), DatabaseFlags::DUPLICATE_KEYS);
+
+        let mut overlay = WriteTransaction::overlay(&env);
+        overlay.put(&db, "one", "1");
+    }
+}
@@ -10,4 +10,6 @@ pub enum ParseError {
     FromHex(#[from] FromHexError),
     #[error("{0}")]
     KeysError(#[from] KeysError),
+    #[error("Invalid curve point")]
+    InvalidPoint,
 }
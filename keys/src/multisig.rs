@@ -3,6 +3,7 @@ use std::error;
 use std::fmt;
 use std::iter::Sum;
 use std::ops::Add;
+use std::str::FromStr;
 
 use curve25519_dalek::constants;
 use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
@@ -13,13 +14,35 @@ use sha2::{self, Digest, Sha512};
 
 use nimiq_utils::key_rng::{CryptoRng, RngCore, SecureGenerate};
 
-use crate::{KeyPair, PublicKey, Signature};
+use crate::{KeyPair, ParseError, PublicKey, Signature};
+
+/// Decodes a hex string into a fixed size byte array.
+fn from_hex_array<const N: usize>(s: &str) -> Result<[u8; N], ParseError> {
+    let mut bytes = [0u8; N];
+    hex::decode_to_slice(s, &mut bytes)?;
+    Ok(bytes)
+}
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub struct RandomSecret(pub Scalar);
 
 impl RandomSecret {
     pub const SIZE: usize = 32;
+
+    #[inline]
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0.as_bytes())
+    }
+}
+
+impl FromStr for RandomSecret {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(RandomSecret::from(
+            from_hex_array::<{ RandomSecret::SIZE }>(s)?,
+        ))
+    }
 }
 
 impl From<[u8; RandomSecret::SIZE]> for RandomSecret {
@@ -50,6 +73,20 @@ impl Commitment {
         let compressed = CompressedEdwardsY(bytes);
         compressed.decompress().map(Commitment)
     }
+
+    #[inline]
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+}
+
+impl FromStr for Commitment {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Commitment::from_bytes(from_hex_array::<{ Commitment::SIZE }>(s)?)
+            .ok_or(ParseError::InvalidPoint)
+    }
 }
 
 impl From<[u8; Commitment::SIZE]> for Commitment {
@@ -160,6 +197,21 @@ impl PartialSignature {
     pub fn as_bytes(&self) -> &[u8; PartialSignature::SIZE] {
         self.0.as_bytes()
     }
+
+    #[inline]
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.as_bytes())
+    }
+}
+
+impl FromStr for PartialSignature {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(PartialSignature::from(from_hex_array::<
+            { PartialSignature::SIZE },
+        >(s)?))
+    }
 }
 
 impl From<[u8; PartialSignature::SIZE]> for PartialSignature {
@@ -267,6 +319,17 @@ impl PublicKey {
     }
 }
 
+/// Aggregates the given public keys into the public key that verifies the signatures created
+/// jointly by the owners of the keys. The keys have to be in the same order that the signers used.
+pub fn aggregate_public_keys(public_keys: &[PublicKey]) -> PublicKey {
+    let public_keys_hash = hash_public_keys(public_keys);
+    let delinearized_pk_sum: EdwardsPoint = public_keys
+        .iter()
+        .map(|public_key| public_key.delinearize(&public_keys_hash))
+        .sum();
+    PublicKey::from(delinearized_pk_sum.compress().to_bytes())
+}
+
 pub fn hash_public_keys(public_keys: &[PublicKey]) -> [u8; 64] {
     // 1. Compute hash over public keys public_keys_hash = C = H(P_1 || ... || P_n).
     let mut h: sha2::Sha512 = sha2::Sha512::default();
@@ -296,3 +359,41 @@ impl ToScalar for ::ed25519_zebra::SigningKey {
         Scalar::from_bits(scalar_bytes)
     }
 }
+
+#[cfg(feature = "serde-derive")]
+mod serde_derive {
+    use std::borrow::Cow;
+
+    use serde::{
+        de::{Deserialize, Deserializer, Error},
+        ser::{Serialize, Serializer},
+    };
+
+    use super::{Commitment, PartialSignature};
+
+    macro_rules! implement_hex_serde {
+        ($name: ident) => {
+            impl Serialize for $name {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    serializer.serialize_str(&self.to_hex())
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $name {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    let data: Cow<'de, str> = Deserialize::deserialize(deserializer)?;
+                    data.parse().map_err(Error::custom)
+                }
+            }
+        };
+    }
+
+    implement_hex_serde!(Commitment);
+    implement_hex_serde!(PartialSignature);
+}
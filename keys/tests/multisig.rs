@@ -370,6 +370,31 @@ fn it_can_aggregate_public_keys() {
     }
 }
 
+#[test]
+fn it_can_aggregate_public_keys_from_the_original_keys() {
+    for vector in VECTORS.iter() {
+        let test = TestVector::from_str(vector);
+
+        assert_eq!(aggregate_public_keys(&test.pub_keys), test.agg_pub_key);
+    }
+}
+
+#[test]
+fn it_can_parse_multisig_values_from_hex() {
+    for vector in VECTORS.iter() {
+        let test = TestVector::from_str(vector);
+
+        let commitment: Commitment = test.agg_commitment.to_hex().parse().unwrap();
+        assert_eq!(commitment, test.agg_commitment);
+
+        let partial_signature: PartialSignature = test.agg_signature.to_hex().parse().unwrap();
+        assert_eq!(partial_signature, test.agg_signature);
+
+        let secret: RandomSecret = test.secrets[0].to_hex().parse().unwrap();
+        assert_eq!(secret, test.secrets[0]);
+    }
+}
+
 #[test]
 fn it_can_finalize_signatures() {
     for vector in VECTORS.iter() {
@@ -84,22 +84,26 @@ pub(crate) struct ClientInner {
     zkp_component: ZKPComponentProxy,
 }
 
-/// This function is used to generate the services flags (provided, needed) based upon the configured sync mode
-pub fn generate_service_flags(sync_mode: SyncMode) -> (Services, Services) {
-    let provided_services = match sync_mode {
+/// This function is used to generate the services flags (provided, needed) based upon the configured sync mode.
+/// Accounts chunks are only provided if the node keeps a snapshot of the accounts, since chunks are
+/// served from that snapshot.
+pub fn generate_service_flags(
+    sync_mode: SyncMode,
+    keep_accounts_snapshot: bool,
+) -> (Services, Services) {
+    let mut provided_services = match sync_mode {
         // Services provided by history nodes
         crate::config::config::SyncMode::History => {
             log::info!("Client configured as a history node");
             Services::HISTORY
                 | Services::FULL_BLOCKS
                 | Services::ACCOUNTS_PROOF
-                | Services::ACCOUNTS_CHUNKS
                 | Services::TRANSACTION_INDEX
         }
         // Services provided by full nodes
         crate::config::config::SyncMode::Full => {
             log::info!("Client configured as a full node");
-            Services::ACCOUNTS_PROOF | Services::FULL_BLOCKS | Services::ACCOUNTS_CHUNKS
+            Services::ACCOUNTS_PROOF | Services::FULL_BLOCKS
         }
         // Services provided by light nodes
         crate::config::config::SyncMode::Light => {
@@ -107,6 +111,9 @@ pub fn generate_service_flags(sync_mode: SyncMode) -> (Services, Services) {
             Services::empty()
         }
     };
+    if keep_accounts_snapshot && sync_mode != SyncMode::Light {
+        provided_services |= Services::ACCOUNTS_CHUNKS;
+    }
 
     let required_services = match sync_mode {
         // Services required by history nodes
@@ -167,8 +174,10 @@ impl ClientInner {
             identity_keypair.public().to_peer_id().to_base58()
         );
 
-        let (provided_services, required_services) =
-            generate_service_flags(config.consensus.sync_mode);
+        let (provided_services, required_services) = generate_service_flags(
+            config.consensus.sync_mode,
+            config.consensus.keep_accounts_snapshot,
+        );
 
         // Generate my peer contact from identity keypair and my provided services
         let mut peer_contact = PeerContact::new(
@@ -187,8 +196,15 @@ impl ClientInner {
             .map(|seed| seed.address)
             .collect();
 
+        // Open database
+        let environment = config.storage.database(
+            config.network_id,
+            config.consensus.sync_mode,
+            config.database,
+        )?;
+
         // Setup libp2p network
-        let network_config = NetworkConfig::new(
+        let mut network_config = NetworkConfig::new(
             identity_keypair,
             peer_contact,
             seeds,
@@ -196,6 +212,10 @@ impl ClientInner {
             false,
             required_services,
         );
+        network_config.ban_list_path = config.network.ban_list_path.clone();
+        network_config.rate_limits = config.network.rate_limits.clone();
+        network_config.peer_byte_budget = config.network.peer_byte_budget;
+        network_config.peer_contacts_env = Some(environment.clone());
 
         log::debug!("listen_addresses = {:?}", config.network.listen_addresses);
 
@@ -204,19 +224,14 @@ impl ClientInner {
         // Start buffering network events as early as possible
         let network_events = network.subscribe_events();
 
-        // Open database
-        let environment = config.storage.database(
-            config.network_id,
-            config.consensus.sync_mode,
-            config.database,
-        )?;
-
         let bls_cache = Arc::new(Mutex::new(PublicKeyCache::new(
             Policy::BLS_CACHE_MAX_CAPACITY,
         )));
 
         let mut blockchain_config = BlockchainConfig {
             max_epochs_stored: config.consensus.max_epochs_stored,
+            keep_accounts_snapshot: config.consensus.keep_accounts_snapshot,
+            max_accounts_snapshots: config.consensus.max_accounts_snapshots,
             ..Default::default()
         };
 
@@ -251,9 +266,42 @@ impl ClientInner {
                 .await;
                 (blockchain_proxy, syncer, zkp_component)
             }
-            SyncMode::Full => todo!(),
+            SyncMode::Full => {
+                blockchain_config.keep_history = false;
+                let blockchain = Arc::new(RwLock::new(
+                    Blockchain::new(
+                        environment.clone(),
+                        blockchain_config,
+                        config.network_id,
+                        time,
+                    )
+                    .unwrap(),
+                ));
+                let blockchain_proxy = BlockchainProxy::from(&blockchain);
+                let zkp_component = ZKPComponent::new(
+                    blockchain_proxy.clone(),
+                    Arc::clone(&network),
+                    config.zkp.prover_active,
+                    None,
+                    environment.clone(),
+                    config.zkp.setup_keys_path,
+                )
+                .await;
+                let syncer = SyncerProxy::new_full(
+                    blockchain_proxy.clone(),
+                    Arc::clone(&network),
+                    bls_cache,
+                    Arc::new(zkp_component.proxy()),
+                    network_events,
+                )
+                .await;
+                (blockchain_proxy, syncer, zkp_component)
+            }
             SyncMode::Light => {
-                let blockchain = Arc::new(RwLock::new(LightBlockchain::new(config.network_id)));
+                let blockchain = Arc::new(RwLock::new(LightBlockchain::with_env(
+                    environment.clone(),
+                    config.network_id,
+                )));
                 let blockchain_proxy = BlockchainProxy::from(&blockchain);
                 let zkp_component = ZKPComponent::new(
                     blockchain_proxy.clone(),
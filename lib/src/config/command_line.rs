@@ -65,6 +65,16 @@ pub struct CommandLine {
     #[clap(long)]
     pub network: Option<NetworkId>,
 
+    /// Accept WebSocket connections to the RPC server, which are needed for the subscription
+    /// methods. The RPC server must be configured in the configuration file.
+    ///
+    /// # Examples
+    ///
+    /// * `nimiq-client --rpc-websocket`
+    ///
+    #[clap(long)]
+    pub rpc_websocket: bool,
+
     /// Internally used flag to start a zero-knowledge prover process.
     #[clap(long, action)]
     pub prove: bool,
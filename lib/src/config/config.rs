@@ -1,10 +1,14 @@
+#[cfg(feature = "rpc-server")]
+use std::collections::HashSet;
 #[cfg(any(feature = "rpc-server", feature = "metrics-server"))]
 use std::net::IpAddr;
 #[cfg(feature = "metrics-server")]
 use std::net::SocketAddr;
 use std::{
+    collections::HashMap,
     path::{Path, PathBuf},
     string::ToString,
+    time::Duration,
 };
 
 use derive_builder::Builder;
@@ -13,15 +17,19 @@ use strum_macros::Display;
 use beserial::Deserialize;
 #[cfg(feature = "validator")]
 use nimiq_bls::{KeyPair as BlsKeyPair, SecretKey as BlsSecretKey};
+use nimiq_consensus::messages::request_type_names as consensus_request_type_names;
 use nimiq_database::{mdbx::MdbxEnvironment, volatile::VolatileEnvironment, Environment};
 #[cfg(feature = "validator")]
 use nimiq_keys::{Address, KeyPair, PrivateKey};
 use nimiq_mempool::{config::MempoolConfig, filter::MempoolRules};
-use nimiq_network_libp2p::{Keypair as IdentityKeypair, Multiaddr};
+use nimiq_network_libp2p::{
+    ByteBudgetConfig, Keypair as IdentityKeypair, Multiaddr, RateLimitConfig,
+};
 use nimiq_primitives::{networks::NetworkId, policy::Policy};
 use nimiq_utils::file_store::FileStore;
 #[cfg(feature = "validator")]
 use nimiq_utils::key_rng::SecureGenerate;
+use nimiq_zkp_component::types::request_type_names as zkp_request_type_names;
 
 #[cfg(any(feature = "rpc-server", feature = "metrics-server"))]
 use crate::config::consts;
@@ -52,7 +60,7 @@ use crate::{
 pub enum SyncMode {
     /// History nodes: They use HistoryMacroSync + BlockLiveSync
     History,
-    /// Full nodes: They use LightMacroSync + StateLiveSync
+    /// Full nodes: They use StateMacroSync (LightMacroSync + state sync) + BlockLiveSync
     Full,
     /// Light nodes: They use LightMacroSync + BlockLiveSync
     Light,
@@ -77,6 +85,12 @@ pub struct ConsensusConfig {
     #[builder(default = "1")]
     /// Maximum number of epochs that are stored in the client
     pub max_epochs_stored: u32,
+    #[builder(default)]
+    /// Keep snapshots of the accounts trie at the latest election blocks
+    pub keep_accounts_snapshot: bool,
+    #[builder(default = "2")]
+    /// Maximum number of election blocks at which snapshots of the accounts trie are kept
+    pub max_accounts_snapshots: usize,
 }
 
 impl Default for ConsensusConfig {
@@ -85,6 +99,8 @@ impl Default for ConsensusConfig {
             sync_mode: SyncMode::default(),
             min_peers: 3,
             max_epochs_stored: Policy::MIN_EPOCHS_STORED,
+            keep_accounts_snapshot: false,
+            max_accounts_snapshots: 2,
         }
     }
 }
@@ -108,6 +124,24 @@ pub struct NetworkConfig {
 
     #[builder(default)]
     pub seeds: Vec<Seed>,
+
+    /// If specified, the list of banned IP addresses and subnets is persisted to this file, such
+    /// that bans survive restarts.
+    ///
+    #[builder(default)]
+    pub ban_list_path: Option<PathBuf>,
+
+    /// Overrides the rate limits of request types, keyed by the name of the request type, e.g.
+    /// `RequestHistoryChunk`.
+    ///
+    #[builder(default)]
+    pub rate_limits: HashMap<String, RateLimitConfig>,
+
+    /// If specified, limits the number of request bytes that each peer may send within a time
+    /// window, across all request types.
+    ///
+    #[builder(default)]
+    pub peer_byte_budget: Option<ByteBudgetConfig>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -198,8 +232,8 @@ pub struct DatabaseConfig {
     #[builder(default = "1024 * 1024 * 1024 * 1024")]
     size: usize,
 
-    /// Max number of DBs. Recommended: 13
-    #[builder(default = "13")]
+    /// Max number of DBs. Recommended: 30
+    #[builder(default = "30")]
     max_dbs: u32,
 
     /// Max number of threads that can open read transactions.
@@ -215,7 +249,7 @@ impl Default for DatabaseConfig {
         Self {
             // 1 TB
             size: 1024 * 1024 * 1024 * 1024,
-            max_dbs: 13,
+            max_dbs: 30,
             max_readers: 600,
         }
     }
@@ -502,7 +536,9 @@ pub struct RpcServerConfig {
     #[builder(default = "consts::RPC_DEFAULT_PORT")]
     pub port: u16,
 
-    /// TODO
+    /// If specified, allow cross-origin requests from these domains. This also restricts the
+    /// origins from which WebSocket connections are accepted.
+    ///
     #[builder(setter(strip_option))]
     pub corsdomain: Option<Vec<String>>,
 
@@ -519,6 +555,202 @@ pub struct RpcServerConfig {
     /// If specified, require HTTP basic auth with these credentials
     #[builder(setter(strip_option))]
     pub credentials: Option<Credentials>,
+
+    /// Accept WebSocket connections, which are needed for the subscription methods.
+    ///
+    /// Default: `false`
+    ///
+    #[builder(default)]
+    pub enable_websocket: bool,
+
+    /// Additional users with their own credentials, each restricted to the methods of its role.
+    ///
+    #[builder(default)]
+    pub users: Vec<RpcUserConfig>,
+}
+
+/// A user of the RPC server that is authorized to call the methods of a role.
+#[cfg(feature = "rpc-server")]
+#[derive(Debug, Clone)]
+pub struct RpcUserConfig {
+    /// The credentials of the user.
+    pub credentials: Credentials,
+
+    /// The name of the role of the user.
+    pub role: String,
+
+    /// The RPC methods that the user is allowed to call.
+    pub allowed_methods: Vec<String>,
+}
+
+/// The RPC methods that can be called by all built-in roles. These only read the state of the
+/// node. Subscriptions are left out, since they need a WebSocket connection, which users with a
+/// role can't open.
+#[cfg(feature = "rpc-server")]
+const RPC_READ_ONLY_METHODS: &[&str] = &[
+    // Blockchain
+    "getBlockNumber",
+    "getBatchNumber",
+    "getEpochNumber",
+    "getBlockByHash",
+    "getBlockByNumber",
+    "getLatestBlock",
+    "getSlotAt",
+    "getTransactionByHash",
+    "getTransactionsByBlockNumber",
+    "getInherentsByBlockNumber",
+    "getTransactionsByBatchNumber",
+    "getInherentsByBatchNumber",
+    "getTransactionHashesByAddress",
+    "getTransactionsByAddress",
+    "getAccountByAddress",
+    "listAccountsByType",
+    "getActiveValidators",
+    "getCurrentSlashedSlots",
+    "getPreviousSlashedSlots",
+    "getParkedValidators",
+    "getValidatorByAddress",
+    "getStakerByAddress",
+    "listStakersByValidator",
+    // Consensus
+    "isConsensusEstablished",
+    "getRawTransactionInfo",
+    // Mempool
+    "mempoolContent",
+    "mempool",
+    "getMinFeePerByte",
+    "estimateFeePerByte",
+    // Network
+    "getPeerId",
+    "getPeerCount",
+    "getPeerList",
+    "getPeerInfo",
+    "getPeerInfoList",
+    // Policy
+    "getPolicyConstants",
+    "getEpochAt",
+    "getEpochIndexAt",
+    "getBatchAt",
+    "getBatchIndexAt",
+    "getElectionBlockAfter",
+    "getElectionBlockBefore",
+    "getLastElectionBlock",
+    "getIsElectionBlockAt",
+    "getMacroBlockAfter",
+    "getMacroBlockBefore",
+    "getLastMacroBlock",
+    "getIsMacroBlockAt",
+    "getIsMicroBlockAt",
+    "getFirstBlockOf",
+    "getFirstBlockOfBatch",
+    "getElectionBlockOf",
+    "getMacroBlockOf",
+    "getFirstBatchOfEpoch",
+    "getSupplyAt",
+    // ZKP component
+    "getZkpState",
+];
+
+/// The RPC methods that can be called by the built-in `wallet` role, in addition to the read-only
+/// methods.
+#[cfg(feature = "rpc-server")]
+const RPC_WALLET_METHODS: &[&str] = &[
+    // Consensus
+    "sendRawTransaction",
+    "createBasicTransaction",
+    "sendBasicTransaction",
+    "createMultisigBasicTransaction",
+    "createBasicTransactionWithData",
+    "sendBasicTransactionWithData",
+    "createNewVestingTransaction",
+    "sendNewVestingTransaction",
+    "createRedeemVestingTransaction",
+    "sendRedeemVestingTransaction",
+    "createNewHtlcTransaction",
+    "sendNewHtlcTransaction",
+    "createRedeemRegularHtlcTransaction",
+    "sendRedeemRegularHtlcTransaction",
+    "createRedeemTimeoutHtlcTransaction",
+    "sendRedeemTimeoutHtlcTransaction",
+    "createRedeemEarlyHtlcTransaction",
+    "sendRedeemEarlyHtlcTransaction",
+    "signRedeemEarlyHtlcTransaction",
+    "createNewStakerTransaction",
+    "sendNewStakerTransaction",
+    "createStakeTransaction",
+    "sendStakeTransaction",
+    "createUpdateStakerTransaction",
+    "sendUpdateStakerTransaction",
+    "createUnstakeTransaction",
+    "sendUnstakeTransaction",
+    "createNewValidatorTransaction",
+    "sendNewValidatorTransaction",
+    "createUpdateValidatorTransaction",
+    "sendUpdateValidatorTransaction",
+    "createInactivateValidatorTransaction",
+    "sendInactivateValidatorTransaction",
+    "createReactivateValidatorTransaction",
+    "sendReactivateValidatorTransaction",
+    "createUnparkValidatorTransaction",
+    "sendUnparkValidatorTransaction",
+    "createDeleteValidatorTransaction",
+    "sendDeleteValidatorTransaction",
+    // Mempool
+    "pushTransaction",
+    "pushHighPriorityTransaction",
+    // Wallet
+    "importRawKey",
+    "isAccountImported",
+    "listAccounts",
+    "lockAccount",
+    "createAccount",
+    "unlockAccount",
+    "isAccountUnlocked",
+    "sign",
+    "verifySignature",
+    "createMultisigAddress",
+    "createMultisigCommitment",
+    "createMultisigPartialSignature",
+    "aggregateMultisigSignatures",
+    "createHdWallet",
+    "importHdWallet",
+    "listHdWallets",
+    "listHdWalletAccounts",
+    "deriveHdAccount",
+    "deriveHdAccountByIndex",
+    "exportAccounts",
+    "importAccounts",
+];
+
+/// The RPC methods that can be called by the built-in `validator-admin` role, in addition to the
+/// read-only methods.
+#[cfg(feature = "rpc-server")]
+const RPC_VALIDATOR_ADMIN_METHODS: &[&str] = &[
+    "getAddress",
+    "getSigningKey",
+    "getVotingKey",
+    "setAutomaticReactivation",
+    "exportSlashingProtection",
+    "importSlashingProtection",
+];
+
+/// Returns the RPC methods of the built-in role with the given name, if it exists.
+#[cfg(feature = "rpc-server")]
+fn builtin_rpc_role_methods(role: &str) -> Option<Vec<String>> {
+    let methods: &[&str] = match role {
+        "read-only" => &[],
+        "wallet" => RPC_WALLET_METHODS,
+        "validator-admin" => RPC_VALIDATOR_ADMIN_METHODS,
+        _ => return None,
+    };
+
+    Some(
+        RPC_READ_ONLY_METHODS
+            .iter()
+            .chain(methods.iter())
+            .map(|method| method.to_string())
+            .collect(),
+    )
 }
 
 #[cfg(feature = "metrics-server")]
@@ -675,6 +907,23 @@ impl ClientConfigBuilder {
 
     /// Applies settings from a configuration file
     pub fn config_file(&mut self, config_file: &ConfigFile) -> Result<&mut Self, Error> {
+        // Rate limits can only be configured for the request types that the client handles.
+        let request_types: Vec<_> = consensus_request_type_names()
+            .into_iter()
+            .chain(zkp_request_type_names())
+            .collect();
+        if let Some(request_type) = config_file
+            .network
+            .rate_limits
+            .keys()
+            .find(|request_type| !request_types.contains(&request_type.as_str()))
+        {
+            return Err(Error::config_error(format!(
+                "Unknown request type in rate limits: {}",
+                request_type
+            )));
+        }
+
         // TODO: if the config field of `listen_addresses` is empty, we should at least add `/ip4/127.0.0.1/...`
         self.network(NetworkConfig {
             listen_addresses: config_file
@@ -692,16 +941,48 @@ impl ClientConfigBuilder {
                 .unwrap_or_default(),
 
             seeds: config_file.network.seed_nodes.clone(),
+
+            ban_list_path: config_file
+                .network
+                .ban_list_file
+                .as_ref()
+                .map(PathBuf::from),
+
+            rate_limits: config_file
+                .network
+                .rate_limits
+                .iter()
+                .map(|(request_type, rate_limit)| {
+                    (
+                        request_type.clone(),
+                        RateLimitConfig {
+                            max_requests: rate_limit.max_requests,
+                            time_window: Duration::from_secs(rate_limit.time_window),
+                        },
+                    )
+                })
+                .collect(),
+
+            peer_byte_budget: config_file.network.peer_byte_budget.as_ref().map(|budget| {
+                ByteBudgetConfig {
+                    max_bytes: budget.max_bytes,
+                    time_window: Duration::from_secs(budget.time_window),
+                }
+            }),
         });
 
         // Configure consensus
         let mut consensus = ConsensusConfigBuilder::default()
             .sync_mode(config_file.consensus.sync_mode)
+            .keep_accounts_snapshot(config_file.consensus.keep_accounts_snapshot)
             .build()
             .unwrap();
         if let Some(min_peers) = config_file.consensus.min_peers {
             consensus.min_peers = min_peers;
         }
+        if let Some(max_accounts_snapshots) = config_file.consensus.max_accounts_snapshots {
+            consensus.max_accounts_snapshots = max_accounts_snapshots;
+        }
         self.consensus(consensus);
 
         // Configure network
@@ -804,13 +1085,55 @@ impl ClientConfigBuilder {
                     }
                 };
 
+                // Otherwise, the users could bypass their roles by sending requests without
+                // credentials.
+                if credentials.is_none() && !rpc_config.users.is_empty() {
+                    return Err(Error::config_error(
+                        "RPC: Username and password have to be set if there are users.",
+                    ));
+                }
+
+                let port = rpc_config.port.unwrap_or(consts::RPC_DEFAULT_PORT);
+
+                let mut usernames: HashSet<&str> = credentials
+                    .iter()
+                    .map(|credentials| credentials.username.as_str())
+                    .collect();
+                let mut users = Vec::with_capacity(rpc_config.users.len());
+                for user in rpc_config.users.iter() {
+                    let allowed_methods = match rpc_config.roles.get(&user.role) {
+                        Some(methods) => methods.clone(),
+                        None => builtin_rpc_role_methods(&user.role).ok_or_else(|| {
+                            Error::config_error(format!(
+                                "RPC: Unknown role {} of user {}",
+                                user.role, user.username
+                            ))
+                        })?,
+                    };
+
+                    if !usernames.insert(user.username.as_str()) {
+                        return Err(Error::config_error(format!(
+                            "RPC: User {} is declared more than once",
+                            user.username
+                        )));
+                    }
+
+                    users.push(RpcUserConfig {
+                        credentials: Credentials::new(user.username.clone(), user.password.clone()),
+                        role: user.role.clone(),
+                        allowed_methods,
+                    });
+                }
+
                 self.rpc_server = Some(Some(RpcServerConfig {
                     bind_to,
-                    port: rpc_config.port.unwrap_or(consts::RPC_DEFAULT_PORT),
+                    port,
                     corsdomain: Some(rpc_config.corsdomain.clone()),
                     allow_ips,
                     allowed_methods: Some(rpc_config.methods.clone()),
                     credentials,
+                    enable_websocket: rpc_config.websocket,
+                    users,
                 }));
             }
         }
@@ -867,7 +1190,19 @@ impl ClientConfigBuilder {
             self.network_id(network_id);
         }
 
-        // NOTE: We're always return `Ok(_)`, but we might want to introduce errors later.
+        // Enable WebSocket connections to the RPC server
+        #[cfg(feature = "rpc-server")]
+        if command_line.rpc_websocket {
+            match &mut self.rpc_server {
+                Some(Some(rpc_server)) => rpc_server.enable_websocket = true,
+                _ => {
+                    return Err(Error::config_error(
+                        "RPC: WebSocket can only be enabled if the RPC server is configured.",
+                    ))
+                }
+            }
+        }
+
         Ok(self)
     }
 }
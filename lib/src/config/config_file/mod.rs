@@ -136,6 +136,30 @@ pub struct NetworkSettings {
 
     pub tls: Option<TlsSettings>,
     pub instant_inbound: Option<bool>,
+
+    #[serde(default)]
+    pub ban_list_file: Option<String>,
+
+    #[serde(default)]
+    pub rate_limits: HashMap<String, RateLimitSettings>,
+    #[serde(default)]
+    pub peer_byte_budget: Option<PeerByteBudgetSettings>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitSettings {
+    pub max_requests: u32,
+    /// The time window in seconds.
+    pub time_window: u64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PeerByteBudgetSettings {
+    pub max_bytes: u64,
+    /// The time window in seconds.
+    pub time_window: u64,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -165,6 +189,11 @@ pub struct ConsensusSettings {
     pub network: Network,
     /// Minimum number of peers necessary to reach consensus
     pub min_peers: Option<usize>,
+    #[serde(default)]
+    /// Keep snapshots of the accounts trie at the latest election blocks
+    pub keep_accounts_snapshot: bool,
+    /// Maximum number of election blocks at which snapshots of the accounts trie are kept
+    pub max_accounts_snapshots: Option<usize>,
 }
 
 #[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
@@ -273,6 +302,20 @@ pub struct RpcServerSettings {
     pub methods: Vec<String>,
     pub username: Option<String>,
     pub password: Option<String>,
+    #[serde(default)]
+    pub websocket: bool,
+    #[serde(default)]
+    pub roles: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub users: Vec<RpcUserSettings>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RpcUserSettings {
+    pub username: String,
+    pub password: String,
+    pub role: String,
 }
 
 #[derive(Clone, Debug, Deserialize, Default)]
@@ -381,6 +424,8 @@ pub struct MempoolSettings {
     pub size_limit: Option<usize>,
     pub control_size_limit: Option<usize>,
     pub blacklist_limit: Option<usize>,
+    pub persistent: Option<bool>,
+    pub replacement_fee_bump: Option<u32>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -432,6 +477,8 @@ impl From<MempoolSettings> for MempoolConfig {
                 .blacklist_limit
                 .unwrap_or(MempoolFilter::DEFAULT_BLACKLIST_SIZE),
             filter_rules: mempool.filter.map(MempoolRules::from).unwrap_or_default(),
+            persistent: mempool.persistent.unwrap_or(false),
+            replacement_fee_bump: mempool.replacement_fee_bump,
         }
     }
 }
@@ -5,15 +5,21 @@ use nimiq_blockchain_proxy::BlockchainProxy;
 use nimiq_consensus::ConsensusProxy;
 use nimiq_mempool::mempool::Mempool;
 use nimiq_network_interface::network::Network;
+use nimiq_validator::validator::ValidatorProxy;
 
 pub use nimiq_metrics_server::NimiqTaskMonitor;
 
+// Ignoring clippy warning because there wouldn't be much to be gained by refactoring this,
+// except making clippy happy
+#[allow(clippy::too_many_arguments)]
 pub fn start_metrics_server<TNetwork: Network>(
     addr: SocketAddr,
     blockchain_proxy: BlockchainProxy,
     mempool: Option<Arc<Mempool>>,
     consensus_proxy: ConsensusProxy<TNetwork>,
     network: Arc<nimiq_network_libp2p::Network>,
+    validator: Option<ValidatorProxy>,
+    min_peers: usize,
     task_monitors: &[NimiqTaskMonitor],
 ) {
     nimiq_metrics_server::start_metrics_server(
@@ -22,6 +28,8 @@ pub fn start_metrics_server<TNetwork: Network>(
         mempool,
         consensus_proxy,
         network,
+        validator,
+        min_peers,
         task_monitors,
     );
 }
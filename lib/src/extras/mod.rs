@@ -7,6 +7,8 @@ pub mod metrics_server;
 #[cfg(feature = "panic")]
 pub mod panic;
 #[cfg(feature = "rpc-server")]
+pub mod rpc_access;
+#[cfg(feature = "rpc-server")]
 pub mod rpc_server;
 #[cfg(feature = "signal-handling")]
 pub mod signal_handling;
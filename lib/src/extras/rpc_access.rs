@@ -0,0 +1,411 @@
+use std::{
+    collections::HashSet,
+    convert::Infallible,
+    io,
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener},
+    sync::Arc,
+};
+
+use hyper::{
+    body::HttpBody,
+    client::HttpConnector,
+    header::{self, HeaderMap, HeaderValue},
+    server::conn::AddrStream,
+    service::{make_service_fn, service_fn},
+    Body, Client, Method, Request, Response, Server, StatusCode, Uri,
+};
+use rand::{distributions::Alphanumeric, thread_rng, Rng};
+use serde_json::Value;
+
+use crate::config::config::{Credentials, RpcUserConfig};
+
+/// Returns the value of the `Authorization` header for HTTP basic auth with the given credentials.
+pub fn basic_auth(credentials: &Credentials) -> String {
+    format!(
+        "Basic {}",
+        base64::encode(format!("{}:{}", credentials.username, credentials.password))
+    )
+}
+
+/// Generates random credentials, which are used to protect the jsonrpc server behind the proxy
+/// from other local processes.
+pub fn random_credentials() -> Credentials {
+    let random_string = || {
+        thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect::<String>()
+    };
+    Credentials::new(random_string(), random_string())
+}
+
+/// The maximum size of a request body that the proxy reads to check the called methods.
+pub const MAX_REQUEST_BODY_SIZE: usize = 1024 * 1024;
+
+/// Reserves an unused port on the loopback interface. The port stays reserved until the returned
+/// listener is dropped.
+pub fn reserve_loopback_port() -> io::Result<TcpListener> {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+}
+
+/// The RPC methods that a user is allowed to call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AllowedMethods {
+    All,
+    Only(HashSet<String>),
+}
+
+impl AllowedMethods {
+    pub fn allows(&self, method: &str) -> bool {
+        match self {
+            AllowedMethods::All => true,
+            AllowedMethods::Only(methods) => methods.contains(method),
+        }
+    }
+
+    /// Returns the methods called by a JSON-RPC request or batch that are not allowed. Bodies that
+    /// can't be parsed are left to the jsonrpc server, which rejects them.
+    pub fn denied_methods(&self, body: &[u8]) -> Vec<String> {
+        if let AllowedMethods::All = self {
+            return vec![];
+        }
+
+        let requests = match serde_json::from_slice::<Value>(body) {
+            Ok(Value::Array(requests)) => requests,
+            Ok(request) => vec![request],
+            Err(_) => return vec![],
+        };
+
+        requests
+            .iter()
+            .filter_map(|request| request.get("method"))
+            .map(|method| match method {
+                Value::String(method) => method.clone(),
+                method => method.to_string(),
+            })
+            .filter(|method| !self.allows(method))
+            .collect()
+    }
+}
+
+/// A user of the RPC server. Requests without credentials are made by the user without
+/// credentials, if there is one.
+#[derive(Clone, Debug)]
+pub struct RpcUser {
+    pub credentials: Option<Credentials>,
+    pub allowed_methods: AllowedMethods,
+}
+
+impl RpcUser {
+    /// Returns the users of the RPC server: the main user, who can call the `allowed_methods`, and
+    /// the configured `users`, who can only call the methods of their roles. Without credentials,
+    /// the main user would serve every request without credentials. It is left out in that case if
+    /// there are other users, since they could otherwise bypass their roles by dropping their
+    /// credentials.
+    pub fn from_config(
+        credentials: Option<Credentials>,
+        allowed_methods: AllowedMethods,
+        users: Vec<RpcUserConfig>,
+    ) -> Vec<RpcUser> {
+        let mut rpc_users = Vec::with_capacity(users.len() + 1);
+        if credentials.is_some() || users.is_empty() {
+            rpc_users.push(RpcUser {
+                credentials,
+                allowed_methods,
+            });
+        }
+
+        for user in users {
+            log::info!(
+                username = %user.credentials.username,
+                role = %user.role,
+                "Adding RPC user",
+            );
+
+            rpc_users.push(RpcUser {
+                credentials: Some(user.credentials),
+                allowed_methods: AllowedMethods::Only(HashSet::from_iter(user.allowed_methods)),
+            });
+        }
+
+        rpc_users
+    }
+}
+
+/// The access rules of the RPC server.
+#[derive(Clone, Debug, Default)]
+pub struct RpcAccessControl {
+    /// The users that are allowed to access the RPC server.
+    pub users: Vec<RpcUser>,
+    /// The origins from which cross-origin requests are allowed. `*` allows all origins.
+    pub cors_origins: Vec<String>,
+    /// If specified, only connections from these IP addresses are accepted.
+    pub allow_ips: Option<HashSet<IpAddr>>,
+}
+
+impl RpcAccessControl {
+    pub fn is_ip_allowed(&self, ip: IpAddr) -> bool {
+        self.allow_ips
+            .as_ref()
+            .map(|allow_ips| allow_ips.contains(&ip))
+            .unwrap_or(true)
+    }
+
+    pub fn is_origin_allowed(&self, origin: &str) -> bool {
+        self.cors_origins
+            .iter()
+            .any(|allowed| allowed == "*" || allowed == origin)
+    }
+
+    /// Identifies the user of a request by its `Authorization` header and returns the methods
+    /// that the user is allowed to call. Returns `None` if the request is not authorized.
+    pub fn authorize(&self, headers: &HeaderMap) -> Option<&AllowedMethods> {
+        let authorization = match headers.get(header::AUTHORIZATION) {
+            Some(authorization) => authorization,
+            None => {
+                return self
+                    .users
+                    .iter()
+                    .find(|user| user.credentials.is_none())
+                    .map(|user| &user.allowed_methods)
+            }
+        };
+
+        let decoded = authorization
+            .to_str()
+            .ok()?
+            .strip_prefix("Basic ")
+            .and_then(|encoded| base64::decode(encoded.trim()).ok())
+            .and_then(|decoded| String::from_utf8(decoded).ok())?;
+        let (username, password) = decoded.split_once(':')?;
+
+        self.users
+            .iter()
+            .find(|user| {
+                user.credentials
+                    .as_ref()
+                    .map(|credentials| credentials.check(username, password))
+                    .unwrap_or(false)
+            })
+            .map(|user| &user.allowed_methods)
+    }
+}
+
+/// The jsonrpc server supports neither cross-origin requests nor more than a single set of
+/// credentials. Therefore, it listens on the loopback interface only and is served publicly by
+/// this proxy, which enforces the access rules and forwards the requests.
+pub struct RpcProxy {
+    /// The public address of the RPC server.
+    pub bind_to: SocketAddr,
+    /// The address of the jsonrpc server.
+    pub backend: SocketAddr,
+    /// The credentials of the jsonrpc server.
+    pub backend_credentials: Credentials,
+    pub access: RpcAccessControl,
+}
+
+impl RpcProxy {
+    pub async fn run(self) {
+        let state = Arc::new(ProxyState {
+            backend: self.backend,
+            backend_authorization: HeaderValue::from_str(&basic_auth(&self.backend_credentials))
+                .expect("Invalid backend credentials"),
+            access: self.access,
+            client: Client::new(),
+        });
+
+        let make_service = make_service_fn(move |connection: &AddrStream| {
+            let state = Arc::clone(&state);
+            let remote_ip = connection.remote_addr().ip();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let state = Arc::clone(&state);
+                    async move { Ok::<_, Infallible>(state.handle(remote_ip, request).await) }
+                }))
+            }
+        });
+
+        let server = match Server::try_bind(&self.bind_to) {
+            Ok(builder) => builder.serve(make_service),
+            Err(error) => {
+                log::error!(bind_to = %self.bind_to, %error, "Failed to bind RPC server");
+                return;
+            }
+        };
+        if let Err(error) = server.await {
+            log::error!(%error, "RPC server error");
+        }
+    }
+}
+
+struct ProxyState {
+    backend: SocketAddr,
+    backend_authorization: HeaderValue,
+    access: RpcAccessControl,
+    client: Client<HttpConnector>,
+}
+
+impl ProxyState {
+    async fn handle(&self, remote_ip: IpAddr, mut request: Request<Body>) -> Response<Body> {
+        if !self.access.is_ip_allowed(remote_ip) {
+            return status_response(StatusCode::FORBIDDEN);
+        }
+
+        // Requests from browsers carry their origin, which must be allowed.
+        let origin = request.headers().get(header::ORIGIN).cloned();
+        if let Some(ref origin) = origin {
+            let is_allowed = origin
+                .to_str()
+                .map(|origin| self.access.is_origin_allowed(origin))
+                .unwrap_or(false);
+            if !is_allowed {
+                return status_response(StatusCode::FORBIDDEN);
+            }
+        }
+
+        // Preflight requests don't carry credentials.
+        if request.method() == Method::OPTIONS {
+            let mut response = status_response(StatusCode::NO_CONTENT);
+            if let Some(origin) = origin {
+                let headers = response.headers_mut();
+                add_cors_headers(headers, origin);
+                headers.insert(
+                    header::ACCESS_CONTROL_ALLOW_METHODS,
+                    HeaderValue::from_static("GET, POST, OPTIONS"),
+                );
+                headers.insert(
+                    header::ACCESS_CONTROL_ALLOW_HEADERS,
+                    HeaderValue::from_static("authorization, content-type"),
+                );
+            }
+            return response;
+        }
+
+        let allowed_methods = match self.access.authorize(request.headers()) {
+            Some(allowed_methods) => allowed_methods,
+            None => {
+                let mut response = status_response(StatusCode::UNAUTHORIZED);
+                response.headers_mut().insert(
+                    header::WWW_AUTHENTICATE,
+                    HeaderValue::from_static("Basic realm=\"nimiq\""),
+                );
+                return response;
+            }
+        };
+
+        if *allowed_methods != AllowedMethods::All {
+            // The messages of WebSocket connections can't be checked, so only users that are
+            // allowed to call all methods can open them.
+            if request.headers().contains_key(header::UPGRADE) {
+                return status_response(StatusCode::FORBIDDEN);
+            }
+            if request.method() != Method::POST {
+                return status_response(StatusCode::METHOD_NOT_ALLOWED);
+            }
+
+            let body = match read_body(request.body_mut(), MAX_REQUEST_BODY_SIZE).await {
+                Ok(body) => body,
+                Err(status) => return status_response(status),
+            };
+            let denied_methods = allowed_methods.denied_methods(&body);
+            if !denied_methods.is_empty() {
+                return Response::builder()
+                    .status(StatusCode::FORBIDDEN)
+                    .body(Body::from(format!(
+                        "Methods not allowed: {}",
+                        denied_methods.join(", ")
+                    )))
+                    .unwrap();
+            }
+            *request.body_mut() = Body::from(body);
+        }
+
+        let mut response = match self.forward(&mut request).await {
+            Ok(response) => response,
+            Err(error) => {
+                log::warn!(%error, "Failed to forward RPC request");
+                status_response(StatusCode::BAD_GATEWAY)
+            }
+        };
+        if let Some(origin) = origin {
+            add_cors_headers(response.headers_mut(), origin);
+        }
+        response
+    }
+
+    /// Forwards a request to the jsonrpc server. WebSocket connections are upgraded on both ends
+    /// and then copied in both directions.
+    async fn forward(&self, request: &mut Request<Body>) -> Result<Response<Body>, hyper::Error> {
+        let path = request
+            .uri()
+            .path_and_query()
+            .map(|path| path.as_str())
+            .unwrap_or("/");
+        let uri: Uri = format!("http://{}{}", self.backend, path)
+            .parse()
+            .expect("Invalid backend URI");
+
+        let mut backend_request = Request::builder()
+            .method(request.method())
+            .uri(uri)
+            .body(std::mem::take(request.body_mut()))
+            .expect("Invalid backend request");
+        let headers = backend_request.headers_mut();
+        *headers = request.headers().clone();
+        headers.remove(header::HOST);
+        headers.insert(header::AUTHORIZATION, self.backend_authorization.clone());
+
+        let mut response = self.client.request(backend_request).await?;
+
+        if response.status() == StatusCode::SWITCHING_PROTOCOLS {
+            let client_upgrade = hyper::upgrade::on(request);
+            let backend_upgrade = hyper::upgrade::on(&mut response);
+            tokio::spawn(async move {
+                match (client_upgrade.await, backend_upgrade.await) {
+                    (Ok(mut client), Ok(mut backend)) => {
+                        if let Err(error) =
+                            tokio::io::copy_bidirectional(&mut client, &mut backend).await
+                        {
+                            log::debug!(%error, "RPC WebSocket connection closed");
+                        }
+                    }
+                    (Err(error), _) | (_, Err(error)) => {
+                        log::warn!(%error, "Failed to upgrade RPC WebSocket connection");
+                    }
+                }
+            });
+        }
+
+        Ok(response)
+    }
+}
+
+/// Reads a request body of at most `limit` bytes.
+async fn read_body(body: &mut Body, limit: usize) -> Result<Vec<u8>, StatusCode> {
+    if body.size_hint().lower() > limit as u64 {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|_| StatusCode::BAD_REQUEST)?;
+        if bytes.len() + chunk.len() > limit {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+fn add_cors_headers(headers: &mut HeaderMap, origin: HeaderValue) {
+    headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    headers.insert(header::VARY, HeaderValue::from_static("origin"));
+}
+
+fn status_response(status: StatusCode) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(Body::empty())
+        .unwrap()
+}
@@ -1,4 +1,4 @@
-use std::{collections::HashSet, iter::FromIterator, sync::Arc};
+use std::{collections::HashSet, iter::FromIterator, net::TcpListener, sync::Arc};
 
 use nimiq_rpc_server::dispatchers::*;
 
@@ -12,41 +12,124 @@ use crate::client::Client;
 use crate::config::config::RpcServerConfig;
 use crate::config::consts::default_bind;
 use crate::error::Error;
+use crate::extras::rpc_access::{
+    random_credentials, reserve_loopback_port, AllowedMethods, RpcAccessControl, RpcProxy, RpcUser,
+};
 
 pub type Server = _Server<AllowListDispatcher<ModularDispatcher>>;
 
+/// The RPC server, consisting of the jsonrpc server on the loopback interface and the proxy that
+/// serves it publicly.
+pub struct RpcServer {
+    server: Server,
+    /// Keeps the port of the jsonrpc server reserved until the server binds it.
+    backend_reservation: TcpListener,
+    proxy: RpcProxy,
+}
+
+impl RpcServer {
+    pub async fn run(self) {
+        let RpcServer {
+            server,
+            backend_reservation,
+            proxy,
+        } = self;
+
+        // The jsonrpc server binds its port itself, so the reservation is released right before,
+        // within the same poll. If another process takes the port anyway, the jsonrpc server fails
+        // to bind, which stops the proxy as well instead of forwarding requests to that process.
+        let backend = async move {
+            drop(backend_reservation);
+            server.run().await
+        };
+        tokio::join!(backend, proxy.run());
+    }
+}
+
+/// Initializes the RPC server. Besides the main credentials, the server accepts the credentials of
+/// every configured user, who can only call the methods of the user's role.
 #[cfg(feature = "rpc-server")]
 pub fn initialize_rpc_server(
     client: &Client,
     config: RpcServerConfig,
     wallet_store: Arc<WalletStore>,
-) -> Result<Server, Error> {
+) -> Result<RpcServer, Error> {
     let ip = config.bind_to.unwrap_or_else(default_bind);
     log::info!("Initializing RPC server: {}:{}", ip, config.port);
 
-    // Configure RPC server
-    let basic_auth = config.credentials.map(|credentials| Credentials {
-        username: credentials.username,
-        password: credentials.password,
-    });
-
     let allowed_methods = config.allowed_methods.unwrap_or_default();
     let allowed_methods = if allowed_methods.is_empty() {
-        None
+        AllowedMethods::All
     } else {
-        Some(HashSet::from_iter(allowed_methods))
+        AllowedMethods::Only(HashSet::from_iter(allowed_methods))
+    };
+
+    if config.enable_websocket {
+        log::info!("Accepting WebSocket connections to the RPC server");
+        if config.credentials.is_none() && !ip.is_loopback() {
+            log::warn!(
+                "The RPC server accepts WebSocket connections on a public interface without credentials"
+            );
+        }
+    }
+
+    if config.credentials.is_none() && !config.users.is_empty() {
+        log::warn!(
+            "The RPC server only accepts requests of its users, since it has no credentials"
+        );
+    }
+    let users = RpcUser::from_config(config.credentials, allowed_methods, config.users);
+
+    let access = RpcAccessControl {
+        users,
+        cors_origins: config.corsdomain.unwrap_or_default(),
+        allow_ips: config
+            .allow_ips
+            .filter(|allow_ips| !allow_ips.is_empty())
+            .map(HashSet::from_iter),
     };
 
-    // TODO: Pass this to the rpc server config
-    let _corsdomain = config.corsdomain.unwrap_or_default();
+    // The jsonrpc server listens on an unused port of the loopback interface and is protected by
+    // random credentials that only the proxy knows. The proxy checks the methods of each request.
+    let backend_reservation = reserve_loopback_port()?;
+    let backend = backend_reservation.local_addr()?;
+    let backend_credentials = random_credentials();
 
+    let server = Server::new(
+        Config {
+            bind_to: backend,
+            enable_websocket: config.enable_websocket,
+            ip_whitelist: None,
+            basic_auth: Some(Credentials {
+                username: backend_credentials.username.clone(),
+                password: backend_credentials.password.clone(),
+            }),
+        },
+        AllowListDispatcher::new(dispatcher(client, wallet_store), None),
+    );
+
+    Ok(RpcServer {
+        server,
+        backend_reservation,
+        proxy: RpcProxy {
+            bind_to: (ip, config.port).into(),
+            backend,
+            backend_credentials,
+            access,
+        },
+    })
+}
+
+/// Creates a dispatcher for all RPC methods supported by the client.
+#[cfg(feature = "rpc-server")]
+fn dispatcher(client: &Client, wallet_store: Arc<WalletStore>) -> ModularDispatcher {
     let mut dispatcher = ModularDispatcher::default();
 
+    dispatcher.add(BlockchainDispatcher::new(client.consensus_proxy()));
+
     let wallet_dispatcher = WalletDispatcher::new(wallet_store);
     let unlocked_wallets = Arc::clone(&wallet_dispatcher.unlocked_wallets);
 
-    dispatcher.add(BlockchainDispatcher::new(client.blockchain()));
-
     dispatcher.add(ConsensusDispatcher::new(
         client.consensus_proxy(),
         Some(unlocked_wallets),
@@ -63,13 +146,5 @@ pub fn initialize_rpc_server(
 
     dispatcher.add(ZKPComponentDispatcher::new(client.zkp_component()));
 
-    Ok(Server::new(
-        Config {
-            bind_to: (config.bind_to.unwrap_or_else(default_bind), config.port).into(),
-            enable_websocket: false,
-            ip_whitelist: None,
-            basic_auth,
-        },
-        AllowListDispatcher::new(dispatcher, allowed_methods),
-    ))
+    dispatcher
 }
@@ -1,13 +1,49 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
 use signal_hook::{consts::SIGINT, iterator::Signals};
-use tokio::time::{sleep, Duration};
+use tokio::time::{sleep, timeout, Duration};
+
+use nimiq_network_libp2p::Network;
+
+/// The maximum time that is spent on shutting down the client cleanly.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Gives the signal handler access to the parts of the client that need to be shut down cleanly.
+/// The client is only created after the signal handler, so they are set later.
+#[derive(Clone, Default)]
+pub struct ShutdownHandle {
+    network: Arc<Mutex<Option<Arc<Network>>>>,
+}
+
+impl ShutdownHandle {
+    /// Sets the network, whose peer contacts are stored before the client exits.
+    pub fn set_network(&self, network: Arc<Network>) {
+        *self.network.lock() = Some(network);
+    }
+
+    async fn shutdown(&self) {
+        let network = self.network.lock().clone();
+        if let Some(network) = network {
+            match timeout(SHUTDOWN_TIMEOUT, network.store_peer_contacts()).await {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => log::error!(%error, "Failed to store peer contacts"),
+                Err(_) => log::error!("Timed out storing peer contacts"),
+            }
+        }
+    }
+}
 
-pub fn initialize_signal_handler() {
+pub fn initialize_signal_handler() -> ShutdownHandle {
+    let shutdown_handle = ShutdownHandle::default();
     let signals = Signals::new([SIGINT]);
 
     if let Ok(mut signals) = signals {
+        let shutdown_handle = shutdown_handle.clone();
         tokio::spawn(async move {
             for _ in signals.forever() {
                 log::warn!("Received Ctrl+C. Closing client");
+                shutdown_handle.shutdown().await;
                 // Add some delay for the log message to propagate into loki
                 sleep(Duration::from_millis(200)).await;
                 std::process::exit(0);
@@ -16,4 +52,6 @@ pub fn initialize_signal_handler() {
     } else {
         log::error!("Could not obtain SIGINT signal");
     }
+
+    shutdown_handle
 }
@@ -113,3 +113,30 @@ fn config_file_partial_db_entry() {
 
     assert_eq!(config.storage, db_config.into());
 }
+
+#[cfg(feature = "rpc-server")]
+#[test]
+fn config_file_rpc_users_require_credentials() {
+    let users = r#"
+    [[rpc-server.users]]
+    username = "monitoring"
+    password = "secret"
+    role = "read-only"
+    "#;
+
+    // Without credentials of the RPC server, its users could drop their credentials to bypass
+    // their roles.
+    let config_file: ConfigFile = toml::from_str(&format!("[rpc-server]\n{}", users)).unwrap();
+    let mut config_builder = ClientConfigBuilder::default();
+    assert!(config_builder.config_file(&config_file).is_err());
+
+    let config_file: ConfigFile = toml::from_str(&format!(
+        "[rpc-server]\nusername = \"super\"\npassword = \"secret\"\n{}",
+        users
+    ))
+    .unwrap();
+    let mut config_builder = ClientConfigBuilder::default();
+    config_builder.config_file(&config_file).unwrap();
+    let config = config_builder.build().unwrap();
+    assert_eq!(config.rpc_server.unwrap().users.len(), 1);
+}
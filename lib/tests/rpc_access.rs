@@ -0,0 +1,269 @@
+#![cfg(feature = "rpc-server")]
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use hyper::header::{self, HeaderMap, HeaderValue};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Client, Method, Request, Response, Server, StatusCode};
+
+use nimiq_lib::config::config::{Credentials, RpcUserConfig};
+use nimiq_lib::extras::rpc_access::{
+    basic_auth, random_credentials, reserve_loopback_port, AllowedMethods, RpcAccessControl,
+    RpcProxy, RpcUser, MAX_REQUEST_BODY_SIZE,
+};
+use nimiq_test_log::test;
+
+fn authorization(credentials: &Credentials) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::AUTHORIZATION,
+        HeaderValue::from_str(&basic_auth(credentials)).unwrap(),
+    );
+    headers
+}
+
+fn only(methods: &[&str]) -> AllowedMethods {
+    AllowedMethods::Only(methods.iter().map(|method| method.to_string()).collect())
+}
+
+/// An admin that can call all methods and a monitoring user that can only read the block number.
+fn access_control() -> RpcAccessControl {
+    RpcAccessControl {
+        users: vec![
+            RpcUser {
+                credentials: Some(Credentials::new("admin", "secret")),
+                allowed_methods: AllowedMethods::All,
+            },
+            RpcUser {
+                credentials: Some(Credentials::new("monitoring", "secret")),
+                allowed_methods: only(&["getBlockNumber"]),
+            },
+        ],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn it_identifies_users_by_their_credentials() {
+    let access = access_control();
+
+    assert_eq!(
+        access.authorize(&authorization(&Credentials::new("admin", "secret"))),
+        Some(&AllowedMethods::All)
+    );
+    assert_eq!(
+        access.authorize(&authorization(&Credentials::new("monitoring", "secret"))),
+        Some(&only(&["getBlockNumber"]))
+    );
+    assert_eq!(
+        access.authorize(&authorization(&Credentials::new("admin", "wrong"))),
+        None
+    );
+    assert_eq!(access.authorize(&HeaderMap::new()), None);
+
+    // Requests without credentials are made by the user without credentials.
+    let mut access = access;
+    access.users.push(RpcUser {
+        credentials: None,
+        allowed_methods: only(&["getPeerCount"]),
+    });
+    assert_eq!(
+        access.authorize(&HeaderMap::new()),
+        Some(&only(&["getPeerCount"]))
+    );
+    assert_eq!(
+        access.authorize(&authorization(&Credentials::new("admin", "wrong"))),
+        None
+    );
+}
+
+#[test]
+fn it_refuses_anonymous_requests_if_there_are_users() {
+    let read_only = RpcUserConfig {
+        credentials: Credentials::new("monitoring", "secret"),
+        role: "read-only".to_string(),
+        allowed_methods: vec!["getBlockNumber".to_string()],
+    };
+
+    // Without credentials of the RPC server, only its users are accepted.
+    let access = RpcAccessControl {
+        users: RpcUser::from_config(None, AllowedMethods::All, vec![read_only.clone()]),
+        ..Default::default()
+    };
+    assert_eq!(access.authorize(&HeaderMap::new()), None);
+    assert_eq!(
+        access.authorize(&authorization(&Credentials::new("monitoring", "secret"))),
+        Some(&only(&["getBlockNumber"]))
+    );
+
+    // With credentials, requests without credentials are refused as well.
+    let access = RpcAccessControl {
+        users: RpcUser::from_config(
+            Some(Credentials::new("admin", "secret")),
+            AllowedMethods::All,
+            vec![read_only],
+        ),
+        ..Default::default()
+    };
+    assert_eq!(access.authorize(&HeaderMap::new()), None);
+    assert_eq!(
+        access.authorize(&authorization(&Credentials::new("admin", "secret"))),
+        Some(&AllowedMethods::All)
+    );
+
+    // Without any users, the RPC server accepts requests without credentials.
+    let access = RpcAccessControl {
+        users: RpcUser::from_config(None, AllowedMethods::All, vec![]),
+        ..Default::default()
+    };
+    assert_eq!(
+        access.authorize(&HeaderMap::new()),
+        Some(&AllowedMethods::All)
+    );
+}
+
+#[test]
+fn it_refuses_methods_that_are_not_allowed() {
+    let allowed_methods = only(&["getBlockNumber"]);
+
+    assert!(allowed_methods
+        .denied_methods(br#"{"jsonrpc":"2.0","method":"getBlockNumber","id":1}"#)
+        .is_empty());
+    assert_eq!(
+        allowed_methods.denied_methods(br#"{"jsonrpc":"2.0","method":"unlockAccount","id":1}"#),
+        vec!["unlockAccount".to_string()]
+    );
+
+    // Every request of a batch is checked.
+    assert_eq!(
+        allowed_methods.denied_methods(
+            br#"[{"method":"getBlockNumber","id":1},{"method":"sendRawTransaction","id":2}]"#
+        ),
+        vec!["sendRawTransaction".to_string()]
+    );
+
+    assert!(AllowedMethods::All
+        .denied_methods(br#"{"method":"unlockAccount"}"#)
+        .is_empty());
+}
+
+#[test]
+fn it_checks_origins_and_ips() {
+    let access = RpcAccessControl {
+        cors_origins: vec!["https://example.com".to_string()],
+        allow_ips: Some(["127.0.0.1".parse().unwrap()].into_iter().collect()),
+        ..Default::default()
+    };
+
+    assert!(access.is_origin_allowed("https://example.com"));
+    assert!(!access.is_origin_allowed("https://example.org"));
+    assert!(access.is_ip_allowed("127.0.0.1".parse().unwrap()));
+    assert!(!access.is_ip_allowed("10.0.0.1".parse().unwrap()));
+
+    let access = RpcAccessControl {
+        cors_origins: vec!["*".to_string()],
+        ..Default::default()
+    };
+    assert!(access.is_origin_allowed("https://example.org"));
+    assert!(access.is_ip_allowed("10.0.0.1".parse().unwrap()));
+
+    // Cross-origin requests are refused by default.
+    assert!(!RpcAccessControl::default().is_origin_allowed("https://example.com"));
+}
+
+#[test(tokio::test)]
+async fn the_proxy_refuses_methods_that_are_not_allowed() {
+    // A backend that accepts every request with the backend credentials.
+    let backend_credentials = random_credentials();
+    let backend_authorization = basic_auth(&backend_credentials);
+    let backend_listener = reserve_loopback_port().unwrap();
+    let backend = backend_listener.local_addr().unwrap();
+    let make_service = make_service_fn(move |_| {
+        let backend_authorization = backend_authorization.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                let is_authorized = request
+                    .headers()
+                    .get(header::AUTHORIZATION)
+                    .map(|value| value == backend_authorization.as_str())
+                    .unwrap_or(false);
+                async move {
+                    let status = if is_authorized {
+                        StatusCode::OK
+                    } else {
+                        StatusCode::UNAUTHORIZED
+                    };
+                    Ok::<_, Infallible>(
+                        Response::builder()
+                            .status(status)
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                }
+            }))
+        }
+    });
+    tokio::spawn(
+        Server::from_tcp(backend_listener)
+            .unwrap()
+            .serve(make_service),
+    );
+
+    let bind_to: SocketAddr = reserve_loopback_port().unwrap().local_addr().unwrap();
+    tokio::spawn(
+        RpcProxy {
+            bind_to,
+            backend,
+            backend_credentials,
+            access: access_control(),
+        }
+        .run(),
+    );
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = Client::new();
+    let call = |credentials: Credentials, method: &str| {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(format!("http://{}/", bind_to))
+            .header(header::AUTHORIZATION, basic_auth(&credentials))
+            .body(Body::from(format!(
+                r#"{{"jsonrpc":"2.0","method":"{}","id":1}}"#,
+                method
+            )))
+            .unwrap();
+        client.request(request)
+    };
+
+    let monitoring = Credentials::new("monitoring", "secret");
+    let admin = Credentials::new("admin", "secret");
+
+    let response = call(monitoring.clone(), "getBlockNumber").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = call(monitoring, "unlockAccount").await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = call(admin, "unlockAccount").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = call(Credentials::new("admin", "wrong"), "getBlockNumber")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // The proxy only reads request bodies up to a limit to check their methods.
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(format!("http://{}/", bind_to))
+        .header(
+            header::AUTHORIZATION,
+            basic_auth(&Credentials::new("monitoring", "secret")),
+        )
+        .body(Body::from(vec![b' '; MAX_REQUEST_BODY_SIZE + 1]))
+        .unwrap();
+    let response = client.request(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
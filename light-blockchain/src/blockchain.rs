@@ -3,6 +3,7 @@ use std::sync::Arc;
 use nimiq_block::{Block, MacroBlock};
 use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainError, ChainInfo};
 use nimiq_collections::BitSet;
+use nimiq_database::Environment;
 use nimiq_genesis::NetworkInfo;
 use nimiq_primitives::{
     networks::NetworkId,
@@ -13,6 +14,7 @@ use nimiq_utils::time::OffsetTime;
 use nimiq_vrf::{Rng, VrfEntropy, VrfUseCase};
 
 use crate::chain_store::ChainStore;
+use crate::state_store::{StateStore, StoredState};
 
 /// The Blockchain struct. It stores all information of the blockchain that is known to the Nano
 /// nodes.
@@ -33,10 +35,16 @@ pub struct LightBlockchain {
     pub genesis_block: Block,
     // The chain store is a database containing all of the chain infos in the current batch.
     pub chain_store: ChainStore,
+    // The database in which the state is persisted, if any.
+    pub(crate) state_store: Option<StateStore>,
 }
 
 /// Implements methods to start a Blockchain.
 impl LightBlockchain {
+    /// Number of election blocks that are persisted, such that the validators of the previous
+    /// epoch are still known after a restart.
+    const NUM_STORED_ELECTION_BLOCKS: usize = 2;
+
     /// Creates a new blockchain from a given network ID.
     pub fn new(network_id: NetworkId) -> Self {
         let network_info = NetworkInfo::from_network_id(network_id);
@@ -44,6 +52,21 @@ impl LightBlockchain {
         Self::with_genesis(network_id, genesis_block)
     }
 
+    /// Creates a new blockchain from a given network ID, which persists its state in the given
+    /// database environment. If a state was persisted before, the blockchain resumes from the
+    /// stored election block instead of the genesis block.
+    pub fn with_env(env: Environment, network_id: NetworkId) -> Self {
+        let mut blockchain = Self::new(network_id);
+        let state_store = StateStore::new(env);
+
+        if let Some(state) = state_store.get_state() {
+            blockchain.restore_state(state);
+        }
+
+        blockchain.state_store = Some(state_store);
+        blockchain
+    }
+
     /// Creates a new blockchain with a given network ID and genesis block.
     pub fn with_genesis(network_id: NetworkId, genesis_block: Block) -> Self {
         let time = Arc::new(OffsetTime::new());
@@ -63,6 +86,81 @@ impl LightBlockchain {
             current_validators: genesis_block.validators(),
             genesis_block,
             chain_store,
+            state_store: None,
+        }
+    }
+
+    /// Resumes the blockchain from a stored state. The latest stored election block becomes the
+    /// head of the chain, the earlier ones are kept to look up the validators of past epochs.
+    fn restore_state(&mut self, state: StoredState) {
+        let election_head = match state.election_blocks.last() {
+            Some(election_head) => election_head.clone(),
+            None => {
+                log::warn!("Ignoring stored light blockchain state without election blocks");
+                return;
+            }
+        };
+
+        let is_valid = state.election_blocks.iter().all(|election_block| {
+            election_block.is_election_block() && election_block.body.is_some()
+        }) && state.election_blocks.windows(2).all(|election_blocks| {
+            election_blocks[0].block_number() + Policy::blocks_per_epoch()
+                == election_blocks[1].block_number()
+        });
+
+        if !is_valid || election_head.block_number() <= self.election_head.block_number() {
+            log::warn!(
+                block_number = election_head.block_number(),
+                "Ignoring invalid stored light blockchain state",
+            );
+            return;
+        }
+
+        self.chain_store.clear();
+        for election_block in state.election_blocks {
+            self.chain_store.put_election(election_block.header.clone());
+            self.chain_store
+                .put_chain_info(ChainInfo::new(Block::Macro(election_block), true));
+        }
+
+        self.head = Block::Macro(election_head.clone());
+        self.macro_head = election_head.clone();
+        self.election_head = election_head;
+        self.current_validators = Some(state.current_validators);
+
+        log::info!(
+            block_number = self.election_head.block_number(),
+            "Restored light blockchain state",
+        );
+    }
+
+    /// Persists the latest election blocks and the current validators, if the blockchain has a
+    /// database.
+    pub(crate) fn store_state(&self) {
+        if let (Some(state_store), Some(current_validators)) =
+            (&self.state_store, &self.current_validators)
+        {
+            // Collect the preceding election blocks that are still known, newest first.
+            let mut election_blocks = vec![self.election_head.clone()];
+            while election_blocks.len() < Self::NUM_STORED_ELECTION_BLOCKS {
+                let block_number = election_blocks.last().unwrap().block_number();
+                if block_number == 0 {
+                    break;
+                }
+
+                match self.get_block_at(Policy::election_block_before(block_number), true) {
+                    Ok(Block::Macro(election_block)) if election_block.body.is_some() => {
+                        election_blocks.push(election_block)
+                    }
+                    _ => break,
+                }
+            }
+            election_blocks.reverse();
+
+            state_store.set_state(&StoredState {
+                election_blocks,
+                current_validators: current_validators.clone(),
+            });
         }
     }
 
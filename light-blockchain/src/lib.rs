@@ -5,4 +5,5 @@ pub(crate) mod abstract_blockchain;
 pub(crate) mod blockchain;
 pub(crate) mod chain_store;
 pub(crate) mod push;
+pub(crate) mod state_store;
 pub(crate) mod sync;
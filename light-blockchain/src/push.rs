@@ -167,6 +167,9 @@ impl LightBlockchain {
 
                 // Store the election block header.
                 this.chain_store.put_election(macro_block.header.clone());
+
+                // Persist the new election head.
+                this.store_state();
             }
         }
 
@@ -0,0 +1,68 @@
+use std::io;
+
+use beserial::{Deserialize, Serialize};
+use nimiq_block::MacroBlock;
+use nimiq_database::{Database, Environment, ReadTransaction, WriteTransaction};
+use nimiq_database_value::{FromDatabaseValue, IntoDatabaseValue};
+use nimiq_primitives::slots::Validators;
+
+/// A database that keeps the state of the light blockchain that is needed to resume syncing after
+/// a restart of the node, without having to sync from the genesis block again.
+#[derive(Debug)]
+pub(crate) struct StateStore {
+    env: Environment,
+    // A database containing the stored state.
+    state_db: Database,
+}
+
+impl StateStore {
+    const STATE_DB_NAME: &'static str = "LightBlockchainState";
+    const STATE_KEY: &'static str = "state";
+
+    pub fn new(env: Environment) -> Self {
+        let state_db = env.open_database(Self::STATE_DB_NAME.to_string());
+
+        StateStore { env, state_db }
+    }
+
+    pub fn get_state(&self) -> Option<StoredState> {
+        ReadTransaction::new(&self.env).get(&self.state_db, Self::STATE_KEY)
+    }
+
+    pub fn set_state(&self, state: &StoredState) {
+        let mut txn = WriteTransaction::new(&self.env);
+        txn.put_reserve(&self.state_db, Self::STATE_KEY, state);
+        txn.commit();
+    }
+}
+
+/// The state of the light blockchain as stored in the database.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct StoredState {
+    /// The latest election blocks, including their bodies, in ascending order. The last one is the
+    /// election head.
+    #[beserial(len_type(u8))]
+    pub election_blocks: Vec<MacroBlock>,
+    /// The validators for the current epoch.
+    pub current_validators: Validators,
+}
+
+impl IntoDatabaseValue for StoredState {
+    fn database_byte_size(&self) -> usize {
+        self.serialized_size()
+    }
+
+    fn copy_into_database(&self, mut bytes: &mut [u8]) {
+        Serialize::serialize(&self, &mut bytes).unwrap();
+    }
+}
+
+impl FromDatabaseValue for StoredState {
+    fn copy_from_database(bytes: &[u8]) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut cursor = io::Cursor::new(bytes);
+        Ok(Deserialize::deserialize(&mut cursor)?)
+    }
+}
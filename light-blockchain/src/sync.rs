@@ -112,6 +112,9 @@ impl LightBlockchain {
 
         this.current_validators = block.validators();
 
+        // Persist the new election head.
+        this.store_state();
+
         Ok(PushResult::Extended)
     }
 
@@ -180,6 +183,9 @@ impl LightBlockchain {
 
             // Store the election block header.
             this.chain_store.put_election(block.unwrap_macro().header);
+
+            // Persist the new election head.
+            this.store_state();
         }
 
         Ok(PushResult::Extended)
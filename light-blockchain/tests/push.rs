@@ -9,6 +9,7 @@ use nimiq_blockchain::Blockchain;
 use nimiq_blockchain_interface::{
     AbstractBlockchain, PushError, PushError::InvalidBlock, PushResult,
 };
+use nimiq_database::volatile::VolatileEnvironment;
 use nimiq_genesis::NetworkId;
 use nimiq_hash::Blake2bHash;
 use nimiq_light_blockchain::LightBlockchain;
@@ -624,3 +625,51 @@ fn it_can_rebranch_to_inferior_macro_block() {
         blockchain2.state.previous_slots
     );
 }
+
+#[test]
+fn it_resumes_from_the_stored_election_head() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let temp_producer = TemporaryLightBlockProducer::new();
+    *temp_producer.light_blockchain.write() =
+        LightBlockchain::with_env(env.clone(), NetworkId::UnitAlbatross);
+
+    // Apply two entire epochs, so that the light blockchain stores both election blocks.
+    for _ in 0..2 * Policy::blocks_per_epoch() {
+        temp_producer.next_block(vec![], false);
+    }
+    let election_head = temp_producer.light_blockchain.read().election_head();
+    assert_eq!(election_head.block_number(), 2 * Policy::blocks_per_epoch());
+    let previous_election_block = temp_producer
+        .light_blockchain
+        .read()
+        .get_block_at(Policy::blocks_per_epoch(), true)
+        .unwrap()
+        .unwrap_macro();
+
+    // A new light blockchain on the same database resumes from the election block.
+    let restored = LightBlockchain::with_env(env, NetworkId::UnitAlbatross);
+    assert_eq!(restored.election_head(), election_head);
+    assert_eq!(restored.macro_head(), election_head);
+    assert_eq!(restored.head_hash(), election_head.hash());
+    assert_eq!(
+        restored.current_validators(),
+        election_head.get_validators()
+    );
+
+    // The restored light blockchain accepts the blocks following the election block.
+    let restored = RwLock::new(restored);
+    let block = temp_producer.next_block(vec![], false);
+    assert_eq!(
+        LightBlockchain::push(restored.upgradable_read(), remove_micro_body(block)),
+        Ok(PushResult::Extended)
+    );
+
+    // The validators of the previous epoch are known from the previous stored election block.
+    assert_eq!(
+        restored
+            .read()
+            .get_validators_for_epoch(Policy::epoch_at(election_head.block_number()))
+            .ok(),
+        previous_election_block.get_validators()
+    );
+}
@@ -42,7 +42,7 @@ async fn main_inner() -> Result<(), Error> {
     initialize_panic_reporting();
 
     // Initialize signal handler
-    initialize_signal_handler();
+    let shutdown_handle = initialize_signal_handler();
 
     // Create config builder and apply command line and config file.
     // You usually want the command line to override config settings, so the order is important.
@@ -58,11 +58,13 @@ async fn main_inner() -> Result<(), Error> {
     let rpc_config = config.rpc_server.clone();
     let metrics_config = config.metrics_server.clone();
     let metrics_enabled = metrics_config.is_some();
+    let min_peers = config.consensus.min_peers;
 
     // Create client from config.
     log::info!("Initializing light client");
     let mut client: Client = Client::from_config(config).await?;
     log::info!("Light client initialized");
+    shutdown_handle.set_network(client.network());
 
     // Initialize RPC server
     if let Some(rpc_config) = rpc_config {
@@ -103,6 +105,8 @@ async fn main_inner() -> Result<(), Error> {
             None,
             client.consensus_proxy(),
             client.network(),
+            None,
+            min_peers,
             &nimiq_task_metric,
         )
     }
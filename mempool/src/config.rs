@@ -12,6 +12,11 @@ pub struct MempoolConfig {
     pub filter_rules: MempoolRules,
     /// Mempool filter limit or size
     pub filter_limit: usize,
+    /// Persist the transactions in the database, such that they are restored after a restart
+    pub persistent: bool,
+    /// Minimum increase of the fee per byte (in percent) for a transaction to replace a pending
+    /// transaction of the same sender. Replace-by-fee is disabled if this is `None`.
+    pub replacement_fee_bump: Option<u32>,
 }
 
 impl Default for MempoolConfig {
@@ -21,6 +26,8 @@ impl Default for MempoolConfig {
             control_size_limit: Mempool::DEFAULT_CONTROL_SIZE_LIMIT,
             filter_rules: MempoolRules::default(),
             filter_limit: MempoolFilter::DEFAULT_BLACKLIST_SIZE,
+            persistent: false,
+            replacement_fee_bump: None,
         }
     }
 }
@@ -14,7 +14,7 @@ use nimiq_primitives::networks::NetworkId;
 use nimiq_transaction::Transaction;
 
 use crate::filter::MempoolFilter;
-use crate::mempool_state::MempoolState;
+use crate::mempool_state::{EvictionReason, MempoolState};
 use crate::mempool_transactions::TxPriority;
 use crate::verify::{verify_tx, VerifyErr};
 
@@ -98,9 +98,15 @@ impl<N: Network, T: Topic + Unpin + Sync> Future for MempoolExecutor<N, T> {
                         verify_tx(&tx, blockchain, network_id, &mempool_state, filter).await;
 
                     match verify_tx_ret {
-                        Ok(mempool_state_lock) => {
-                            RwLockUpgradableReadGuard::upgrade(mempool_state_lock)
-                                .put(&tx, TxPriority::MediumPriority);
+                        Ok((mempool_state_lock, replaced_tx)) => {
+                            let mut mempool_state =
+                                RwLockUpgradableReadGuard::upgrade(mempool_state_lock);
+                            // The replacing transaction is accepted, so it is gossiped further.
+                            if let Some(replaced_tx) = replaced_tx {
+                                mempool_state.remove(&replaced_tx, EvictionReason::Replaced);
+                            }
+                            mempool_state.put(&tx, TxPriority::MediumPriority);
+                            mempool_state.request_persist();
                             MsgAcceptance::Accept
                         }
                         // Reject the message if signature verification fails or transaction is invalid
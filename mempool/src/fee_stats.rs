@@ -0,0 +1,176 @@
+use std::collections::VecDeque;
+
+use beserial::Serialize;
+use nimiq_block::{Block, MicroBlock};
+
+/// Fee statistics of a single micro block.
+#[derive(Clone, Debug)]
+struct BlockFeeStats {
+    /// The number of the block.
+    block_number: u32,
+    /// The number of bytes used by the transactions of the block.
+    used_bytes: usize,
+    /// The number of bytes that were available for transactions in the block.
+    available_bytes: usize,
+    /// The lowest fee per byte of the transactions included in the block, if it has any.
+    min_fee_per_byte: Option<f64>,
+}
+
+impl BlockFeeStats {
+    fn is_full(&self) -> bool {
+        self.used_bytes as f64 >= FeeStats::FULL_BLOCK_RATE * self.available_bytes as f64
+    }
+}
+
+/// Fee statistics of the most recent micro blocks, which are updated as blocks are adopted and
+/// reverted so that estimating fees doesn't need to read blocks from the blockchain.
+#[derive(Debug, Default)]
+pub(crate) struct FeeStats {
+    blocks: VecDeque<BlockFeeStats>,
+}
+
+impl FeeStats {
+    /// Number of recent micro blocks that are taken into account for fee estimation
+    const NUM_BLOCKS: usize = 60;
+
+    /// Fill rate of micro blocks above which block space is considered to be contended
+    const FULL_BLOCK_RATE: f64 = 0.9;
+
+    /// Adds the statistics of an adopted block. Macro blocks don't contain transactions and are
+    /// ignored.
+    pub(crate) fn push_block(&mut self, block: &Block) {
+        let body = match block {
+            Block::Micro(MicroBlock {
+                body: Some(body), ..
+            }) => body,
+            _ => return,
+        };
+
+        // Statistics of blocks at the same or a higher height belong to a chain that was replaced.
+        self.revert_block(block);
+
+        // The size of an executed transaction includes the extra byte that encodes its final
+        // execution status, which is also accounted for when estimating fees.
+        let used_bytes = body
+            .transactions
+            .iter()
+            .map(|tx| tx.serialized_size())
+            .sum();
+        let min_fee_per_byte = body
+            .transactions
+            .iter()
+            .map(|tx| tx.get_raw_transaction().fee_per_byte())
+            .reduce(f64::min);
+
+        self.blocks.push_back(BlockFeeStats {
+            block_number: block.block_number(),
+            used_bytes,
+            available_bytes: MicroBlock::get_available_bytes(body.fork_proofs.len()),
+            min_fee_per_byte,
+        });
+        if self.blocks.len() > Self::NUM_BLOCKS {
+            self.blocks.pop_front();
+        }
+    }
+
+    /// Removes the statistics of a reverted block and of all blocks after it.
+    pub(crate) fn revert_block(&mut self, block: &Block) {
+        let block_number = block.block_number();
+        while matches!(self.blocks.back(), Some(stats) if stats.block_number >= block_number) {
+            self.blocks.pop_back();
+        }
+    }
+
+    /// Returns the fee per byte that transactions needed to pay to be included in recent blocks
+    /// if block space was contended, i.e. if recent blocks were (almost) full on average. This is
+    /// the median of the lowest fees per byte that were included in the full blocks.
+    pub(crate) fn contended_fee_per_byte(&self) -> Option<f64> {
+        let used_bytes: usize = self.blocks.iter().map(|stats| stats.used_bytes).sum();
+        let available_bytes: usize = self.blocks.iter().map(|stats| stats.available_bytes).sum();
+        if available_bytes == 0
+            || (used_bytes as f64) < Self::FULL_BLOCK_RATE * available_bytes as f64
+        {
+            return None;
+        }
+
+        let mut fees_per_byte: Vec<f64> = self
+            .blocks
+            .iter()
+            .filter(|stats| stats.is_full())
+            .filter_map(|stats| stats.min_fee_per_byte)
+            .collect();
+        if fees_per_byte.is_empty() {
+            return None;
+        }
+        fees_per_byte.sort_unstable_by(|a, b| a.total_cmp(b));
+
+        Some(fees_per_byte[fees_per_byte.len() / 2])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use beserial::Serialize;
+    use nimiq_block::{Block, MicroBlock, MicroBody, MicroHeader};
+    use nimiq_hash::Blake2bHash;
+    use nimiq_keys::Address;
+    use nimiq_primitives::{coin::Coin, networks::NetworkId, policy::Policy};
+    use nimiq_transaction::{ExecutedTransaction, Transaction};
+    use nimiq_vrf::VrfSeed;
+
+    use super::FeeStats;
+
+    fn micro_block(block_number: u32, body: MicroBody) -> Block {
+        Block::Micro(MicroBlock {
+            header: MicroHeader {
+                version: 0,
+                block_number,
+                timestamp: 0,
+                parent_hash: Blake2bHash::default(),
+                seed: VrfSeed::default(),
+                extra_data: vec![],
+                state_root: Blake2bHash::default(),
+                body_root: Blake2bHash::default(),
+                history_root: Blake2bHash::default(),
+            },
+            body: Some(body),
+            justification: None,
+        })
+    }
+
+    #[test]
+    fn it_counts_the_bytes_of_the_block_body() {
+        let tx = Transaction::new_basic(
+            Address::default(),
+            Address::from([1u8; Address::SIZE]),
+            Coin::from_u64_unchecked(10),
+            Coin::from_u64_unchecked(100),
+            1,
+            NetworkId::UnitAlbatross,
+        );
+        let body = MicroBody {
+            fork_proofs: vec![],
+            transactions: vec![ExecutedTransaction::Ok(tx.clone()); 3],
+        };
+        let body_size = body.serialized_size();
+        let block = micro_block(1, body);
+
+        let mut fee_stats = FeeStats::default();
+        fee_stats.push_block(&block);
+        let stats = fee_stats.blocks.back().unwrap();
+
+        // The used bytes are the size of the body without the bytes that are not available for
+        // transactions, and match the size of the transactions as counted for fee estimation.
+        assert_eq!(stats.available_bytes, MicroBlock::get_available_bytes(0));
+        assert_eq!(
+            stats.used_bytes,
+            body_size - (Policy::MAX_SIZE_MICRO_BODY - stats.available_bytes)
+        );
+        assert_eq!(stats.used_bytes, 3 * (1 + tx.serialized_size()));
+        assert_eq!(stats.min_fee_per_byte, Some(tx.fee_per_byte()));
+
+        // Reverting the block removes its statistics.
+        fee_stats.revert_block(&block);
+        assert!(fee_stats.blocks.is_empty());
+    }
+}
@@ -15,13 +15,19 @@ pub mod config;
 /// Mempool executor module
 pub mod executor;
 
+/// Fee statistics module
+mod fee_stats;
 /// Mempool filter module
 pub mod filter;
 /// Main mempool module
 pub mod mempool;
+/// Mempool events module
+pub mod mempool_events;
 /// Mempool metrics
 #[cfg(feature = "metrics")]
 mod mempool_metrics;
+/// Mempool persistence module
+mod mempool_store;
 /// Mempool transaction module
 pub mod mempool_transactions;
 /// Verify transaction module
@@ -1,14 +1,16 @@
 use futures::future::{AbortHandle, Abortable};
 use futures::lock::{Mutex, MutexGuard};
-use futures::stream::BoxStream;
+use futures::stream::{BoxStream, StreamExt};
 use parking_lot::{RwLock, RwLockUpgradableReadGuard};
-use std::sync::atomic::AtomicU32;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Weak};
+use tokio::sync::Notify;
 use tokio_metrics::TaskMonitor;
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
 
 use beserial::Serialize;
 use nimiq_account::{Account, AccountTransactionInteraction, BasicAccount};
-use nimiq_block::Block;
+use nimiq_block::{Block, MicroBlock};
 use nimiq_blockchain::{Blockchain, TransactionVerificationCache};
 use nimiq_blockchain_interface::AbstractBlockchain;
 use nimiq_hash::{Blake2bHash, Hash};
@@ -22,10 +24,13 @@ use nimiq_transaction::Transaction;
 
 use crate::config::MempoolConfig;
 use crate::executor::MempoolExecutor;
+use crate::fee_stats::FeeStats;
 use crate::filter::{MempoolFilter, MempoolRules};
+use crate::mempool_events::MempoolEvent;
 #[cfg(feature = "metrics")]
 use crate::mempool_metrics::MempoolMetrics;
 use crate::mempool_state::{EvictionReason, MempoolState};
+use crate::mempool_store::{MempoolStore, MempoolStoreChanges, StoreChanges};
 use crate::mempool_transactions::TxPriority;
 use crate::verify::{verify_tx, VerifyErr};
 
@@ -72,6 +77,15 @@ pub struct Mempool {
 
     /// Total number of ongoing verification tasks
     verification_tasks: Arc<AtomicU32>,
+
+    /// The database where the transactions are persisted, if persistence is enabled
+    store: Option<Arc<MempoolStore>>,
+
+    /// Flag indicating if the persisted transactions were already restored
+    restored_transactions: AtomicBool,
+
+    /// Fee statistics of recent blocks, used to estimate fees
+    fee_stats: RwLock<FeeStats>,
 }
 
 impl Mempool {
@@ -81,13 +95,33 @@ impl Mempool {
     /// Default total size limit of control transactions in the mempool (bytes)
     pub const DEFAULT_CONTROL_SIZE_LIMIT: usize = 6_000_000;
 
-    /// Creates a new mempool
+    /// Creates a new mempool. If persistence is enabled, this spawns the task that writes the
+    /// persisted transactions and must be called from within a Tokio runtime.
     pub fn new(blockchain: Arc<RwLock<Blockchain>>, config: MempoolConfig) -> Self {
+        let (store, store_changes, store_writer) = if config.persistent {
+            let store = Arc::new(MempoolStore::new(blockchain.read().env().clone()));
+            let store_writer = Arc::new(Notify::new());
+            let store_changes = MempoolStoreChanges::new(Arc::clone(&store_writer));
+            (Some(store), Some(store_changes), Some(store_writer))
+        } else {
+            (None, None, None)
+        };
+
         let state = Arc::new(RwLock::new(MempoolState::new(
             config.size_limit,
             config.control_size_limit,
+            store_changes,
+            config.replacement_fee_bump,
         )));
 
+        if let (Some(store), Some(store_writer)) = (&store, store_writer) {
+            tokio::spawn(Self::write_store_changes(
+                Arc::downgrade(&state),
+                Arc::clone(store),
+                store_writer,
+            ));
+        }
+
         Self {
             blockchain: Arc::clone(&blockchain),
             state: Arc::clone(&state),
@@ -98,6 +132,47 @@ impl Mempool {
             executor_handle: Mutex::new(None),
             control_executor_handle: Mutex::new(None),
             verification_tasks: Arc::new(AtomicU32::new(0)),
+            store,
+            restored_transactions: AtomicBool::new(false),
+            fee_stats: RwLock::new(FeeStats::default()),
+        }
+    }
+
+    /// Writes the changes to the persisted transactions whenever they are requested, until the
+    /// mempool is dropped. Changes that are made while a batch is written are written together in
+    /// the next batch.
+    async fn write_store_changes(
+        state: Weak<RwLock<MempoolState>>,
+        store: Arc<MempoolStore>,
+        store_writer: Arc<Notify>,
+    ) {
+        loop {
+            store_writer.notified().await;
+
+            let state = match state.upgrade() {
+                Some(state) => state,
+                None => return,
+            };
+            store.persist(|| Self::take_store_changes(&state)).await;
+        }
+    }
+
+    fn take_store_changes(state: &RwLock<MempoolState>) -> StoreChanges {
+        state
+            .write()
+            .store_changes
+            .as_mut()
+            .map(|store_changes| store_changes.take())
+            .unwrap_or_default()
+    }
+
+    /// Writes the pending changes to the persisted transactions to the database right away,
+    /// instead of waiting for the background writer. Does nothing if persistence is disabled.
+    pub async fn persist_transactions(&self) {
+        if let Some(ref store) = self.store {
+            store
+                .persist(|| Self::take_store_changes(&self.state))
+                .await;
         }
     }
 
@@ -160,6 +235,15 @@ impl Mempool {
             return;
         }
 
+        // The executors are started once we have consensus, so this is the first time the
+        // persisted transactions can be verified against an up-to-date blockchain.
+        if !self.restored_transactions.swap(true, Ordering::SeqCst) {
+            let num_restored = self.restore_transactions().await;
+            if num_restored > 0 {
+                info!(num_restored, "Restored persisted mempool transactions");
+            }
+        }
+
         // Subscribe to the network TX topic
         let txn_stream = network.subscribe::<TransactionTopic>().await.unwrap();
 
@@ -293,6 +377,10 @@ impl Mempool {
     /// This function prunes transactions from the mempool that were already included in the blockchain
     /// This is generally used by history sync when it adopts epochs
     pub fn mempool_clean_up(&self) {
+        // The adopted history doesn't include the bodies of the micro blocks, so the fee statistics
+        // of the blocks before it no longer describe the recent blocks.
+        *self.fee_stats.write() = FeeStats::default();
+
         // Acquire the mempool and blockchain locks
         let blockchain = self.blockchain.read();
         let mut mempool_state = self.state.write();
@@ -317,6 +405,8 @@ impl Mempool {
                 mempool_state.remove(&tx_hash, EvictionReason::AlreadyIncluded);
             }
         }
+
+        mempool_state.request_persist();
     }
 
     /// Updates the mempool given a set of reverted and adopted blocks.
@@ -338,6 +428,17 @@ impl Mempool {
         adopted_blocks: &[(Blake2bHash, Block)],
         reverted_blocks: &[(Blake2bHash, Block)],
     ) {
+        // Update the fee statistics, reverted blocks are removed before the adopted ones are added
+        {
+            let mut fee_stats = self.fee_stats.write();
+            for (_, block) in reverted_blocks {
+                fee_stats.revert_block(block);
+            }
+            for (_, block) in adopted_blocks {
+                fee_stats.push_block(block);
+            }
+        }
+
         // Acquire the mempool and blockchain locks
         let blockchain = self.blockchain.read();
         let mut mempool_state = self.state.write();
@@ -577,6 +678,8 @@ impl Mempool {
                 }
             }
         }
+
+        mempool_state.request_persist();
     }
 
     /// Returns a vector with accepted transactions from the mempool.
@@ -631,6 +734,8 @@ impl Mempool {
             tx_vec.push(tx);
         }
 
+        mempool_state_upgraded.request_persist();
+
         debug!(
             returned_txs = tx_vec.len(),
             remaining_txs = mempool_state_upgraded
@@ -697,6 +802,8 @@ impl Mempool {
             tx_vec.push(tx);
         }
 
+        mempool_state_upgraded.request_persist();
+
         debug!(
             returned_txs = tx_vec.len(),
             remaining_txs = mempool_state_upgraded
@@ -723,11 +830,16 @@ impl Mempool {
             verify_tx(&transaction, blockchain, network_id, &mempool_state, filter).await;
 
         match verify_tx_ret {
-            Ok(mempool_state_lock) => {
-                RwLockUpgradableReadGuard::upgrade(mempool_state_lock).put(
+            Ok((mempool_state_lock, replaced_tx)) => {
+                let mut mempool_state = RwLockUpgradableReadGuard::upgrade(mempool_state_lock);
+                if let Some(replaced_tx) = replaced_tx {
+                    mempool_state.remove(&replaced_tx, EvictionReason::Replaced);
+                }
+                mempool_state.put(
                     &transaction,
                     tx_priority.unwrap_or(TxPriority::MediumPriority),
                 );
+                mempool_state.request_persist();
 
                 Ok(())
             }
@@ -735,6 +847,37 @@ impl Mempool {
         }
     }
 
+    /// Restores the transactions that were persisted before the last shutdown. The transactions
+    /// are verified against the current state of the blockchain and the ones that are no longer
+    /// valid are dropped. Returns the number of restored transactions.
+    pub async fn restore_transactions(&self) -> usize {
+        let transactions = match self.store {
+            Some(ref store) => store.get_transactions(),
+            None => return 0,
+        };
+
+        let mut num_restored = 0;
+        for (transaction, priority) in transactions {
+            let tx_hash: Blake2bHash = transaction.hash();
+
+            match self.add_transaction(transaction, Some(priority)).await {
+                Ok(_) => num_restored += 1,
+                // The transaction might have been received again in the meantime.
+                Err(VerifyErr::Known) => {}
+                Err(error) => {
+                    debug!(%tx_hash, %error, "Dropping persisted transaction");
+                    if let Some(ref mut store_changes) = self.state.write().store_changes {
+                        store_changes.remove(&tx_hash);
+                    }
+                }
+            }
+        }
+
+        self.persist_transactions().await;
+
+        num_restored
+    }
+
     /// Checks whether a transaction has been filtered
     pub fn is_filtered(&self, hash: &Blake2bHash) -> bool {
         self.filter.read().blacklisted(hash)
@@ -745,6 +888,59 @@ impl Mempool {
         self.filter.read().rules.clone()
     }
 
+    /// Estimates the fee per byte a transaction needs to pay in order to be included within the
+    /// next `target_blocks` micro blocks.
+    ///
+    /// If recent micro blocks were not (almost) full, block space is not contended and the
+    /// minimum fee per byte accepted by the mempool is returned. Otherwise, the pending
+    /// transactions are walked in the order in which they would be included in blocks until the
+    /// space of `target_blocks` blocks is exhausted. A transaction needs to pay more than the fee
+    /// per byte of the first pending transaction that does not fit, and at least the fee per byte
+    /// that was needed to be included in the recent full blocks.
+    pub fn estimate_fee_per_byte(&self, target_blocks: u32) -> f64 {
+        let min_fee_per_byte = self.get_rules().tx_fee_per_byte;
+
+        let contended_fee_per_byte = match self.fee_stats.read().contended_fee_per_byte() {
+            Some(fee_per_byte) => fee_per_byte.max(min_fee_per_byte),
+            None => return min_fee_per_byte,
+        };
+
+        let mut available_bytes =
+            target_blocks.max(1) as usize * MicroBlock::get_available_bytes(0);
+
+        let state = self.state.read();
+
+        // Control transactions are always included before regular transactions.
+        // We need to account for one extra byte per transaction to encode its final execution status
+        available_bytes = available_bytes.saturating_sub(
+            state.control_transactions.total_size + state.control_transactions.transactions.len(),
+        );
+
+        // Sort the regular transactions in the order in which they are included in blocks.
+        let mut best_transactions: Vec<_> = state
+            .regular_transactions
+            .best_transactions
+            .iter()
+            .collect();
+        best_transactions.sort_unstable_by(|(_, a), (_, b)| b.cmp(a));
+
+        for (tx_hash, tx_order) in best_transactions {
+            let tx_size = 1 + state
+                .regular_transactions
+                .get(tx_hash)
+                .unwrap()
+                .serialized_size();
+
+            if tx_size > available_bytes {
+                return tx_order.fee_per_byte.max(contended_fee_per_byte);
+            }
+
+            available_bytes -= tx_size;
+        }
+
+        contended_fee_per_byte
+    }
+
     /// Checks if a transactions is in the mempool, by its hash.
     pub fn contains_transaction_by_hash(&self, hash: &Blake2bHash) -> bool {
         self.state.read().contains(hash)
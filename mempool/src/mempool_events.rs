@@ -0,0 +1,28 @@
+use nimiq_transaction::Transaction;
+
+/// Event emitted by the mempool whenever a transaction enters or leaves the pool.
+#[derive(Clone, Debug)]
+pub enum MempoolEvent {
+    /// The transaction was added to the mempool.
+    Added(Transaction),
+    /// The transaction was removed from the mempool for the given reason.
+    Removed(Transaction, RemovalReason),
+    /// The subscriber fell behind and missed the given number of events. It needs to resync its
+    /// view of the mempool, e.g. by requesting the transactions that are in the mempool.
+    Lagged(u64),
+}
+
+/// The reason why a transaction left the mempool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RemovalReason {
+    /// The transaction was included in a block of the chain.
+    Included,
+    /// The transaction was taken from the mempool to build a block, which is not necessarily part
+    /// of the chain yet.
+    BlockBuilding,
+    /// The validity window of the transaction has passed.
+    Expired,
+    /// The transaction was evicted, either because it became invalid, because it was replaced by
+    /// another transaction or because the mempool was full.
+    Evicted,
+}
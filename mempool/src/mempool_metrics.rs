@@ -20,6 +20,7 @@ enum TxRemovedReason {
     AlreadyIncludedTx,
     Invalid,
     TooFull,
+    Replaced,
 }
 
 impl MempoolMetrics {
@@ -37,6 +38,7 @@ impl MempoolMetrics {
             EvictionReason::AlreadyIncluded => TxRemovedReason::AlreadyIncludedTx,
             EvictionReason::Invalid => TxRemovedReason::Invalid,
             EvictionReason::TooFull => TxRemovedReason::TooFull,
+            EvictionReason::Replaced => TxRemovedReason::Replaced,
             _ => return,
         };
         self.evicted_tx
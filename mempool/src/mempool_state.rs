@@ -3,8 +3,12 @@ use std::{
     sync::Arc,
 };
 
+use tokio::sync::broadcast::{channel as broadcast, Sender as BroadcastSender};
+
+use crate::mempool_events::{MempoolEvent, RemovalReason};
 #[cfg(feature = "metrics")]
 use crate::mempool_metrics::MempoolMetrics;
+use crate::mempool_store::MempoolStoreChanges;
 use crate::mempool_transactions::{MempoolTransactions, TxPriority};
 use nimiq_hash::{Blake2bHash, Hash};
 use nimiq_keys::Address;
@@ -36,12 +40,32 @@ pub(crate) struct MempoolState {
     pub(crate) creating_validators: HashMap<Address, Transaction>,
     pub(crate) creating_stakers: HashMap<Address, Transaction>,
 
+    // The changes to the persisted transactions that were not written yet, if persistence is
+    // enabled.
+    pub(crate) store_changes: Option<MempoolStoreChanges>,
+
+    // The minimum increase of the fee per byte (in percent) for a transaction to replace a pending
+    // transaction of the same sender, if replace-by-fee is enabled.
+    pub(crate) replacement_fee_bump: Option<u32>,
+
+    // Sender used to notify subscribers about transactions entering or leaving the mempool.
+    pub(crate) notifier: BroadcastSender<MempoolEvent>,
+
     #[cfg(feature = "metrics")]
     pub(crate) metrics: Arc<MempoolMetrics>,
 }
 
 impl MempoolState {
-    pub fn new(regular_txns_limit: usize, control_txns_limit: usize) -> Self {
+    const BROADCAST_MAX_CAPACITY: usize = 256;
+
+    pub fn new(
+        regular_txns_limit: usize,
+        control_txns_limit: usize,
+        store_changes: Option<MempoolStoreChanges>,
+        replacement_fee_bump: Option<u32>,
+    ) -> Self {
+        let (notifier, _rx) = broadcast(Self::BROADCAST_MAX_CAPACITY);
+
         MempoolState {
             regular_transactions: MempoolTransactions::new(regular_txns_limit),
             control_transactions: MempoolTransactions::new(control_txns_limit),
@@ -50,6 +74,9 @@ impl MempoolState {
             outgoing_stakers: HashMap::new(),
             creating_validators: HashMap::new(),
             creating_stakers: HashMap::new(),
+            store_changes,
+            replacement_fee_bump,
+            notifier,
             #[cfg(feature = "metrics")]
             metrics: Default::default(),
         }
@@ -86,6 +113,12 @@ impl MempoolState {
             self.regular_transactions.insert(tx, priority);
         }
 
+        if let Some(store_changes) = &mut self.store_changes {
+            store_changes.put(tx_hash.clone(), tx, priority);
+        }
+
+        self.notify(|| MempoolEvent::Added(tx.clone()));
+
         // Update the per sender state
         match self.state_by_sender.get_mut(&tx.sender) {
             None => {
@@ -191,6 +224,13 @@ impl MempoolState {
 
             self.remove_from_staking_state(&tx);
 
+            if let Some(store_changes) = &mut self.store_changes {
+                store_changes.remove(tx_hash);
+            }
+
+            let removal_reason = reason.removal_reason();
+            self.notify(|| MempoolEvent::Removed(tx.clone(), removal_reason));
+
             #[cfg(feature = "metrics")]
             self.metrics.note_evicted(reason);
 
@@ -210,11 +250,34 @@ impl MempoolState {
                     .or_else(|| self.control_transactions.delete(tx_hash))
                 {
                     self.remove_from_staking_state(&tx);
+
+                    if let Some(store_changes) = &mut self.store_changes {
+                        store_changes.remove(tx_hash);
+                    }
+
+                    self.notify(|| MempoolEvent::Removed(tx, RemovalReason::Evicted));
                 }
             }
         }
     }
 
+    /// Requests the changes to the transactions to be written to the database, if persistence is
+    /// enabled. The changes are written in the background, outside of the mempool lock.
+    pub(crate) fn request_persist(&self) {
+        if let Some(store_changes) = &self.store_changes {
+            store_changes.request_persist();
+        }
+    }
+
+    // Internal helper function that sends an event to the subscribers, if there are any.
+    // The event is only constructed if somebody is listening.
+    fn notify<F: FnOnce() -> MempoolEvent>(&self, event: F) {
+        if self.notifier.receiver_count() > 0 {
+            // Sending only fails if all receivers were dropped in the meantime.
+            let _ = self.notifier.send(event());
+        }
+    }
+
     // Internal helper function that takes care of removing staking transactions from the mempool state
     fn remove_from_staking_state(&mut self, tx: &Transaction) {
         // If it is an outgoing staking transaction then we have additional work.
@@ -275,6 +338,21 @@ pub(crate) enum EvictionReason {
     AlreadyIncluded,
     Invalid,
     TooFull,
+    Replaced,
+}
+
+impl EvictionReason {
+    // The reason that is reported to the subscribers of mempool events.
+    pub(crate) fn removal_reason(&self) -> RemovalReason {
+        match self {
+            EvictionReason::BlockBuilding => RemovalReason::BlockBuilding,
+            EvictionReason::AlreadyIncluded => RemovalReason::Included,
+            EvictionReason::Expired => RemovalReason::Expired,
+            EvictionReason::Invalid | EvictionReason::TooFull | EvictionReason::Replaced => {
+                RemovalReason::Evicted
+            }
+        }
+    }
 }
 
 pub(crate) struct SenderPendingState {
@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::io;
+use std::mem;
+use std::sync::Arc;
+
+use futures::lock::Mutex;
+use tokio::sync::Notify;
+
+use beserial::{Deserialize, ReadBytesExt, Serialize, SerializingError, WriteBytesExt};
+use nimiq_database::cursor::ReadCursor;
+use nimiq_database::{Database, Environment, ReadTransaction, WriteTransaction};
+use nimiq_database_value::{FromDatabaseValue, IntoDatabaseValue};
+use nimiq_hash::Blake2bHash;
+use nimiq_transaction::Transaction;
+
+use crate::mempool_transactions::TxPriority;
+
+/// A database that keeps a copy of the transactions in the mempool, such that they survive a
+/// restart of the node. The changes are collected in `MempoolStoreChanges` under the mempool lock
+/// and written to the database in batches outside of it.
+pub(crate) struct MempoolStore {
+    env: Environment,
+    // A database of the transactions in the mempool, indexed by their hash.
+    tx_db: Database,
+    // Held while a batch of changes is taken and written, such that batches are written in the
+    // order in which they were taken.
+    write_lock: Mutex<()>,
+}
+
+impl MempoolStore {
+    const TX_DB_NAME: &'static str = "MempoolTransactions";
+
+    pub fn new(env: Environment) -> Self {
+        let tx_db = env.open_database(Self::TX_DB_NAME.to_string());
+
+        MempoolStore {
+            env,
+            tx_db,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Writes the changes returned by `take_changes` to the database in a single transaction.
+    /// The transaction is committed on a blocking thread, so `take_changes` should only hold the
+    /// mempool lock while taking the changes.
+    pub async fn persist<F>(self: &Arc<Self>, take_changes: F)
+    where
+        F: FnOnce() -> StoreChanges,
+    {
+        let _write_guard = self.write_lock.lock().await;
+
+        let changes = take_changes();
+        if changes.is_empty() {
+            return;
+        }
+
+        let store = Arc::clone(self);
+        if let Err(error) = tokio::task::spawn_blocking(move || store.write(changes)).await {
+            error!(%error, "Failed to persist mempool transactions");
+        }
+    }
+
+    fn write(&self, changes: StoreChanges) {
+        let mut txn = WriteTransaction::new(&self.env);
+
+        for (tx_hash, stored_tx) in changes {
+            match stored_tx {
+                Some(stored_tx) => txn.put_reserve(&self.tx_db, &tx_hash, &stored_tx),
+                None => txn.remove(&self.tx_db, &tx_hash),
+            }
+        }
+
+        txn.commit();
+    }
+
+    /// Returns all transactions in the database, together with their priority. Pending changes
+    /// are not taken into account.
+    pub fn get_transactions(&self) -> Vec<(Transaction, TxPriority)> {
+        let txn = ReadTransaction::new(&self.env);
+        let mut cursor = txn.cursor(&self.tx_db);
+        let mut transactions = vec![];
+
+        let mut entry: Option<(Blake2bHash, StoredTransaction)> = cursor.first();
+        while let Some((_, stored_tx)) = entry {
+            transactions.push((stored_tx.transaction, stored_tx.priority));
+            entry = cursor.next();
+        }
+
+        transactions
+    }
+}
+
+/// Changes to the stored transactions by transaction hash. A `None` marks a removed transaction.
+pub(crate) type StoreChanges = HashMap<Blake2bHash, Option<StoredTransaction>>;
+
+/// The changes to the transactions in the mempool that were not written to the `MempoolStore`
+/// yet.
+pub(crate) struct MempoolStoreChanges {
+    changes: StoreChanges,
+    // Wakes up the task that writes the changes to the store.
+    writer: Arc<Notify>,
+}
+
+impl MempoolStoreChanges {
+    pub fn new(writer: Arc<Notify>) -> Self {
+        MempoolStoreChanges {
+            changes: HashMap::new(),
+            writer,
+        }
+    }
+
+    pub fn put(&mut self, tx_hash: Blake2bHash, transaction: &Transaction, priority: TxPriority) {
+        self.changes.insert(
+            tx_hash,
+            Some(StoredTransaction {
+                transaction: transaction.clone(),
+                priority,
+            }),
+        );
+    }
+
+    pub fn remove(&mut self, tx_hash: &Blake2bHash) {
+        self.changes.insert(tx_hash.clone(), None);
+    }
+
+    /// Wakes up the writer task if there are changes to write. Changes that are made while the
+    /// writer is busy are written together in its next batch.
+    pub fn request_persist(&self) {
+        if !self.changes.is_empty() {
+            self.writer.notify_one();
+        }
+    }
+
+    pub fn take(&mut self) -> StoreChanges {
+        mem::take(&mut self.changes)
+    }
+}
+
+/// A transaction in the mempool as stored in the database.
+pub(crate) struct StoredTransaction {
+    transaction: Transaction,
+    priority: TxPriority,
+}
+
+impl Serialize for StoredTransaction {
+    fn serialize<W: WriteBytesExt>(&self, writer: &mut W) -> Result<usize, SerializingError> {
+        let mut size = Serialize::serialize(&(self.priority as u8), writer)?;
+        size += Serialize::serialize(&self.transaction, writer)?;
+        Ok(size)
+    }
+
+    fn serialized_size(&self) -> usize {
+        (self.priority as u8).serialized_size() + self.transaction.serialized_size()
+    }
+}
+
+impl Deserialize for StoredTransaction {
+    fn deserialize<R: ReadBytesExt>(reader: &mut R) -> Result<Self, SerializingError> {
+        let priority = match u8::deserialize(reader)? {
+            1 => TxPriority::LowPriority,
+            2 => TxPriority::MediumPriority,
+            3 => TxPriority::HighPriority,
+            _ => return Err(SerializingError::InvalidValue),
+        };
+        let transaction = Deserialize::deserialize(reader)?;
+
+        Ok(StoredTransaction {
+            transaction,
+            priority,
+        })
+    }
+}
+
+impl IntoDatabaseValue for StoredTransaction {
+    fn database_byte_size(&self) -> usize {
+        self.serialized_size()
+    }
+
+    fn copy_into_database(&self, mut bytes: &mut [u8]) {
+        Serialize::serialize(&self, &mut bytes).unwrap();
+    }
+}
+
+impl FromDatabaseValue for StoredTransaction {
+    fn copy_from_database(bytes: &[u8]) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut cursor = io::Cursor::new(bytes);
+        Ok(Deserialize::deserialize(&mut cursor)?)
+    }
+}
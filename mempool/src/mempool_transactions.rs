@@ -30,7 +30,7 @@ pub enum TxPriority {
 #[derive(PartialEq)]
 pub struct BestTxOrder {
     priority: TxPriority,
-    fee_per_byte: f64,
+    pub(crate) fee_per_byte: f64,
     insertion_order: u64,
 }
 
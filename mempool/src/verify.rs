@@ -7,10 +7,11 @@ use std::{
 use nimiq_account::{Account, AccountTransactionInteraction, BasicAccount, StakingContract};
 use nimiq_blockchain::Blockchain;
 use nimiq_blockchain_interface::AbstractBlockchain;
-use nimiq_hash::Hash;
+use nimiq_hash::{Blake2bHash, Hash};
 use nimiq_primitives::account::AccountType;
 use nimiq_primitives::coin::Coin;
 use nimiq_primitives::networks::NetworkId;
+use nimiq_primitives::policy::Policy;
 use nimiq_transaction::account::staking_contract::{
     IncomingStakingTransactionData, OutgoingStakingTransactionProof,
 };
@@ -92,13 +93,21 @@ impl Display for VerifyErr {
 /// returns a result of a RwLockUpgradableReadGuard of the mempool such that in
 /// case of an accepted transaction (`Ok(RwLockUpgradableReadGuard)`), the
 /// caller can upgrade the lock and add the transaction to the mempool.
+/// If the transaction replaces a pending transaction (replace-by-fee), the hash of
+/// the replaced transaction is returned as well and the caller has to remove it.
 pub(crate) async fn verify_tx<'a>(
     transaction: &Transaction,
     blockchain: Arc<RwLock<Blockchain>>,
     network_id: Arc<NetworkId>,
     mempool_state: &'a Arc<RwLock<MempoolState>>,
     filter: Arc<RwLock<MempoolFilter>>,
-) -> Result<RwLockUpgradableReadGuard<'a, MempoolState>, VerifyErr> {
+) -> Result<
+    (
+        RwLockUpgradableReadGuard<'a, MempoolState>,
+        Option<Blake2bHash>,
+    ),
+    VerifyErr,
+> {
     // 1. Verify transaction signature (and other stuff)
     let mut tx = transaction.clone();
 
@@ -190,6 +199,45 @@ pub(crate) async fn verify_tx<'a>(
         Some(x) => x,
     };
 
+    let blockchain_sender_balance = sender_account.balance();
+
+    // Read the pending transactions balance of the sender
+    let mut sender_current_balance = Coin::ZERO;
+
+    if let Some(sender_state) = mempool_state.state_by_sender.get(&transaction.sender) {
+        sender_current_balance = sender_state.total;
+    }
+
+    // If the transaction doesn't fit into the sender balance together with the pending transactions
+    // of the sender, it might still replace one of them.
+    let mut replaced_tx = None;
+    if transaction.total_value() + sender_current_balance > blockchain_sender_balance {
+        if let Some(fee_bump) = mempool_state.replacement_fee_bump {
+            replaced_tx = find_replaceable_tx(
+                &mempool_state,
+                transaction,
+                blockchain_sender_balance,
+                fee_bump,
+            );
+        }
+
+        if let Some(ref replaced_tx) = replaced_tx {
+            log::debug!(
+                replaced_tx = %replaced_tx.hash::<Blake2bHash>(),
+                "Transaction replaces a pending transaction"
+            );
+            sender_current_balance -= replaced_tx.total_value();
+        }
+    }
+
+    // A pending staking transaction only conflicts with the transaction if it isn't replaced by it.
+    let conflicts = |pending_tx: Option<&Transaction>| match (pending_tx, &replaced_tx) {
+        (Some(pending_tx), Some(replaced_tx)) => {
+            pending_tx.hash::<Blake2bHash>() != replaced_tx.hash()
+        }
+        (pending_tx, _) => pending_tx.is_some(),
+    };
+
     // If it is an outgoing staking transaction then we have additional checks.
     if transaction.sender_type == AccountType::Staking {
         let accounts_tree = &blockchain.state().accounts.tree;
@@ -201,12 +249,14 @@ pub(crate) async fn verify_tx<'a>(
 
         // If the sender is already in the mempool then we don't accept another transaction.
         let duplicate = match data.clone() {
-            OutgoingStakingTransactionProof::DeleteValidator { proof } => mempool_state
-                .outgoing_validators
-                .contains_key(&proof.compute_signer()),
-            OutgoingStakingTransactionProof::Unstake { proof } => mempool_state
-                .outgoing_stakers
-                .contains_key(&proof.compute_signer()),
+            OutgoingStakingTransactionProof::DeleteValidator { proof } => conflicts(
+                mempool_state
+                    .outgoing_validators
+                    .get(&proof.compute_signer()),
+            ),
+            OutgoingStakingTransactionProof::Unstake { proof } => {
+                conflicts(mempool_state.outgoing_stakers.get(&proof.compute_signer()))
+            }
         };
 
         if duplicate {
@@ -240,12 +290,14 @@ pub(crate) async fn verify_tx<'a>(
 
         // If the recipient is already in the mempool then we don't accept another transaction.
         let duplicate = match data.clone() {
-            IncomingStakingTransactionData::CreateValidator { proof, .. } => mempool_state
-                .creating_validators
-                .contains_key(&proof.compute_signer()),
-            IncomingStakingTransactionData::CreateStaker { proof, .. } => mempool_state
-                .creating_stakers
-                .contains_key(&proof.compute_signer()),
+            IncomingStakingTransactionData::CreateValidator { proof, .. } => conflicts(
+                mempool_state
+                    .creating_validators
+                    .get(&proof.compute_signer()),
+            ),
+            IncomingStakingTransactionData::CreateStaker { proof, .. } => {
+                conflicts(mempool_state.creating_stakers.get(&proof.compute_signer()))
+            }
             _ => false,
         };
 
@@ -262,17 +314,11 @@ pub(crate) async fn verify_tx<'a>(
         }
     }
 
-    let blockchain_sender_balance = sender_account.balance();
     let blockchain_recipient_balance = recipient_account.balance();
 
     // Read the pending transactions balance
-    let mut sender_current_balance = Coin::ZERO;
     let mut recipient_current_balance = blockchain_recipient_balance;
 
-    if let Some(sender_state) = mempool_state.state_by_sender.get(&transaction.sender) {
-        sender_current_balance = sender_state.total;
-    }
-
     if let Some(recipient_state) = mempool_state.state_by_sender.get(&transaction.recipient) {
         // We found the recipient in the mempool. Subtract the mempool balance from the recipient balance
         recipient_current_balance -= recipient_state.total;
@@ -326,5 +372,45 @@ pub(crate) async fn verify_tx<'a>(
         return Err(VerifyErr::NotEnoughFunds);
     }
 
-    Ok(mempool_state)
+    Ok((mempool_state, replaced_tx.map(|tx| tx.hash())))
+}
+
+/// Returns the pending transaction of the same sender that the given transaction can replace, if
+/// any. The transaction must fit into the balance of the sender once the pending transaction is
+/// removed, their validity windows must overlap and the transaction must pay at least `fee_bump`
+/// percent more fee per byte. If several pending transactions qualify, the one with the lowest fee
+/// per byte is replaced.
+fn find_replaceable_tx(
+    mempool_state: &MempoolState,
+    transaction: &Transaction,
+    sender_balance: Coin,
+    fee_bump: u32,
+) -> Option<Transaction> {
+    let sender_state = mempool_state.state_by_sender.get(&transaction.sender)?;
+    let min_fee_per_byte_factor = 1.0 + fee_bump as f64 / 100.0;
+
+    sender_state
+        .txns
+        .iter()
+        .filter_map(|tx_hash| mempool_state.get(tx_hash))
+        .filter(|pending_tx| {
+            pending_tx
+                .validity_start_height
+                .abs_diff(transaction.validity_start_height)
+                < Policy::TRANSACTION_VALIDITY_WINDOW
+        })
+        .filter(|pending_tx| {
+            transaction.fee > pending_tx.fee
+                && transaction.fee_per_byte() >= pending_tx.fee_per_byte() * min_fee_per_byte_factor
+        })
+        .filter(|pending_tx| {
+            sender_state.total - pending_tx.total_value() + transaction.total_value()
+                <= sender_balance
+        })
+        .min_by(|a, b| {
+            a.fee_per_byte()
+                .partial_cmp(&b.fee_per_byte())
+                .expect("fees can't be NaN")
+        })
+        .cloned()
 }
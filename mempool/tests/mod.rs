@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use futures::StreamExt;
 use nimiq_primitives::coin::Coin;
 use parking_lot::RwLock;
 use rand::rngs::StdRng;
@@ -20,8 +21,10 @@ use nimiq_keys::{
     Address, KeyPair as SchnorrKeyPair, PrivateKey as SchnorrPrivateKey,
     PublicKey as SchnorrPublicKey, SecureGenerate,
 };
+use nimiq_mempool::filter::MempoolRules;
 use nimiq_mempool::mempool::Mempool;
-use nimiq_mempool::{config::MempoolConfig, mempool_transactions::TxPriority};
+use nimiq_mempool::mempool_events::{MempoolEvent, RemovalReason};
+use nimiq_mempool::{config::MempoolConfig, mempool_transactions::TxPriority, verify::VerifyErr};
 use nimiq_network_mock::{MockHub, MockId, MockNetwork, MockPeerId};
 use nimiq_primitives::{networks::NetworkId, policy::Policy};
 use nimiq_test_log::test;
@@ -282,6 +285,35 @@ fn create_dummy_micro_block(transactions: Option<Vec<Transaction>>) -> Block {
     Block::Micro(micro_block)
 }
 
+/// Creates a blockchain with a genesis validator from the accounts added to the genesis builder.
+fn blockchain_from_genesis(mut genesis_builder: GenesisBuilder) -> Arc<RwLock<Blockchain>> {
+    let mut rng = StdRng::seed_from_u64(0);
+    let time = Arc::new(OffsetTime::new());
+    let env = VolatileEnvironment::new(11).unwrap();
+
+    // Add a validator
+    genesis_builder.with_genesis_validator(
+        Address::from(&SchnorrKeyPair::generate(&mut rng)),
+        SchnorrPublicKey::from([0u8; 32]),
+        BlsKeyPair::generate(&mut rng).public_key,
+        Address::default(),
+    );
+
+    let genesis_info = genesis_builder.generate(env.clone()).unwrap();
+
+    Arc::new(RwLock::new(
+        Blockchain::with_genesis(
+            env,
+            BlockchainConfig::default(),
+            time,
+            NetworkId::UnitAlbatross,
+            genesis_info.block,
+            genesis_info.accounts,
+        )
+        .unwrap(),
+    ))
+}
+
 #[test(tokio::test)]
 async fn push_same_tx_twice() {
     // Generate and sign transaction from an address
@@ -341,6 +373,438 @@ async fn push_same_tx_twice() {
     assert_eq!(txns.len(), 1);
 }
 
+#[test(tokio::test)]
+async fn it_can_restore_persisted_transactions() {
+    // Generate and sign transactions from an address
+    let num_txns = 2;
+    let mut mempool_transactions = vec![];
+    let sender_balances = vec![10000; 1];
+    let recipient_balances = vec![0; num_txns];
+    let mut genesis_builder = GenesisBuilder::default();
+
+    // Generate recipient accounts
+    let recipient_accounts = generate_accounts(recipient_balances, &mut genesis_builder, false);
+    // Generate sender accounts
+    let sender_accounts = generate_accounts(sender_balances, &mut genesis_builder, true);
+
+    // Generate transactions
+    for recipient in recipient_accounts.iter() {
+        let mempool_transaction = TestTransaction {
+            fee: 0,
+            value: 10,
+            recipient: recipient.clone(),
+            sender: sender_accounts[0].clone(),
+        };
+        mempool_transactions.push(mempool_transaction);
+    }
+    let (txns, _) = generate_transactions(mempool_transactions, true);
+
+    let blockchain = blockchain_from_genesis(genesis_builder);
+
+    let mempool_config = MempoolConfig {
+        persistent: true,
+        ..Default::default()
+    };
+
+    let mempool = Mempool::new(Arc::clone(&blockchain), mempool_config.clone());
+    for tx in txns.iter() {
+        mempool.add_transaction(tx.clone(), None).await.unwrap();
+    }
+    mempool.persist_transactions().await;
+    drop(mempool);
+
+    // A new mempool restores the transactions of the previous one.
+    let mempool = Mempool::new(Arc::clone(&blockchain), mempool_config.clone());
+    assert_eq!(mempool.num_transactions(), 0);
+    assert_eq!(mempool.restore_transactions().await, num_txns);
+    for tx in txns.iter() {
+        assert!(mempool.contains_transaction_by_hash(&tx.hash()));
+    }
+
+    // Transactions that left the mempool are no longer restored.
+    let (obtained_txns, _) = mempool.get_transactions_for_block(usize::MAX);
+    assert_eq!(obtained_txns.len(), num_txns);
+    mempool.persist_transactions().await;
+    drop(mempool);
+
+    let mempool = Mempool::new(Arc::clone(&blockchain), mempool_config);
+    assert_eq!(mempool.restore_transactions().await, 0);
+}
+
+#[test(tokio::test)]
+async fn it_can_replace_transactions_by_fee() {
+    let mut genesis_builder = GenesisBuilder::default();
+
+    // Generate recipient accounts
+    let recipient_accounts = generate_accounts(vec![0; 3], &mut genesis_builder, false);
+    // Generate a sender account that can only pay for one transaction at a time
+    let sender_accounts = generate_accounts(vec![13], &mut genesis_builder, true);
+
+    // The second transaction pays a higher fee, the third one doesn't pay enough to replace it.
+    let mempool_transactions = vec![(1, 10), (2, 9), (2, 9)]
+        .into_iter()
+        .zip(recipient_accounts.iter())
+        .map(|((fee, value), recipient)| TestTransaction {
+            fee,
+            value,
+            recipient: recipient.clone(),
+            sender: sender_accounts[0].clone(),
+        })
+        .collect();
+    let (txns, _) = generate_transactions(mempool_transactions, true);
+
+    let blockchain = blockchain_from_genesis(genesis_builder);
+
+    // Without replace-by-fee, the second transaction is rejected.
+    let mempool = Mempool::new(Arc::clone(&blockchain), MempoolConfig::default());
+    mempool
+        .add_transaction(txns[0].clone(), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        mempool.add_transaction(txns[1].clone(), None).await,
+        Err(VerifyErr::NotEnoughFunds)
+    );
+
+    // With replace-by-fee, it replaces the first one.
+    let mempool_config = MempoolConfig {
+        replacement_fee_bump: Some(10),
+        ..Default::default()
+    };
+    let mempool = Mempool::new(Arc::clone(&blockchain), mempool_config);
+    mempool
+        .add_transaction(txns[0].clone(), None)
+        .await
+        .unwrap();
+    mempool
+        .add_transaction(txns[1].clone(), None)
+        .await
+        .unwrap();
+    assert_eq!(mempool.num_transactions(), 1);
+    assert!(mempool.contains_transaction_by_hash(&txns[1].hash()));
+
+    // The fee of the third transaction is not higher than the one of the second.
+    assert_eq!(
+        mempool.add_transaction(txns[2].clone(), None).await,
+        Err(VerifyErr::NotEnoughFunds)
+    );
+    assert!(mempool.contains_transaction_by_hash(&txns[1].hash()));
+}
+
+#[test(tokio::test)]
+async fn it_can_replace_staking_transactions_by_fee() {
+    let mut genesis_builder = GenesisBuilder::default();
+
+    // Generate a staker account that can only pay for one staker creation at a time
+    let staker_account = generate_accounts(vec![1000], &mut genesis_builder, true).remove(0);
+
+    // The second transaction creates the same staker as the first one, but pays a higher fee. The
+    // fee of the third one is not high enough to replace the second one.
+    let txns: Vec<_> = [10, 100, 101]
+        .into_iter()
+        .map(|fee| {
+            TransactionBuilder::new_create_staker(
+                &staker_account.keypair,
+                &staker_account.keypair,
+                None,
+                Coin::from_u64_unchecked(800),
+                Coin::from_u64_unchecked(fee),
+                1,
+                NetworkId::UnitAlbatross,
+            )
+            .unwrap()
+        })
+        .collect();
+
+    let blockchain = blockchain_from_genesis(genesis_builder);
+
+    let mempool_config = MempoolConfig {
+        replacement_fee_bump: Some(10),
+        ..Default::default()
+    };
+    let mempool = Mempool::new(blockchain, mempool_config);
+    mempool
+        .add_transaction(txns[0].clone(), None)
+        .await
+        .unwrap();
+
+    // The pending staker creation of the replaced transaction doesn't conflict with the new one.
+    mempool
+        .add_transaction(txns[1].clone(), None)
+        .await
+        .unwrap();
+    assert_eq!(mempool.num_transactions(), 1);
+    assert!(mempool.contains_transaction_by_hash(&txns[1].hash()));
+
+    // The staker creation is now pending through the replacing transaction.
+    assert_eq!(
+        mempool.add_transaction(txns[2].clone(), None).await,
+        Err(VerifyErr::Filtered)
+    );
+
+    // Once the replacing transaction leaves the mempool, the staker creation is no longer pending.
+    let (control_txns, _) = mempool.get_control_transactions_for_block(10_000);
+    assert_eq!(control_txns, vec![txns[1].clone()]);
+    mempool
+        .add_transaction(txns[2].clone(), None)
+        .await
+        .unwrap();
+}
+
+#[test(tokio::test)]
+async fn it_estimates_min_fee_per_byte_without_full_blocks() {
+    let mut genesis_builder = GenesisBuilder::default();
+
+    // Generate accounts
+    let recipient_accounts = generate_accounts(vec![0; 3], &mut genesis_builder, false);
+    let sender_accounts = generate_accounts(vec![10000; 3], &mut genesis_builder, true);
+
+    let mempool_transactions = recipient_accounts
+        .iter()
+        .zip(sender_accounts.iter())
+        .enumerate()
+        .map(|(i, (recipient, sender))| TestTransaction {
+            fee: 1000 * (i as u64 + 1),
+            value: 10,
+            recipient: recipient.clone(),
+            sender: sender.clone(),
+        })
+        .collect();
+    let (txns, _) = generate_transactions(mempool_transactions, true);
+
+    let blockchain = blockchain_from_genesis(genesis_builder);
+
+    let mempool_config = MempoolConfig {
+        filter_rules: MempoolRules {
+            tx_fee_per_byte: 1.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mempool = Mempool::new(blockchain, mempool_config);
+
+    for tx in txns {
+        mempool.add_transaction(tx, None).await.unwrap();
+    }
+    assert_eq!(mempool.num_transactions(), 3);
+
+    // There are no full blocks yet, so the minimum fee per byte is enough to be included.
+    assert_eq!(mempool.estimate_fee_per_byte(1), 1.0);
+    assert_eq!(mempool.estimate_fee_per_byte(10), 1.0);
+}
+
+#[test(tokio::test)]
+async fn it_estimates_fee_per_byte_with_full_blocks() {
+    let mut genesis_builder = GenesisBuilder::default();
+
+    // Generate accounts
+    let recipient_accounts = generate_accounts(vec![0; 4], &mut genesis_builder, false);
+    let sender_accounts = generate_accounts(vec![10000; 4], &mut genesis_builder, true);
+
+    // The last transaction fills the recent blocks, the others are pending in the mempool.
+    let mempool_transactions = recipient_accounts
+        .iter()
+        .zip(sender_accounts.iter())
+        .enumerate()
+        .map(|(i, (recipient, sender))| TestTransaction {
+            fee: if i < 3 { 1000 * (i as u64 + 1) } else { 500 },
+            value: 10,
+            recipient: recipient.clone(),
+            sender: sender.clone(),
+        })
+        .collect();
+    let (mut txns, _) = generate_transactions(mempool_transactions, true);
+    let block_tx = txns.pop().unwrap();
+
+    let blockchain = blockchain_from_genesis(genesis_builder);
+
+    let mempool_config = MempoolConfig {
+        filter_rules: MempoolRules {
+            tx_fee_per_byte: 1.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mempool = Mempool::new(blockchain, mempool_config);
+
+    for tx in txns {
+        mempool.add_transaction(tx, None).await.unwrap();
+    }
+    assert_eq!(mempool.num_transactions(), 3);
+
+    // Fill the available space of the recent blocks.
+    let num_block_txns = MicroBlock::get_available_bytes(0)
+        / ExecutedTransaction::Ok(block_tx.clone()).serialized_size();
+    let full_blocks: Vec<_> = (1..=10)
+        .map(|block_number| {
+            let mut block = create_dummy_micro_block(Some(vec![block_tx.clone(); num_block_txns]));
+            if let Block::Micro(ref mut micro_block) = block {
+                micro_block.header.block_number = block_number;
+            }
+            (block.hash(), block)
+        })
+        .collect();
+    mempool.mempool_update(&full_blocks, &[]);
+
+    // All pending transactions fit in the next block, but recent full blocks only included
+    // transactions paying at least the fee per byte of the block transaction.
+    assert!(block_tx.fee_per_byte() > 1.0);
+    assert_eq!(mempool.estimate_fee_per_byte(1), block_tx.fee_per_byte());
+    assert_eq!(mempool.estimate_fee_per_byte(10), block_tx.fee_per_byte());
+
+    // Once the full blocks are reverted, block space is no longer contended.
+    mempool.mempool_update(&[], &full_blocks);
+    assert_eq!(mempool.estimate_fee_per_byte(1), 1.0);
+}
+
+#[test(tokio::test)]
+async fn it_cleans_up_transactions_and_fee_statistics() {
+    let mut genesis_builder = GenesisBuilder::default();
+
+    // Generate accounts
+    let recipient_accounts = generate_accounts(vec![0; 2], &mut genesis_builder, false);
+    let sender_accounts = generate_accounts(vec![10000; 2], &mut genesis_builder, true);
+
+    let mempool_transactions = recipient_accounts
+        .iter()
+        .zip(sender_accounts.iter())
+        .map(|(recipient, sender)| TestTransaction {
+            fee: 1000,
+            value: 10,
+            recipient: recipient.clone(),
+            sender: sender.clone(),
+        })
+        .collect();
+    let (mut txns, _) = generate_transactions(mempool_transactions, true);
+    let block_tx = txns.pop().unwrap();
+
+    let blockchain = blockchain_from_genesis(genesis_builder);
+
+    let mempool_config = MempoolConfig {
+        filter_rules: MempoolRules {
+            tx_fee_per_byte: 1.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mempool = Mempool::new(blockchain, mempool_config);
+    mempool
+        .add_transaction(txns[0].clone(), None)
+        .await
+        .unwrap();
+
+    // Fill the available space of the recent blocks.
+    let num_block_txns = MicroBlock::get_available_bytes(0)
+        / ExecutedTransaction::Ok(block_tx.clone()).serialized_size();
+    let full_blocks: Vec<_> = (1..=10)
+        .map(|block_number| {
+            let mut block = create_dummy_micro_block(Some(vec![block_tx.clone(); num_block_txns]));
+            if let Block::Micro(ref mut micro_block) = block {
+                micro_block.header.block_number = block_number;
+            }
+            (block.hash(), block)
+        })
+        .collect();
+    mempool.mempool_update(&full_blocks, &[]);
+    assert_eq!(mempool.estimate_fee_per_byte(1), block_tx.fee_per_byte());
+
+    // The pending transaction was not included in the blockchain and is kept, but the fee
+    // statistics of the blocks before the adopted history are dropped.
+    mempool.mempool_clean_up();
+    assert!(mempool.contains_transaction_by_hash(&txns[0].hash()));
+    assert_eq!(mempool.estimate_fee_per_byte(1), 1.0);
+}
+
+#[test(tokio::test)]
+async fn it_notifies_about_added_and_removed_transactions() {
+    let mut genesis_builder = GenesisBuilder::default();
+
+    // Generate accounts
+    let recipient_accounts = generate_accounts(vec![0; 2], &mut genesis_builder, false);
+    let sender_accounts = generate_accounts(vec![10000; 2], &mut genesis_builder, true);
+
+    let mempool_transactions = recipient_accounts
+        .iter()
+        .zip(sender_accounts.iter())
+        .map(|(recipient, sender)| TestTransaction {
+            fee: 0,
+            value: 10,
+            recipient: recipient.clone(),
+            sender: sender.clone(),
+        })
+        .collect();
+    let (txns, txns_len) = generate_transactions(mempool_transactions, true);
+
+    let blockchain = blockchain_from_genesis(genesis_builder);
+
+    let mempool = Mempool::new(blockchain, MempoolConfig::default());
+    let mut events = mempool.notifier_as_stream();
+
+    for tx in &txns {
+        mempool.add_transaction(tx.clone(), None).await.unwrap();
+    }
+
+    for tx in &txns {
+        match events.next().await {
+            Some(MempoolEvent::Added(added_tx)) => assert_eq!(&added_tx, tx),
+            event => panic!("Unexpected event: {:?}", event),
+        }
+    }
+
+    // Collecting the transactions for a block removes them from the mempool.
+    let (block_txns, _) = mempool.get_transactions_for_block(txns_len);
+    assert_eq!(block_txns.len(), 2);
+
+    for tx in &block_txns {
+        match events.next().await {
+            Some(MempoolEvent::Removed(removed_tx, RemovalReason::BlockBuilding)) => {
+                assert_eq!(&removed_tx, tx)
+            }
+            event => panic!("Unexpected event: {:?}", event),
+        }
+    }
+}
+
+#[test(tokio::test)]
+async fn it_reports_missed_notifications() {
+    let mut genesis_builder = GenesisBuilder::default();
+    let num_txns = 150;
+
+    // Generate accounts
+    let recipient_accounts = generate_accounts(vec![0; num_txns], &mut genesis_builder, false);
+    let sender_accounts = generate_accounts(vec![10000; num_txns], &mut genesis_builder, true);
+
+    let mempool_transactions = recipient_accounts
+        .iter()
+        .zip(sender_accounts.iter())
+        .map(|(recipient, sender)| TestTransaction {
+            fee: 0,
+            value: 10,
+            recipient: recipient.clone(),
+            sender: sender.clone(),
+        })
+        .collect();
+    let (txns, txns_len) = generate_transactions(mempool_transactions, true);
+
+    let blockchain = blockchain_from_genesis(genesis_builder);
+
+    let mempool = Mempool::new(blockchain, MempoolConfig::default());
+    let mut events = mempool.notifier_as_stream();
+
+    // Adding the transactions and collecting them for a block emits more events than the
+    // subscriber buffers, since it doesn't consume any of them in the meantime.
+    for tx in &txns {
+        mempool.add_transaction(tx.clone(), None).await.unwrap();
+    }
+    let (block_txns, _) = mempool.get_transactions_for_block(txns_len);
+    assert_eq!(block_txns.len(), num_txns);
+
+    match events.next().await {
+        Some(MempoolEvent::Lagged(missed_events)) => assert!(missed_events > 0),
+        event => panic!("Unexpected event: {:?}", event),
+    }
+}
+
 #[test(tokio::test)]
 async fn valid_tx_not_in_blockchain() {
     // Generate and sign transaction from an address
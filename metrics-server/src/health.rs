@@ -0,0 +1,85 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use serde::Serialize;
+use time::{Duration, OffsetDateTime};
+
+use nimiq_blockchain_interface::AbstractBlockchain;
+use nimiq_blockchain_proxy::BlockchainProxy;
+use nimiq_consensus::ConsensusProxy;
+use nimiq_network_interface::network::Network;
+
+/// The maximum age of the head block for the node to be considered ready.
+const MAX_HEAD_AGE: Duration = Duration::minutes(1);
+
+/// The result of a readiness check, as it is reported by the `/ready` endpoint.
+#[derive(Serialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub failures: Vec<String>,
+}
+
+/// Determines whether the node is ready to serve requests.
+pub struct ReadinessCheck {
+    is_consensus_established: Box<dyn Fn() -> bool + Send + Sync>,
+    blockchain_proxy: BlockchainProxy,
+    network: Arc<nimiq_network_libp2p::Network>,
+    min_peers: usize,
+    validator_active: Option<Arc<AtomicBool>>,
+}
+
+impl ReadinessCheck {
+    pub fn new<TNetwork: Network>(
+        consensus_proxy: ConsensusProxy<TNetwork>,
+        network: Arc<nimiq_network_libp2p::Network>,
+        min_peers: usize,
+        validator_active: Option<Arc<AtomicBool>>,
+    ) -> Self {
+        Self {
+            blockchain_proxy: consensus_proxy.blockchain.clone(),
+            is_consensus_established: Box::new(move || consensus_proxy.is_established()),
+            network,
+            min_peers,
+            validator_active,
+        }
+    }
+
+    pub fn check(&self) -> Readiness {
+        let mut failures = vec![];
+
+        if !(self.is_consensus_established)() {
+            failures.push("Consensus is not established".to_string());
+        }
+
+        let head_timestamp = self.blockchain_proxy.read().timestamp();
+        let head_age = OffsetDateTime::now_utc()
+            - OffsetDateTime::from_unix_timestamp_nanos(head_timestamp as i128 * 1_000_000)
+                .unwrap_or(OffsetDateTime::UNIX_EPOCH);
+        if head_age > MAX_HEAD_AGE {
+            failures.push(format!(
+                "Head block is {} seconds old, at most {} seconds allowed",
+                head_age.whole_seconds(),
+                MAX_HEAD_AGE.whole_seconds()
+            ));
+        }
+
+        let peer_count = self.network.peer_count();
+        if peer_count < self.min_peers {
+            failures.push(format!(
+                "Connected to {} peers, at least {} required",
+                peer_count, self.min_peers
+            ));
+        }
+
+        if let Some(validator_active) = &self.validator_active {
+            if !validator_active.load(Ordering::Acquire) {
+                failures.push("Validator is not active in the current epoch".to_string());
+            }
+        }
+
+        Readiness {
+            ready: failures.is_empty(),
+            failures,
+        }
+    }
+}
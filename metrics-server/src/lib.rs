@@ -8,16 +8,19 @@ use tokio_metrics::TaskMonitor;
 
 use crate::chain::BlockMetrics;
 use crate::consensus::ConsensusMetrics;
+use crate::health::ReadinessCheck;
 use crate::mempool::MempoolMetrics;
 use crate::network::NetworkMetrics;
 use crate::server::metrics_server;
 #[cfg(tokio_unstable)]
 use crate::tokio_runtime::TokioRuntimeMetrics;
 use crate::tokio_task::TokioTaskMetrics;
+use crate::validator::ValidatorMetrics;
 use nimiq_blockchain_proxy::BlockchainProxy;
 use nimiq_consensus::ConsensusProxy;
 use nimiq_mempool::mempool::Mempool;
 use nimiq_network_interface::network::Network;
+use nimiq_validator::validator::ValidatorProxy;
 use prometheus_client::metrics::MetricType;
 use std::sync::Arc;
 #[cfg(tokio_unstable)]
@@ -25,12 +28,14 @@ use tokio_metrics::RuntimeMonitor;
 
 mod chain;
 mod consensus;
+mod health;
 mod mempool;
 mod network;
 mod server;
 #[cfg(tokio_unstable)]
 mod tokio_runtime;
 mod tokio_task;
+mod validator;
 
 #[derive(Clone)]
 pub struct NimiqTaskMonitor {
@@ -75,25 +80,41 @@ impl<T: Encode + Sized> EncodeMetric for NumericClosureMetric<T> {
     }
 }
 
+// Ignoring clippy warning because there wouldn't be much to be gained by refactoring this,
+// except making clippy happy
+#[allow(clippy::too_many_arguments)]
 pub fn start_metrics_server<TNetwork: Network>(
     addr: SocketAddr,
     blockchain_proxy: BlockchainProxy,
     mempool: Option<Arc<Mempool>>,
     consensus_proxy: ConsensusProxy<TNetwork>,
     network: Arc<nimiq_network_libp2p::Network>,
+    validator: Option<ValidatorProxy>,
+    min_peers: usize,
     task_monitors: &[NimiqTaskMonitor],
 ) {
     let mut registry = Registry::default();
     let nimiq_registry = registry.sub_registry_with_prefix("nimiq");
 
     BlockMetrics::register(nimiq_registry, blockchain_proxy);
-    ConsensusMetrics::register(nimiq_registry, consensus_proxy);
-    NetworkMetrics::register(nimiq_registry, network);
+    ConsensusMetrics::register(nimiq_registry, consensus_proxy.clone());
+    NetworkMetrics::register(nimiq_registry, Arc::clone(&network));
 
     if let Some(mempool) = mempool {
         MempoolMetrics::register(nimiq_registry, mempool);
     }
 
+    if let Some(validator) = &validator {
+        ValidatorMetrics::register(nimiq_registry, Arc::clone(&validator.metrics));
+    }
+
+    let readiness = ReadinessCheck::new(
+        consensus_proxy,
+        network,
+        min_peers,
+        validator.map(|validator| validator.active),
+    );
+
     // Setup the task metrics
     let task_metrics = Arc::new(RwLock::new(TokioTaskMetrics::new()));
     task_metrics.write().register(
@@ -121,7 +142,7 @@ pub fn start_metrics_server<TNetwork: Network>(
     }
 
     // Spawn the metrics server
-    tokio::spawn(metrics_server(addr, registry));
+    tokio::spawn(metrics_server(addr, registry, readiness));
 
     // Spawn Tokio task monitor updaters
     for i in 0..task_monitors.len() {
@@ -14,8 +14,14 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
-pub async fn metrics_server(addr: SocketAddr, registry: Registry) -> Result<(), std::io::Error> {
-    let server = Server::bind(&addr).serve(MakeMetricService::new(registry));
+use crate::health::ReadinessCheck;
+
+pub async fn metrics_server(
+    addr: SocketAddr,
+    registry: Registry,
+    readiness: ReadinessCheck,
+) -> Result<(), std::io::Error> {
+    let server = Server::bind(&addr).serve(MakeMetricService::new(registry, readiness));
     info!("Metrics server on http://{}/metrics", server.local_addr());
     if let Err(e) = server.await {
         error!("server error: {}", e);
@@ -25,6 +31,7 @@ pub async fn metrics_server(addr: SocketAddr, registry: Registry) -> Result<(),
 
 pub struct MetricService {
     reg: Arc<RwLock<Registry>>,
+    readiness: Arc<ReadinessCheck>,
 }
 
 type SharedRegistry = Arc<RwLock<Registry>>;
@@ -44,6 +51,26 @@ impl MetricService {
             .body(Body::from(encoded))
             .unwrap()
     }
+    fn respond_with_health(&mut self) -> Response<Body> {
+        Response::builder()
+            .status(StatusCode::OK)
+            .header(hyper::header::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"healthy":true}"#))
+            .unwrap()
+    }
+    fn respond_with_readiness(&mut self) -> Response<Body> {
+        let readiness = self.readiness.check();
+        let status = if readiness.ready {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        };
+        Response::builder()
+            .status(status)
+            .header(hyper::header::CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_vec(&readiness).unwrap()))
+            .unwrap()
+    }
     fn respond_with_404_not_found(&mut self) -> Response<Body> {
         Response::builder()
             .status(StatusCode::NOT_FOUND)
@@ -67,6 +94,11 @@ impl Service<Request<Body>> for MetricService {
         let resp = if (req_method == Method::GET) && (req_path == "/metrics") {
             // Encode and serve metrics from registry.
             self.respond_with_metrics()
+        } else if (req_method == Method::GET) && (req_path == "/health") {
+            // The node is alive as long as it is able to respond.
+            self.respond_with_health()
+        } else if (req_method == Method::GET) && (req_path == "/ready") {
+            self.respond_with_readiness()
         } else {
             self.respond_with_404_not_found()
         };
@@ -76,12 +108,14 @@ impl Service<Request<Body>> for MetricService {
 
 pub struct MakeMetricService {
     reg: SharedRegistry,
+    readiness: Arc<ReadinessCheck>,
 }
 
 impl MakeMetricService {
-    pub fn new(registry: Registry) -> MakeMetricService {
+    pub fn new(registry: Registry, readiness: ReadinessCheck) -> MakeMetricService {
         MakeMetricService {
             reg: Arc::new(RwLock::new(registry)),
+            readiness: Arc::new(readiness),
         }
     }
 }
@@ -97,7 +131,8 @@ impl<T> Service<T> for MakeMetricService {
 
     fn call(&mut self, _: T) -> Self::Future {
         let reg = self.reg.clone();
-        let fut = async move { Ok(MetricService { reg }) };
+        let readiness = self.readiness.clone();
+        let fut = async move { Ok(MetricService { reg, readiness }) };
         Box::pin(fut)
     }
 }
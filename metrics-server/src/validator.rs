@@ -0,0 +1,14 @@
+use prometheus_client::registry::Registry;
+
+use nimiq_validator::validator_metrics::ValidatorMetrics as ValidatorMetricsRecorder;
+use std::sync::Arc;
+
+pub struct ValidatorMetrics {}
+
+impl ValidatorMetrics {
+    pub fn register(registry: &mut Registry, metrics: Arc<ValidatorMetricsRecorder>) {
+        let sub_registry = registry.sub_registry_with_prefix("validator");
+
+        metrics.register(sub_registry);
+    }
+}
@@ -6,6 +6,8 @@ pub enum CloseReason {
     Other,
     RemoteClosed,
     Error,
+    /// The peer sent invalid data.
+    MaliciousPeer,
 }
 
 #[derive(Debug, Error)]
@@ -187,6 +187,14 @@ pub trait Message: RequestCommon<Kind = MessageMarker, Response = ()> {}
 impl<T: RequestCommon<Kind = RequestMarker>> Request for T {}
 impl<T: RequestCommon<Kind = MessageMarker, Response = ()>> Message for T {}
 
+/// Returns the name of a request type without its module path and generic parameters, e.g.
+/// `RequestHistoryChunk`. This is the name under which its rate limit can be configured.
+pub fn request_type_name<Req: RequestCommon>() -> &'static str {
+    let name = std::any::type_name::<Req>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
 pub fn peek_type(buffer: &[u8]) -> Result<RequestType, SerializingError> {
     let ty = u16::deserialize_from_vec(buffer)?;
     Ok(RequestType(ty))
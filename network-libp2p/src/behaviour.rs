@@ -143,6 +143,7 @@ impl NimiqBehaviour {
             Arc::clone(&contacts),
             config.seeds,
             config.discovery.required_services,
+            config.ban_list_path,
         );
 
         // Request Response behaviour
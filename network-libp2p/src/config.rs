@@ -5,11 +5,13 @@ use libp2p::{
     Multiaddr,
 };
 use std::{
-    collections::hash_map::DefaultHasher,
+    collections::{hash_map::DefaultHasher, HashMap},
     hash::{Hash, Hasher},
+    path::PathBuf,
     time::Duration,
 };
 
+use nimiq_database::Environment;
 use nimiq_hash::Blake2bHash;
 
 use crate::discovery::{
@@ -17,6 +19,21 @@ use crate::discovery::{
     peer_contacts::{PeerContact, Services},
 };
 
+/// The maximum number of requests of a type that a peer may send within a time window.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RateLimitConfig {
+    pub max_requests: u32,
+    pub time_window: Duration,
+}
+
+/// The maximum number of request bytes that a peer may send within a time window, across all
+/// request types.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ByteBudgetConfig {
+    pub max_bytes: u64,
+    pub time_window: Duration,
+}
+
 pub struct Config {
     pub keypair: Keypair,
     pub peer_contact: PeerContact,
@@ -26,6 +43,16 @@ pub struct Config {
     pub gossipsub: GossipsubConfig,
     pub memory_transport: bool,
     pub required_services: Services,
+    /// If set, the list of banned IP addresses is persisted to this file.
+    pub ban_list_path: Option<PathBuf>,
+    /// Overrides the rate limits of request types, keyed by the name of the request type, e.g.
+    /// `RequestHistoryChunk`. Request types without an entry use the limits they declare.
+    pub rate_limits: HashMap<String, RateLimitConfig>,
+    /// If set, limits the number of request bytes that each peer may send.
+    pub peer_byte_budget: Option<ByteBudgetConfig>,
+    /// If set, the peer contact book is persisted to this database environment and restored on
+    /// startup.
+    pub peer_contacts_env: Option<Environment>,
 }
 
 impl Config {
@@ -73,6 +100,10 @@ impl Config {
             gossipsub,
             memory_transport,
             required_services,
+            ban_list_path: None,
+            rate_limits: HashMap::new(),
+            peer_byte_budget: None,
+            peer_contacts_env: None,
         }
     }
 }
@@ -1,8 +1,11 @@
 use std::{
     collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    fs,
+    net::IpAddr,
+    path::{Path, PathBuf},
     sync::Arc,
     task::{Context, Poll, Waker},
-    time::{Duration, Instant, SystemTime},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use ip_network::IpNetwork;
@@ -26,6 +29,9 @@ use crate::discovery::peer_contacts::{PeerContactBook, Services};
 
 use super::handler::ConnectionPoolHandler;
 
+/// The maximum duration of a ban. Longer bans are effectively permanent.
+pub const MAX_BAN_DURATION: Duration = Duration::from_secs(60 * 60 * 24 * 365 * 100); // 100 years
+
 #[derive(Clone, Debug)]
 struct ConnectionPoolLimits {
     ip_count: HashMap<IpNetwork, usize>,
@@ -44,6 +50,7 @@ struct ConnectionPoolConfig {
     dialing_count_max: usize,
     retry_down_after: Duration,
     housekeeping_interval: Duration,
+    ban_duration: Duration,
 }
 
 impl Default for ConnectionPoolConfig {
@@ -58,6 +65,7 @@ impl Default for ConnectionPoolConfig {
             dialing_count_max: 3,
             retry_down_after: Duration::from_secs(60 * 10), // 10 minutes
             housekeeping_interval: Duration::from_secs(60 * 2), // 2 minutes
+            ban_duration: Duration::from_secs(60 * 10),     // 10 minutes
         }
     }
 }
@@ -175,6 +183,8 @@ pub struct ConnectionPoolBehaviour {
     limits: ConnectionPoolLimits,
     config: ConnectionPoolConfig,
     banned: HashMap<IpNetwork, SystemTime>,
+    ban_list_path: Option<PathBuf>,
+    peer_ips: HashMap<PeerId, IpAddr>,
     waker: Option<Waker>,
     housekeeping_timer: Interval,
 }
@@ -184,6 +194,7 @@ impl ConnectionPoolBehaviour {
         contacts: Arc<RwLock<PeerContactBook>>,
         seeds: Vec<Multiaddr>,
         required_services: Services,
+        ban_list_path: Option<PathBuf>,
     ) -> Self {
         let limits = ConnectionPoolLimits {
             ip_count: HashMap::new(),
@@ -192,6 +203,10 @@ impl ConnectionPoolBehaviour {
         };
         let config = ConnectionPoolConfig::default();
         let housekeeping_timer = tokio::time::interval(config.housekeeping_interval);
+        let banned = ban_list_path
+            .as_deref()
+            .map(Self::load_ban_list)
+            .unwrap_or_default();
 
         Self {
             contacts,
@@ -203,7 +218,9 @@ impl ConnectionPoolBehaviour {
             active: false,
             limits,
             config,
-            banned: HashMap::new(),
+            banned,
+            ban_list_path,
+            peer_ips: HashMap::new(),
             waker: None,
             housekeeping_timer,
         }
@@ -317,32 +334,129 @@ impl ConnectionPoolBehaviour {
         self.peer_ids.housekeeping();
         self.addresses.housekeeping();
 
-        for (ip, time) in self.banned.clone() {
-            if time < SystemTime::now() {
-                self.banned.remove(&ip);
-            }
+        let now = SystemTime::now();
+        let num_banned = self.banned.len();
+        self.banned.retain(|_, until| *until >= now);
+        if self.banned.len() != num_banned {
+            self.store_ban_list();
         }
 
         self.maintain_peers();
     }
 
-    pub fn _ban_ip(&mut self, ip: IpNetwork) {
-        if self
-            .banned
-            .insert(ip, SystemTime::now() + Duration::from_secs(60 * 10)) // 10 minutes
-            .is_none()
-        {
+    /// Bans an IP address or subnet for the given duration, or for the default ban duration if
+    /// none is given. Peers that are connected from within the banned range are disconnected.
+    /// Longer durations are capped to `MAX_BAN_DURATION`.
+    pub fn ban_ip(&mut self, ip: IpNetwork, duration: Option<Duration>) {
+        let duration = duration
+            .unwrap_or(self.config.ban_duration)
+            .min(MAX_BAN_DURATION);
+        let until = SystemTime::now()
+            .checked_add(duration)
+            .expect("Capped ban duration can't overflow");
+        if self.banned.insert(ip, until).is_none() {
             debug!(%ip, "IP added to banned set of peers");
         } else {
-            debug!(%ip, "IP already part of banned set of peers");
+            debug!(%ip, "IP already part of banned set of peers, updating expiry");
+        }
+        self.store_ban_list();
+
+        // Disconnect the peers that are now banned.
+        let banned_peers: Vec<PeerId> = self
+            .peer_ips
+            .iter()
+            .filter(|(_, peer_ip)| ip.contains(**peer_ip))
+            .map(|(peer_id, _)| *peer_id)
+            .collect();
+        for peer_id in banned_peers {
+            debug!(%peer_id, %ip, "Disconnecting banned peer");
+            self.actions
+                .push_back(NetworkBehaviourAction::CloseConnection {
+                    peer_id,
+                    connection: CloseConnection::All,
+                });
         }
+        self.wake();
+    }
+
+    /// Bans the IP address of a connected peer. Returns the banned IP address, or `None` if we
+    /// don't know the IP address of the peer.
+    pub fn ban_peer(&mut self, peer_id: &PeerId, duration: Option<Duration>) -> Option<IpNetwork> {
+        let ip = IpNetwork::from(*self.peer_ips.get(peer_id)?);
+        self.ban_ip(ip, duration);
+        Some(ip)
     }
 
-    pub fn _unban_ip(&mut self, ip: IpNetwork) {
+    /// Lifts the ban of an IP address or subnet. Returns whether it was banned.
+    pub fn unban_ip(&mut self, ip: IpNetwork) -> bool {
         if self.banned.remove(&ip).is_some() {
             debug!(%ip, "IP removed from banned set of peers");
+            self.store_ban_list();
+            true
         } else {
             debug!(%ip, "IP was not part of banned set of peers");
+            false
+        }
+    }
+
+    /// Returns the banned IP addresses and subnets, together with the time until which they are
+    /// banned.
+    pub fn banned_ips(&self) -> Vec<(IpNetwork, SystemTime)> {
+        self.banned
+            .iter()
+            .map(|(ip, until)| (*ip, *until))
+            .collect()
+    }
+
+    fn is_banned(&self, ip: IpAddr) -> bool {
+        let now = SystemTime::now();
+        self.banned
+            .iter()
+            .any(|(network, until)| *until >= now && network.contains(ip))
+    }
+
+    /// Loads the ban list from a file with one ban per line, consisting of the IP address or subnet
+    /// and the UNIX timestamp in seconds until which it is banned. Invalid lines are skipped.
+    fn load_ban_list(path: &Path) -> HashMap<IpNetwork, SystemTime> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(error) => {
+                debug!(path = %path.display(), %error, "Could not read ban list");
+                return HashMap::new();
+            }
+        };
+
+        content
+            .lines()
+            .filter_map(|line| {
+                let (ip, until) = line.trim().split_once(' ')?;
+                let ip = ip.parse::<IpNetwork>().ok()?;
+                let until = UNIX_EPOCH.checked_add(Duration::from_secs(until.parse().ok()?))?;
+                Some((ip, until))
+            })
+            .collect()
+    }
+
+    fn store_ban_list(&self) {
+        let path = match &self.ban_list_path {
+            Some(path) => path,
+            None => return,
+        };
+
+        let content: String = self
+            .banned
+            .iter()
+            .map(|(ip, until)| {
+                let until = until
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs();
+                format!("{} {}\n", ip, until)
+            })
+            .collect();
+
+        if let Err(error) = fs::write(path, content) {
+            warn!(path = %path.display(), %error, "Could not store ban list");
         }
     }
 }
@@ -399,22 +513,23 @@ impl NetworkBehaviour for ConnectionPoolBehaviour {
         }
 
         // Get IP from multiaddress if it exists.
-        let ip = match address.iter().next() {
-            Some(Protocol::Ip4(ip)) => {
-                IpNetwork::new_truncate(ip, self.config.ipv4_subnet_mask).ok()
-            }
-            Some(Protocol::Ip6(ip)) => {
-                IpNetwork::new_truncate(ip, self.config.ipv6_subnet_mask).ok()
-            }
+        let peer_ip = match address.iter().next() {
+            Some(Protocol::Ip4(ip)) => Some(IpAddr::from(ip)),
+            Some(Protocol::Ip6(ip)) => Some(IpAddr::from(ip)),
             _ => None,
         };
+        let ip = match peer_ip {
+            Some(IpAddr::V4(ip)) => IpNetwork::new_truncate(ip, self.config.ipv4_subnet_mask).ok(),
+            Some(IpAddr::V6(ip)) => IpNetwork::new_truncate(ip, self.config.ipv6_subnet_mask).ok(),
+            None => None,
+        };
 
         // If we have an IP, check connection limits per IP/subnet.
-        if let Some(ip) = ip {
+        if let (Some(peer_ip), Some(ip)) = (peer_ip, ip) {
             let mut close_connection = false;
 
-            if self.banned.get(&ip).is_some() {
-                debug!(%ip, "IP is banned");
+            if self.is_banned(peer_ip) {
+                debug!(%peer_ip, "IP is banned");
                 close_connection = true;
             }
 
@@ -467,6 +582,8 @@ impl NetworkBehaviour for ConnectionPoolBehaviour {
                 return;
             }
 
+            self.peer_ips.insert(*peer_id, peer_ip);
+
             // Increment peer counts per IP
             let value = self.limits.ip_count.entry(ip).or_insert(0);
             *value = value.saturating_add(1);
@@ -508,6 +625,8 @@ impl NetworkBehaviour for ConnectionPoolBehaviour {
 
         let address = endpoint.get_remote_address();
 
+        self.peer_ips.remove(peer_id);
+
         let ip = match address.iter().next() {
             Some(Protocol::Ip4(ip)) => {
                 IpNetwork::new_truncate(ip, self.config.ipv4_subnet_mask).unwrap()
@@ -0,0 +1,86 @@
+use std::io;
+
+use beserial::{Deserialize, Serialize};
+use nimiq_database::{Database, Environment, ReadTransaction, WriteTransaction};
+use nimiq_database_value::{FromDatabaseValue, IntoDatabaseValue};
+
+use super::peer_contacts::SignedPeerContact;
+
+/// The peer contacts as they are stored in the database.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct StoredPeerContacts {
+    #[beserial(len_type(u32))]
+    contacts: Vec<SignedPeerContact>,
+}
+
+impl IntoDatabaseValue for StoredPeerContacts {
+    fn database_byte_size(&self) -> usize {
+        self.serialized_size()
+    }
+
+    fn copy_into_database(&self, mut bytes: &mut [u8]) {
+        Serialize::serialize(&self, &mut bytes).unwrap();
+    }
+}
+
+impl FromDatabaseValue for StoredPeerContacts {
+    fn copy_from_database(bytes: &[u8]) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut cursor = io::Cursor::new(bytes);
+        Ok(Deserialize::deserialize(&mut cursor)?)
+    }
+}
+
+/// Persists the signed contacts of the peer contact book, such that a node can reconnect to the
+/// network after a restart even if the seed nodes are unavailable.
+pub struct PeerContactStore {
+    env: Environment,
+    database: Database,
+}
+
+impl PeerContactStore {
+    const DB_NAME: &'static str = "PeerContacts";
+    const CONTACTS_KEY: &'static str = "contacts";
+
+    pub fn new(env: Environment) -> Self {
+        let database = env.open_database(Self::DB_NAME.to_string());
+
+        Self { env, database }
+    }
+
+    /// Loads the stored peer contacts. Contacts with an invalid signature are skipped.
+    pub fn load(&self) -> Vec<SignedPeerContact> {
+        let stored: StoredPeerContacts = ReadTransaction::new(&self.env)
+            .get(&self.database, Self::CONTACTS_KEY)
+            .unwrap_or_default();
+
+        stored
+            .contacts
+            .into_iter()
+            .filter(|contact| {
+                let valid = contact.verify();
+                if !valid {
+                    warn!(peer_id = %contact.inner.peer_id(), "Ignoring stored peer contact with invalid signature");
+                }
+                valid
+            })
+            .collect()
+    }
+
+    /// Replaces the stored peer contacts with the given contacts of other peers. This writes to the
+    /// database, so it should not be called from async code.
+    pub fn store(&self, contacts: Vec<SignedPeerContact>) {
+        let stored = StoredPeerContacts { contacts };
+
+        debug!(
+            num_contacts = stored.contacts.len(),
+            "Storing peer contacts"
+        );
+
+        let mut txn = WriteTransaction::new(&self.env);
+        txn.put_reserve(&self.database, Self::CONTACTS_KEY, &stored);
+        txn.commit();
+    }
+}
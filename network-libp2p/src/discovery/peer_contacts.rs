@@ -389,6 +389,17 @@ impl PeerContactBook {
         self.insert(contact.sign(keypair));
     }
 
+    /// Returns the signed contacts of all other peers that are not seeds.
+    pub fn other_contacts(&self) -> Vec<SignedPeerContact> {
+        self.peer_contacts
+            .values()
+            .filter(|contact| {
+                contact.peer_id != self.own_peer_contact.peer_id && !contact.is_seed()
+            })
+            .map(|contact| contact.signed().clone())
+            .collect()
+    }
+
     pub fn get_own_contact(&self) -> &PeerContactInfo {
         &self.own_peer_contact
     }
@@ -16,8 +16,11 @@ pub const REQRES_PROTOCOL: &[u8] = b"/nimiq/reqres/0.0.1";
 pub const MESSAGE_PROTOCOL: &[u8] = b"/nimiq/message/0.0.1";
 pub const DISCOVERY_PROTOCOL: &[u8] = b"/nimiq/discovery/0.0.1";
 
+pub use ip_network::IpNetwork;
 pub use libp2p::{self, identity::Keypair, swarm::NetworkInfo, Multiaddr, PeerId};
 
-pub use config::Config;
+pub use config::{ByteBudgetConfig, Config, RateLimitConfig};
+pub use connection_pool::behaviour::MAX_BAN_DURATION;
 pub use error::NetworkError;
-pub use network::Network;
+pub use network::{ConnectionDirection, Network, PeerInfo};
+pub use nimiq_network_interface::request::request_type_name;
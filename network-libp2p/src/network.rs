@@ -5,15 +5,17 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 #[cfg(feature = "metrics")]
 use std::time::Instant;
+use std::time::{Duration, SystemTime};
 
 use async_trait::async_trait;
 use bytes::{Buf, Bytes};
 use futures::{ready, stream::BoxStream, Stream, StreamExt};
+use ip_network::IpNetwork;
 use libp2p::core::transport::MemoryTransport;
 use libp2p::gossipsub::PeerScoreParams;
 use libp2p::{
     core,
-    core::{muxing::StreamMuxerBox, transport::Boxed},
+    core::{muxing::StreamMuxerBox, transport::Boxed, ConnectedPoint},
     gossipsub::{
         error::PublishError, GossipsubEvent, GossipsubMessage, IdentTopic, MessageAcceptance,
         MessageId, TopicHash, TopicScoreParams,
@@ -34,6 +36,7 @@ use libp2p::{dns, tcp, websocket};
 use log::Instrument;
 use parking_lot::{Mutex, RwLock};
 use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::task::JoinHandle;
 use tokio::time::{Instant as TokioInstant, Interval};
 use tokio_stream::wrappers::{BroadcastStream, ReceiverStream};
 
@@ -45,14 +48,15 @@ use nimiq_network_interface::{
     },
     peer::CloseReason,
     request::{
-        peek_type, InboundRequestError, Message, OutboundRequestError, Request, RequestCommon,
-        RequestError, RequestType,
+        peek_type, request_type_name, InboundRequestError, Message, OutboundRequestError, Request,
+        RequestCommon, RequestError, RequestType,
     },
 };
 use nimiq_utils::time::OffsetTime;
 use nimiq_validator_network::validator_record::SignedValidatorRecord;
 
 use crate::discovery::behaviour::DiscoveryEvent;
+use crate::discovery::peer_contact_store::PeerContactStore;
 use crate::discovery::peer_contacts::{PeerContact, PeerContactBook, Services};
 #[cfg(feature = "metrics")]
 use crate::network_metrics::NetworkMetrics;
@@ -61,12 +65,19 @@ use crate::{
     behaviour::{NimiqBehaviour, NimiqEvent, NimiqNetworkBehaviourError, RequestResponseEvent},
     connection_pool::behaviour::ConnectionPoolEvent,
     dispatch::codecs::typed::{IncomingRequest, OutgoingResponse},
-    Config, NetworkError,
+    ByteBudgetConfig, Config, NetworkError, RateLimitConfig,
 };
 
 /// Maximum simultaneous libp2p connections per peer
 const MAX_CONNECTIONS_PER_PEER: u32 = 2;
 
+/// The key under which the byte budget of a peer is tracked in the peer request limits. It doesn't
+/// collide with the type ID of any request.
+const BYTE_BUDGET_KEY: u16 = u16::MAX;
+
+/// Interval in which the peer contact book is persisted, if persistence is enabled.
+const PEER_CONTACTS_STORE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
 type NimiqSwarm = Swarm<NimiqBehaviour>;
 
 #[derive(Debug)]
@@ -131,6 +142,32 @@ pub(crate) enum NetworkAction {
     DisconnectPeer {
         peer_id: PeerId,
     },
+    BanIp {
+        ip: IpNetwork,
+        duration: Option<Duration>,
+    },
+    BanPeer {
+        peer_id: PeerId,
+        duration: Option<Duration>,
+        output: oneshot::Sender<Option<IpNetwork>>,
+    },
+    UnbanIp {
+        ip: IpNetwork,
+        output: oneshot::Sender<bool>,
+    },
+    ListBanned {
+        output: oneshot::Sender<Vec<(IpNetwork, SystemTime)>>,
+    },
+    PeerInfo {
+        peer_id: PeerId,
+        output: oneshot::Sender<Option<PeerInfo>>,
+    },
+    ListPeerInfo {
+        output: oneshot::Sender<Vec<PeerInfo>>,
+    },
+    StorePeerContacts {
+        output: oneshot::Sender<()>,
+    },
 }
 
 struct ValidateMessage<P: Clone> {
@@ -156,6 +193,59 @@ impl<P: Clone> ValidateMessage<P> {
     }
 }
 
+/// The direction of the connection to a peer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectionDirection {
+    /// The peer connected to us.
+    Inbound,
+    /// We connected to the peer.
+    Outbound,
+}
+
+/// Information about a connected peer.
+#[derive(Clone, Debug)]
+pub struct PeerInfo {
+    pub peer_id: PeerId,
+    /// The address of the peer that the connection was established with.
+    pub address: Multiaddr,
+    pub direction: ConnectionDirection,
+    /// The services advertised in the peer contact, once the peer has completed discovery.
+    pub services: Option<Services>,
+    /// The user agent that the peer sent during identification.
+    pub user_agent: Option<String>,
+    /// The protocol version that the peer sent during identification.
+    pub protocol_version: Option<String>,
+    /// The gossipsub score of the peer.
+    pub score: Option<f64>,
+    /// The time at which the connection was established.
+    pub connected_since: SystemTime,
+    /// The number of requests that we sent to the peer.
+    pub requests_sent: u64,
+    /// The number of requests that we received from the peer.
+    pub requests_received: u64,
+}
+
+impl PeerInfo {
+    fn new(peer_id: PeerId, endpoint: &ConnectedPoint) -> Self {
+        Self {
+            peer_id,
+            address: endpoint.get_remote_address().clone(),
+            direction: if endpoint.is_dialer() {
+                ConnectionDirection::Outbound
+            } else {
+                ConnectionDirection::Inbound
+            },
+            services: None,
+            user_agent: None,
+            protocol_version: None,
+            score: None,
+            connected_since: SystemTime::now(),
+            requests_sent: 0,
+            requests_received: 0,
+        }
+    }
+}
+
 #[derive(Default)]
 struct TaskState {
     dht_puts: HashMap<QueryId, oneshot::Sender<Result<(), NetworkError>>>,
@@ -167,6 +257,8 @@ struct TaskState {
     requests_initiated: HashMap<RequestId, Instant>,
     response_channels: HashMap<RequestId, ResponseChannel<OutgoingResponse>>,
     receive_requests: HashMap<RequestType, mpsc::Sender<(Bytes, RequestId, PeerId)>>,
+    peer_infos: HashMap<PeerId, PeerInfo>,
+    peer_contact_store: Option<Arc<PeerContactStore>>,
 }
 
 #[derive(Clone, Debug)]
@@ -195,6 +287,10 @@ pub struct Network {
     validate_tx: mpsc::UnboundedSender<ValidateMessage<PeerId>>,
     /// Rate limiting capabilities
     peer_request_limits: Arc<Mutex<HashMap<PeerId, HashMap<u16, RateLimit>>>>,
+    /// Configured rate limits that override the ones declared by the request types
+    rate_limits: HashMap<String, RateLimitConfig>,
+    /// Configured number of request bytes that each peer may send
+    peer_byte_budget: Option<ByteBudgetConfig>,
     /// Metrics used for data analysis
     #[cfg(feature = "metrics")]
     metrics: Arc<NetworkMetrics>,
@@ -213,11 +309,26 @@ impl Network {
     ///
     pub async fn new(clock: Arc<OffsetTime>, config: Config) -> Self {
         let required_services = config.required_services;
-        // TODO: persist to disk
+        let rate_limits = config.rate_limits.clone();
+        let peer_byte_budget = config.peer_byte_budget;
         let own_peer_contact = config.peer_contact.clone();
-        let contacts = Arc::new(RwLock::new(PeerContactBook::new(
-            own_peer_contact.sign(&config.keypair),
-        )));
+        let mut contact_book = PeerContactBook::new(own_peer_contact.sign(&config.keypair));
+
+        // Restore the peer contacts that were persisted before the last shutdown.
+        let peer_contact_store = config
+            .peer_contacts_env
+            .clone()
+            .map(|env| Arc::new(PeerContactStore::new(env)));
+        if let Some(store) = &peer_contact_store {
+            let stored_contacts = store.load();
+            debug!(
+                num_contacts = stored_contacts.len(),
+                "Restoring stored peer contacts"
+            );
+            contact_book.insert_all_filtered(stored_contacts, required_services);
+            contact_book.house_keeping();
+        }
+        let contacts = Arc::new(RwLock::new(contact_book));
         let params = PeerScoreParams {
             ip_colocation_factor_threshold: 20.0,
             ..Default::default()
@@ -233,6 +344,7 @@ impl Network {
         let peer_request_limits = Arc::new(Mutex::new(HashMap::new()));
         let rate_limits_pending_deletion = Arc::new(Mutex::new(VecDeque::new()));
         let update_scores = tokio::time::interval(params.decay_interval);
+        let store_contacts = tokio::time::interval(PEER_CONTACTS_STORE_INTERVAL);
 
         #[cfg(feature = "metrics")]
         let metrics = Arc::new(NetworkMetrics::default());
@@ -247,6 +359,8 @@ impl Network {
             Arc::clone(&rate_limits_pending_deletion),
             update_scores,
             contacts,
+            peer_contact_store,
+            store_contacts,
             #[cfg(feature = "metrics")]
             metrics.clone(),
         ));
@@ -258,6 +372,8 @@ impl Network {
             action_tx,
             validate_tx,
             peer_request_limits,
+            rate_limits,
+            peer_byte_budget,
             #[cfg(feature = "metrics")]
             metrics,
             required_services,
@@ -365,9 +481,14 @@ impl Network {
         rate_limits_pending_deletion: Arc<Mutex<VecDeque<((PeerId, u16), TokioInstant)>>>,
         mut update_scores: Interval,
         contacts: Arc<RwLock<PeerContactBook>>,
+        peer_contact_store: Option<Arc<PeerContactStore>>,
+        mut store_contacts: Interval,
         #[cfg(feature = "metrics")] metrics: Arc<NetworkMetrics>,
     ) {
-        let mut task_state = TaskState::default();
+        let mut task_state = TaskState {
+            peer_contact_store,
+            ..Default::default()
+        };
 
         let peer_id = Swarm::local_peer_id(&swarm);
         let task_span = trace_span!("swarm task", peer_id=?peer_id);
@@ -411,13 +532,36 @@ impl Network {
                     _ = update_scores.tick() => {
                         swarm.behaviour().update_scores(Arc::clone(&contacts));
                     },
+                    _ = store_contacts.tick(), if task_state.peer_contact_store.is_some() => {
+                        if let Some(store) = &task_state.peer_contact_store {
+                            Self::spawn_store_contacts(store, &contacts);
+                        }
+                    },
                 };
             }
+
+            // Persist the peer contacts one last time when the network is dropped.
+            if let Some(store) = &task_state.peer_contact_store {
+                if let Err(error) = Self::spawn_store_contacts(store, &contacts).await {
+                    error!(%error, "Failed to store peer contacts");
+                }
+            }
         }
         .instrument(task_span)
         .await
     }
 
+    /// Stores the contacts of the peer contact book. The database write runs on a blocking task,
+    /// whose handle is returned.
+    fn spawn_store_contacts(
+        store: &Arc<PeerContactStore>,
+        contacts: &RwLock<PeerContactBook>,
+    ) -> JoinHandle<()> {
+        let store = Arc::clone(store);
+        let contacts = contacts.read().other_contacts();
+        tokio::task::spawn_blocking(move || store.store(contacts))
+    }
+
     fn handle_event(
         event: SwarmEvent<NimiqEvent, NimiqNetworkBehaviourError>,
         events_tx: &broadcast::Sender<NetworkEvent<PeerId>>,
@@ -442,6 +586,11 @@ impl Network {
                     "Connection established",
                 );
 
+                state
+                    .peer_infos
+                    .entry(peer_id)
+                    .or_insert_with(|| PeerInfo::new(peer_id, &endpoint));
+
                 if let Some(dial_errors) = concurrent_dial_errors {
                     for (addr, error) in dial_errors {
                         debug!(
@@ -495,6 +644,7 @@ impl Network {
                 // Remove Peer
                 if num_established == 0 {
                     connected_peers.write().remove(&peer_id);
+                    state.peer_infos.remove(&peer_id);
                     swarm.behaviour_mut().remove_peer(peer_id);
 
                     // Removes or marks to remove the respective rate limits.
@@ -639,6 +789,10 @@ impl Network {
                                 peer_id,
                                 peer_contact,
                             } => {
+                                if let Some(peer_info) = state.peer_infos.get_mut(&peer_id) {
+                                    peer_info.services = Some(peer_contact.services);
+                                }
+
                                 if connected_peers
                                     .write()
                                     .insert(peer_id, peer_contact)
@@ -715,6 +869,12 @@ impl Network {
                                     "Received identity",
                                 );
 
+                                if let Some(peer_info) = state.peer_infos.get_mut(&peer_id) {
+                                    peer_info.user_agent = Some(info.agent_version.clone());
+                                    peer_info.protocol_version =
+                                        Some(info.protocol_version.clone());
+                                }
+
                                 // Save identified peer listen addresses
                                 for listen_addr in info.listen_addrs {
                                     swarm.behaviour_mut().add_peer_address(peer_id, listen_addr);
@@ -760,6 +920,10 @@ impl Network {
                                 channel,
                             } => {
                                 // TODO Add rate limiting (per peer).
+                                if let Some(peer_info) = state.peer_infos.get_mut(&peer_id) {
+                                    peer_info.requests_received += 1;
+                                }
+
                                 if let Ok(type_id) = peek_type(&request) {
                                     trace!(
                                         %request_id,
@@ -1129,6 +1293,9 @@ impl Network {
                     "Request was sent to peer",
                 );
                 state.requests.insert(request_id, response_channel);
+                if let Some(peer_info) = state.peer_infos.get_mut(&peer_id) {
+                    peer_info.requests_sent += 1;
+                }
                 #[cfg(feature = "metrics")]
                 state.requests_initiated.insert(request_id, Instant::now());
                 if output.send(request_id).is_err() {
@@ -1178,6 +1345,84 @@ impl Network {
                     warn!(%peer_id, "Peer already closed");
                 }
             }
+            NetworkAction::BanIp { ip, duration } => {
+                swarm.behaviour_mut().pool.ban_ip(ip, duration);
+            }
+            NetworkAction::BanPeer {
+                peer_id,
+                duration,
+                output,
+            } => {
+                let ip = swarm.behaviour_mut().pool.ban_peer(&peer_id, duration);
+                if output.send(ip).is_err() {
+                    error!(%peer_id, error = "receiver hung up", "could not send ban peer result to channel");
+                }
+            }
+            NetworkAction::UnbanIp { ip, output } => {
+                let was_banned = swarm.behaviour_mut().pool.unban_ip(ip);
+                if output.send(was_banned).is_err() {
+                    error!(%ip, error = "receiver hung up", "could not send unban IP result to channel");
+                }
+            }
+            NetworkAction::ListBanned { output } => {
+                if output.send(swarm.behaviour().pool.banned_ips()).is_err() {
+                    error!(
+                        error = "receiver hung up",
+                        "could not send banned IPs to channel",
+                    );
+                }
+            }
+            NetworkAction::PeerInfo { peer_id, output } => {
+                let peer_info = state
+                    .peer_infos
+                    .get(&peer_id)
+                    .map(|peer_info| Self::with_score(swarm, peer_info));
+                if output.send(peer_info).is_err() {
+                    error!(%peer_id, error = "receiver hung up", "could not send peer info to channel");
+                }
+            }
+            NetworkAction::ListPeerInfo { output } => {
+                let peer_infos = state
+                    .peer_infos
+                    .values()
+                    .map(|peer_info| Self::with_score(swarm, peer_info))
+                    .collect();
+                if output.send(peer_infos).is_err() {
+                    error!(
+                        error = "receiver hung up",
+                        "could not send peer infos to channel",
+                    );
+                }
+            }
+            NetworkAction::StorePeerContacts { output } => {
+                let stored = state.peer_contact_store.as_ref().map(|store| {
+                    Self::spawn_store_contacts(
+                        store,
+                        &swarm.behaviour().discovery.peer_contact_book(),
+                    )
+                });
+                tokio::spawn(async move {
+                    if let Some(stored) = stored {
+                        if let Err(error) = stored.await {
+                            error!(%error, "Failed to store peer contacts");
+                        }
+                    }
+                    if output.send(()).is_err() {
+                        error!(
+                            error = "receiver hung up",
+                            "could not send stored peer contacts to channel",
+                        );
+                    }
+                });
+            }
+        }
+    }
+
+    /// Returns a copy of the peer info with the current gossipsub score of the peer.
+    fn with_score(swarm: &NimiqSwarm, peer_info: &PeerInfo) -> PeerInfo {
+        PeerInfo {
+            score: swarm.behaviour().gossipsub.peer_score(&peer_info.peer_id),
+            ..peer_info.clone()
         }
     }
 
@@ -1191,6 +1436,19 @@ impl Network {
         Ok(output_rx.await?)
     }
 
+    /// Stores the peer contacts in the database, if there is one. Besides that, the contacts are
+    /// only stored periodically and when the network is dropped, so this should be called before
+    /// the process exits.
+    pub async fn store_peer_contacts(&self) -> Result<(), NetworkError> {
+        let (output_tx, output_rx) = oneshot::channel();
+
+        self.action_tx
+            .clone()
+            .send(NetworkAction::StorePeerContacts { output: output_tx })
+            .await?;
+        Ok(output_rx.await?)
+    }
+
     pub async fn listen_on(&self, listen_addresses: Vec<Multiaddr>) {
         if let Err(error) = self
             .action_tx
@@ -1202,6 +1460,91 @@ impl Network {
         }
     }
 
+    /// Bans an IP address or subnet for the given duration, or for a default duration if none is
+    /// given. Peers that are connected from within the banned range are disconnected.
+    pub async fn ban_ip(
+        &self,
+        ip: IpNetwork,
+        duration: Option<Duration>,
+    ) -> Result<(), NetworkError> {
+        self.action_tx
+            .clone()
+            .send(NetworkAction::BanIp { ip, duration })
+            .await?;
+        Ok(())
+    }
+
+    /// Bans the IP address of a connected peer and disconnects it. Returns the banned IP address,
+    /// or `None` if the IP address of the peer is unknown.
+    pub async fn ban_peer(
+        &self,
+        peer_id: PeerId,
+        duration: Option<Duration>,
+    ) -> Result<Option<IpNetwork>, NetworkError> {
+        let (output_tx, output_rx) = oneshot::channel();
+
+        self.action_tx
+            .clone()
+            .send(NetworkAction::BanPeer {
+                peer_id,
+                duration,
+                output: output_tx,
+            })
+            .await?;
+        Ok(output_rx.await?)
+    }
+
+    /// Lifts the ban of an IP address or subnet. Returns whether it was banned.
+    pub async fn unban_ip(&self, ip: IpNetwork) -> Result<bool, NetworkError> {
+        let (output_tx, output_rx) = oneshot::channel();
+
+        self.action_tx
+            .clone()
+            .send(NetworkAction::UnbanIp {
+                ip,
+                output: output_tx,
+            })
+            .await?;
+        Ok(output_rx.await?)
+    }
+
+    /// Returns the banned IP addresses and subnets, together with the time until which they are
+    /// banned.
+    pub async fn banned_ips(&self) -> Result<Vec<(IpNetwork, SystemTime)>, NetworkError> {
+        let (output_tx, output_rx) = oneshot::channel();
+
+        self.action_tx
+            .clone()
+            .send(NetworkAction::ListBanned { output: output_tx })
+            .await?;
+        Ok(output_rx.await?)
+    }
+
+    /// Returns information about a connected peer, or `None` if the peer is not connected.
+    pub async fn peer_info(&self, peer_id: PeerId) -> Result<Option<PeerInfo>, NetworkError> {
+        let (output_tx, output_rx) = oneshot::channel();
+
+        self.action_tx
+            .clone()
+            .send(NetworkAction::PeerInfo {
+                peer_id,
+                output: output_tx,
+            })
+            .await?;
+        Ok(output_rx.await?)
+    }
+
+    /// Returns information about all connected peers.
+    pub async fn peer_infos(&self) -> Result<Vec<PeerInfo>, NetworkError> {
+        let (output_tx, output_rx) = oneshot::channel();
+
+        self.action_tx
+            .clone()
+            .send(NetworkAction::ListPeerInfo { output: output_tx })
+            .await?;
+        Ok(output_rx.await?)
+    }
+
     pub async fn start_connecting(&self) {
         if let Err(error) = self
             .action_tx
@@ -1318,6 +1661,10 @@ impl Network {
         }
 
         let peer_request_limits = Arc::clone(&self.peer_request_limits);
+        let rate_limit = self.rate_limit::<Req>();
+        let peer_byte_budget = self.peer_byte_budget;
+        #[cfg(feature = "metrics")]
+        let metrics = Arc::clone(&self.metrics);
         let action_tx = self.action_tx.clone();
         let action_tx2 = self.action_tx.clone();
         ReceiveStream::WaitingForRegister(Box::pin(async move {
@@ -1337,17 +1684,40 @@ impl Network {
         .filter_map(move |(data, request_id, peer_id)| {
             let peer_request_limits = Arc::clone(&peer_request_limits);
             let action_tx2 = action_tx2.clone();
+            #[cfg(feature = "metrics")]
+            let metrics = Arc::clone(&metrics);
             async move {
-                // If the request is not respecting the rate limits for its request type, filters the request out
-                // and replies with the respective error message.
-                if !Self::is_under_the_rate_limits::<Req>(peer_request_limits, peer_id, request_id)
-                {
+                // If the request is not respecting the rate limits for its request type or the byte
+                // budget of the peer, filters the request out and replies with the respective error message.
+                let exceeded_limit = if !Self::is_under_the_rate_limits::<Req>(
+                    Arc::clone(&peer_request_limits),
+                    peer_id,
+                    request_id,
+                    rate_limit,
+                ) {
+                    Some("requests")
+                } else if !Self::is_within_byte_budget(
+                    peer_request_limits,
+                    peer_id,
+                    request_id,
+                    peer_byte_budget,
+                    data.len(),
+                ) {
+                    Some("bytes")
+                } else {
+                    None
+                };
+
+                if let Some(limit) = exceeded_limit {
                     info!(
                         %request_id,
                         %peer_id,
                         type_id = std::any::type_name::<Req>(),
+                        limit,
                         "Rate limit was exceeded!",
                     );
+                    #[cfg(feature = "metrics")]
+                    metrics.note_rate_limit_hit(request_type_name::<Req>(), limit);
                     if let Err(e) = Self::respond_with_error::<Req>(
                         action_tx2,
                         request_id,
@@ -1414,10 +1784,23 @@ impl Network {
         self.metrics.clone()
     }
 
+    /// Returns the rate limit for requests of type `Req`, which is either configured or declared by
+    /// the request type.
+    fn rate_limit<Req: RequestCommon>(&self) -> RateLimitConfig {
+        self.rate_limits
+            .get(request_type_name::<Req>())
+            .copied()
+            .unwrap_or(RateLimitConfig {
+                max_requests: Req::MAX_REQUESTS,
+                time_window: Req::TIME_WINDOW,
+            })
+    }
+
     fn is_under_the_rate_limits<Req: RequestCommon>(
         peer_request_limits: Arc<Mutex<HashMap<PeerId, HashMap<u16, RateLimit>>>>,
         peer_id: PeerId,
         request_id: RequestId,
+        rate_limit: RateLimitConfig,
     ) -> bool {
         // Gets lock of peer requests limits read and write on it.
         let mut peer_request_limits = peer_request_limits.lock();
@@ -1428,7 +1811,11 @@ impl Network {
             .or_default()
             .entry(Req::TYPE_ID)
             .or_insert_with(|| {
-                RateLimit::new(Req::MAX_REQUESTS, Req::TIME_WINDOW, TokioInstant::now())
+                RateLimit::new(
+                    rate_limit.max_requests.into(),
+                    rate_limit.time_window,
+                    TokioInstant::now(),
+                )
             });
 
         // Ensures that the request is allowed based on the set limits and updates the counter.
@@ -1439,8 +1826,50 @@ impl Network {
                 request_id,
                 peer_id,
                 std::any::type_name::<Req>(),
-                Req::MAX_REQUESTS,
-                Req::TIME_WINDOW,
+                rate_limit.max_requests,
+                rate_limit.time_window,
+            );
+            return false;
+        }
+        true
+    }
+
+    /// Charges the size of a request to the byte budget of the peer. Returns whether the peer is
+    /// still within its budget, which is always the case if no budget is configured.
+    fn is_within_byte_budget(
+        peer_request_limits: Arc<Mutex<HashMap<PeerId, HashMap<u16, RateLimit>>>>,
+        peer_id: PeerId,
+        request_id: RequestId,
+        byte_budget: Option<ByteBudgetConfig>,
+        request_size: usize,
+    ) -> bool {
+        let byte_budget = match byte_budget {
+            Some(byte_budget) => byte_budget,
+            None => return true,
+        };
+
+        let mut peer_request_limits = peer_request_limits.lock();
+
+        let budget = peer_request_limits
+            .entry(peer_id)
+            .or_default()
+            .entry(BYTE_BUDGET_KEY)
+            .or_insert_with(|| {
+                RateLimit::new(
+                    byte_budget.max_bytes,
+                    byte_budget.time_window,
+                    TokioInstant::now(),
+                )
+            });
+
+        let request_size = u64::try_from(request_size).unwrap_or(u64::MAX);
+        if !budget.increment_and_is_allowed(request_size) {
+            debug!(
+                %request_id,
+                %peer_id,
+                max_bytes = byte_budget.max_bytes,
+                time_window = ?byte_budget.time_window,
+                "Exceeded the byte budget of the peer",
             );
             return false;
         }
@@ -10,6 +10,7 @@ pub struct NetworkMetrics {
     gossipsub_messages_received: Family<TopicLabels, Counter>,
     gossipsub_messages_published: Family<TopicLabels, Counter>,
     response_times: Histogram,
+    rate_limit_hits: Family<RateLimitLabels, Counter>,
 }
 
 #[derive(Clone, Hash, PartialEq, Eq, Encode)]
@@ -17,6 +18,12 @@ struct TopicLabels {
     topic: String,
 }
 
+#[derive(Clone, Hash, PartialEq, Eq, Encode)]
+struct RateLimitLabels {
+    request_type: String,
+    limit: String,
+}
+
 #[derive(Clone, Hash, PartialEq, Eq, Encode)]
 struct RequestIdLabels {
     request_id: String,
@@ -28,6 +35,7 @@ impl Default for NetworkMetrics {
             gossipsub_messages_received: Default::default(),
             gossipsub_messages_published: Default::default(),
             response_times: Histogram::new([0.01, 0.02, 0.05, 0.1, 0.2, 0.5, 1.0, 2.0].into_iter()),
+            rate_limit_hits: Default::default(),
         }
     }
 }
@@ -51,6 +59,12 @@ impl NetworkMetrics {
             "Time between requests and responses",
             Box::new(self.response_times.clone()),
         );
+
+        registry.register(
+            "rate_limit_hits",
+            "Number of requests rejected because a peer exceeded a rate limit",
+            Box::new(self.rate_limit_hits.clone()),
+        );
     }
 
     pub(crate) fn note_received_pubsub_message(&self, topic: &TopicHash) {
@@ -77,6 +91,15 @@ impl NetworkMetrics {
             .inc();
     }
 
+    pub(crate) fn note_rate_limit_hit(&self, request_type: &str, limit: &str) {
+        self.rate_limit_hits
+            .get_or_create(&RateLimitLabels {
+                request_type: String::from(request_type),
+                limit: String::from(limit),
+            })
+            .inc();
+    }
+
     pub(crate) fn note_response_time(&self, duration: Duration) {
         self.response_times.observe(duration.as_secs_f64());
     }
@@ -4,17 +4,17 @@ use tokio::time::{Duration, Instant};
 #[derive(Debug, Eq, Hash, PartialEq)]
 pub struct RateLimit {
     /// Max allowed requests.
-    allowed_occurrences: u32,
+    allowed_occurrences: u64,
     /// The range/window of time.
     time_window: Duration,
     /// The timestamp of the last reset.
     last_reset: Instant,
     /// The counter of requests submited within the current block range.
-    occurrences_counter: u32,
+    occurrences_counter: u64,
 }
 
 impl RateLimit {
-    pub fn new(allowed_occurrences: u32, time_window: Duration, last_reset: Instant) -> Self {
+    pub fn new(allowed_occurrences: u64, time_window: Duration, last_reset: Instant) -> Self {
         RateLimit {
             allowed_occurrences,
             time_window,
@@ -26,13 +26,13 @@ impl RateLimit {
     /// Updates the last_reset if needed and then increments the counter of number of requests by
     /// the specified number.
     /// Receives the number to increment the counter and the current time measured in seconds.
-    pub fn increment_and_is_allowed(&mut self, request_count: u32) -> bool {
+    pub fn increment_and_is_allowed(&mut self, request_count: u64) -> bool {
         let current_time = Instant::now();
         if self.next_reset_time() <= current_time {
             self.last_reset = current_time;
             self.occurrences_counter = 0;
         }
-        self.occurrences_counter += request_count;
+        self.occurrences_counter = self.occurrences_counter.saturating_add(request_count);
         self.occurrences_counter <= self.allowed_occurrences
     }
 
@@ -1,4 +1,7 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use futures::{Stream, StreamExt};
 use libp2p::{
@@ -12,14 +15,16 @@ use rand::{thread_rng, Rng};
 use tokio::time::timeout;
 
 use beserial::{Deserialize, Serialize};
+use nimiq_database::volatile::VolatileEnvironment;
 use nimiq_network_interface::network::{MsgAcceptance, NetworkEvent, Topic};
 use nimiq_network_interface::{network::Network as NetworkInterface, peer::CloseReason};
 use nimiq_network_libp2p::{
     discovery::{
         behaviour::DiscoveryConfig,
+        peer_contact_store::PeerContactStore,
         peer_contacts::{PeerContact, Services},
     },
-    Config, Network,
+    Config, IpNetwork, Network, MAX_BAN_DURATION,
 };
 use nimiq_test_log::test;
 use nimiq_utils::time::OffsetTime;
@@ -58,6 +63,10 @@ fn network_config(address: Multiaddr) -> Config {
         gossipsub,
         memory_transport: true,
         required_services: Services::all(),
+        ban_list_path: None,
+        rate_limits: Default::default(),
+        peer_byte_budget: None,
+        peer_contacts_env: None,
     }
 }
 
@@ -472,3 +481,71 @@ async fn test_gossipsub() {
     }
     net1.network_info().await.unwrap();
 }
+
+#[test(tokio::test)]
+async fn ips_can_be_banned_and_unbanned() {
+    let net = TestNetwork::new().spawn().await;
+    let ip: IpNetwork = "10.0.0.0/8".parse().unwrap();
+
+    net.ban_ip(ip, Some(Duration::from_secs(60))).await.unwrap();
+    let banned = net.banned_ips().await.unwrap();
+    assert_eq!(banned.len(), 1);
+    assert_eq!(banned[0].0, ip);
+    assert!(banned[0].1 > SystemTime::now());
+
+    assert!(net.unban_ip(ip).await.unwrap());
+    assert!(!net.unban_ip(ip).await.unwrap());
+    assert_eq!(net.banned_ips().await.unwrap(), vec![]);
+}
+
+#[test(tokio::test)]
+async fn long_bans_are_capped() {
+    let net = TestNetwork::new().spawn().await;
+    let ip: IpNetwork = "2001:db8::/32".parse().unwrap();
+
+    net.ban_ip(ip, Some(Duration::from_secs(u64::MAX)))
+        .await
+        .unwrap();
+    let banned = net.banned_ips().await.unwrap();
+    assert_eq!(banned.len(), 1);
+    assert!(banned[0].1 <= SystemTime::now() + MAX_BAN_DURATION);
+    assert!(banned[0].1 > SystemTime::now() + MAX_BAN_DURATION - Duration::from_secs(60));
+}
+
+#[test(tokio::test)]
+async fn peer_contacts_are_stored_on_request() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let addr1 = multiaddr![Memory(thread_rng().gen::<u64>())];
+    let addr2 = multiaddr![Memory(thread_rng().gen::<u64>())];
+
+    let mut config = network_config(addr1.clone());
+    config.peer_contacts_env = Some(env.clone());
+    let net1 = Network::new(Arc::new(OffsetTime::new()), config).await;
+    net1.listen_on(vec![addr1.clone()]).await;
+
+    let net2 = Network::new(Arc::new(OffsetTime::new()), network_config(addr2.clone())).await;
+    net2.listen_on(vec![addr2]).await;
+
+    let mut events1 = net1.subscribe_events();
+    net2.dial_address(addr1).await.unwrap();
+    let event1 = events1.next().await.unwrap().unwrap();
+    assert_peer_joined(&event1, &net2.get_local_peer_id());
+
+    // The contact of the other peer is stored once discovery has exchanged it.
+    let store = PeerContactStore::new(env);
+    let stored = timeout(Duration::from_secs(10), async {
+        loop {
+            net1.store_peer_contacts().await.unwrap();
+            let stored = store.load();
+            if !stored.is_empty() {
+                break stored;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("Peer contacts were not stored");
+
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].inner.peer_id(), net2.get_local_peer_id());
+}
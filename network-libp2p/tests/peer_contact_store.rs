@@ -0,0 +1,38 @@
+use libp2p::{identity::Keypair, multiaddr::multiaddr};
+use rand::{thread_rng, Rng};
+
+use nimiq_database::volatile::VolatileEnvironment;
+use nimiq_network_libp2p::discovery::{
+    peer_contact_store::PeerContactStore,
+    peer_contacts::{PeerContact, PeerContactBook, Services, SignedPeerContact},
+};
+use nimiq_test_log::test;
+
+fn signed_peer_contact(keypair: &Keypair) -> SignedPeerContact {
+    let mut peer_contact = PeerContact::new(
+        vec![multiaddr![Memory(thread_rng().gen::<u64>())]],
+        keypair.public(),
+        Services::all(),
+        None,
+    );
+    peer_contact.set_current_time();
+    peer_contact.sign(keypair)
+}
+
+#[test]
+fn it_restores_stored_peer_contacts() {
+    let env = VolatileEnvironment::new(10).unwrap();
+
+    let own_keypair = Keypair::generate_ed25519();
+    let mut contacts = PeerContactBook::new(signed_peer_contact(&own_keypair));
+    contacts.update_own_contact(&own_keypair);
+
+    let other_contact = signed_peer_contact(&Keypair::generate_ed25519());
+    contacts.insert(other_contact.clone());
+
+    PeerContactStore::new(env.clone()).store(contacts.other_contacts());
+
+    // Our own contact is not stored.
+    let restored = PeerContactStore::new(env).load();
+    assert_eq!(restored, vec![other_contact]);
+}
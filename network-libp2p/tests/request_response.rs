@@ -25,7 +25,7 @@ use nimiq_network_libp2p::{
         behaviour::DiscoveryConfig,
         peer_contacts::{PeerContact, Services},
     },
-    Config, Network, PeerId,
+    ByteBudgetConfig, Config, Network, PeerId, RateLimitConfig,
 };
 use nimiq_test_log::test;
 use nimiq_utils::time::OffsetTime;
@@ -148,11 +148,19 @@ struct TestNetwork {}
 
 impl TestNetwork {
     async fn create_connected_networks() -> (Network, Network) {
-        log::debug!("Creating connected test networks");
         let addr1 = multiaddr![Memory(thread_rng().gen::<u64>())];
+        Self::create_connected_networks_with_config(addr1.clone(), network_config(addr1)).await
+    }
+
+    /// Creates two connected networks, the first of which listens on `addr1` and uses `config1`.
+    async fn create_connected_networks_with_config(
+        addr1: Multiaddr,
+        config1: Config,
+    ) -> (Network, Network) {
+        log::debug!("Creating connected test networks");
         let addr2 = multiaddr![Memory(thread_rng().gen::<u64>())];
 
-        let net1 = Network::new(Arc::new(OffsetTime::new()), network_config(addr1.clone())).await;
+        let net1 = Network::new(Arc::new(OffsetTime::new()), config1).await;
         net1.listen_on(vec![addr1.clone()]).await;
 
         let net2 = Network::new(Arc::new(OffsetTime::new()), network_config(addr2.clone())).await;
@@ -279,6 +287,10 @@ fn network_config(address: Multiaddr) -> Config {
         gossipsub,
         memory_transport: true,
         required_services: Services::all(),
+        ban_list_path: None,
+        rate_limits: Default::default(),
+        peer_byte_budget: None,
+        peer_contacts_env: None,
     }
 }
 
@@ -705,6 +717,112 @@ async fn it_can_limit_requests_rate() {
     send_n_request_to_succeed(&net1, &net2, TestRequest4::MAX_REQUESTS).await;
 }
 
+#[test(tokio::test)]
+async fn it_can_override_requests_rate_limits() {
+    let max_requests = TestRequest4::MAX_REQUESTS + 2;
+
+    let addr1 = multiaddr![Memory(thread_rng().gen::<u64>())];
+    let mut config1 = network_config(addr1.clone());
+    config1.rate_limits.insert(
+        "TestRequest4".to_string(),
+        RateLimitConfig {
+            max_requests,
+            time_window: TestRequest4::TIME_WINDOW,
+        },
+    );
+
+    let (net1, net2) = TestNetwork::create_connected_networks_with_config(addr1, config1).await;
+    let net1 = Arc::new(net1);
+    let net2 = Arc::new(net2);
+
+    let test_response = TestResponse4 { response: 43 };
+
+    // Subscribe for receiving requests.
+    let request_stream = net1.receive_requests::<TestRequest4>();
+    let network1 = Arc::clone(&net1);
+    let request_listener_future =
+        request_stream.for_each(move |(_request, request_id, _peer_id)| {
+            let test_response = test_response.clone();
+            let network1 = Arc::clone(&network1);
+            async move {
+                let _result = network1
+                    .respond::<TestRequest4>(request_id, test_response.clone())
+                    .await;
+            }
+        });
+
+    // Spawn the request listener future.
+    tokio::spawn(request_listener_future);
+
+    time::sleep(Duration::from_secs(1)).await;
+
+    time::pause();
+
+    // The configured limit applies instead of the one declared by the request type.
+    send_n_request_to_succeed(&net1, &net2, max_requests).await;
+    send_n_request_to_fail(&net1, &net2, 5).await;
+
+    // Counters should be reset after the time window.
+    time::advance(TestRequest4::TIME_WINDOW).await;
+    send_n_request_to_succeed(&net1, &net2, max_requests).await;
+}
+
+#[test(tokio::test)]
+async fn it_can_limit_the_request_bytes_of_a_peer() {
+    let max_requests = 3;
+    let request_size = TestRequest4 { request: 42 }.serialized_request_size();
+
+    let addr1 = multiaddr![Memory(thread_rng().gen::<u64>())];
+    let mut config1 = network_config(addr1.clone());
+    // Only the byte budget should limit the requests.
+    config1.rate_limits.insert(
+        "TestRequest4".to_string(),
+        RateLimitConfig {
+            max_requests: 100,
+            time_window: TestRequest4::TIME_WINDOW,
+        },
+    );
+    config1.peer_byte_budget = Some(ByteBudgetConfig {
+        max_bytes: max_requests as u64 * request_size as u64,
+        time_window: TestRequest4::TIME_WINDOW,
+    });
+
+    let (net1, net2) = TestNetwork::create_connected_networks_with_config(addr1, config1).await;
+    let net1 = Arc::new(net1);
+    let net2 = Arc::new(net2);
+
+    let test_response = TestResponse4 { response: 43 };
+
+    // Subscribe for receiving requests.
+    let request_stream = net1.receive_requests::<TestRequest4>();
+    let network1 = Arc::clone(&net1);
+    let request_listener_future =
+        request_stream.for_each(move |(_request, request_id, _peer_id)| {
+            let test_response = test_response.clone();
+            let network1 = Arc::clone(&network1);
+            async move {
+                let _result = network1
+                    .respond::<TestRequest4>(request_id, test_response.clone())
+                    .await;
+            }
+        });
+
+    // Spawn the request listener future.
+    tokio::spawn(request_listener_future);
+
+    time::sleep(Duration::from_secs(1)).await;
+
+    time::pause();
+
+    // The requests are refused once their bytes exceed the budget.
+    send_n_request_to_succeed(&net1, &net2, max_requests).await;
+    send_n_request_to_fail(&net1, &net2, 2).await;
+
+    // The budget should be reset after the time window.
+    time::advance(TestRequest4::TIME_WINDOW).await;
+    send_n_request_to_succeed(&net1, &net2, max_requests).await;
+}
+
 #[test(tokio::test)]
 async fn it_can_limit_requests_rate_after_reconnection() {
     let ((net1, addr1), (net2, _), (net3, _), (net4, _)) =
@@ -32,6 +32,13 @@ impl Accounts {
         Accounts { env, tree }
     }
 
+    /// Creates a new, completely empty Accounts, which keeps up to `max_snapshots` copy-on-write
+    /// snapshots of the accounts trie. See `MerkleRadixTrie::take_snapshot`.
+    pub fn new_with_snapshots(env: Environment, max_snapshots: usize) -> Self {
+        let tree = AccountsTrie::new_with_snapshots(env.clone(), "AccountsTrie", max_snapshots);
+        Accounts { env, tree }
+    }
+
     /// Initializes the Accounts struct with a given list of accounts.
     pub fn init(&self, txn: &mut WriteTransaction, genesis_accounts: Vec<(KeyNibbles, Account)>) {
         log::debug!("Initializing Accounts");
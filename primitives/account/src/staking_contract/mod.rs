@@ -204,6 +204,29 @@ impl StakingContract {
         stakers
     }
 
+    /// Get a list containing the addresses of at most `limit` stakers that are delegating for a
+    /// given validator, ordered by address and starting at the given staker address (inclusive).
+    pub fn get_validator_stakers_from(
+        accounts_tree: &AccountsTrie,
+        db_txn: &DBTransaction,
+        validator_address: &Address,
+        start: &Address,
+        limit: usize,
+    ) -> Vec<Address> {
+        let start_key = StakingContract::get_key_validator_staker(validator_address, start);
+
+        // The stakers list of a validator is followed by the next validator or the stakers list
+        // of the staking contract, so we stop at the first account that is not a staker address.
+        accounts_tree
+            .get_chunk(db_txn, &start_key, limit)
+            .into_iter()
+            .map_while(|account| match account {
+                Account::StakingValidatorsStaker(address) => Some(address),
+                _ => None,
+            })
+            .collect()
+    }
+
     /// Get a staker information given its address, if it exists.
     pub fn get_staker(
         accounts_tree: &AccountsTrie,
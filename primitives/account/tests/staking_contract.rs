@@ -2277,6 +2277,74 @@ fn finalize_epoch_inherents_work() {
     );
 }
 
+#[test]
+fn get_validator_stakers_from_works() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let accounts_tree = AccountsTrie::new(env.clone(), "AccountsTrie");
+    let mut db_txn = WriteTransaction::new(&env);
+
+    make_sample_contract(&accounts_tree, &mut db_txn, false);
+
+    let validator_address = Address::from_any_str(VALIDATOR_ADDRESS).unwrap();
+
+    let staker_addresses: Vec<Address> = (1..=3).map(|i| Address::from([i; 20])).collect();
+
+    for staker_address in &staker_addresses {
+        StakingContract::create_staker(
+            &accounts_tree,
+            &mut db_txn,
+            staker_address,
+            Coin::from_u64_unchecked(150_000_000),
+            Some(validator_address.clone()),
+        )
+        .unwrap();
+    }
+
+    // The stakers list of the validator is followed by the stakers themselves, which must not be
+    // returned.
+    assert_eq!(
+        StakingContract::get_validator_stakers_from(
+            &accounts_tree,
+            &db_txn,
+            &validator_address,
+            &Address::from([0; 20]),
+            10
+        ),
+        staker_addresses
+    );
+
+    assert_eq!(
+        StakingContract::get_validator_stakers_from(
+            &accounts_tree,
+            &db_txn,
+            &validator_address,
+            &Address::from([0; 20]),
+            2
+        ),
+        staker_addresses[..2]
+    );
+
+    assert_eq!(
+        StakingContract::get_validator_stakers_from(
+            &accounts_tree,
+            &db_txn,
+            &validator_address,
+            &staker_addresses[1],
+            10
+        ),
+        staker_addresses[1..]
+    );
+
+    assert!(StakingContract::get_validator_stakers_from(
+        &accounts_tree,
+        &db_txn,
+        &validator_address,
+        &Address::from([4; 20]),
+        10
+    )
+    .is_empty());
+}
+
 fn make_empty_contract(accounts_tree: &AccountsTrie, db_txn: &mut WriteTransaction) {
     StakingContract::create(accounts_tree, db_txn)
 }
@@ -11,4 +11,8 @@ pub enum MerkleRadixTrieError {
     BranchesHaveNoValue,
     #[error("Tried to query a child that does not exist.")]
     ChildDoesNotExist,
+    #[error("The proof is not valid for the given root hash.")]
+    InvalidProof,
+    #[error("The proof doesn't contain all the nodes needed to prove a key.")]
+    IncompleteProof,
 }
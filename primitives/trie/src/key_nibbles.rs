@@ -189,6 +189,16 @@ impl KeyNibbles {
     pub fn suffix(&self, start: u8) -> Self {
         self.slice(start as usize, self.len())
     }
+
+    /// Returns the address that this key was created from, if the key has the length of an
+    /// address.
+    pub fn to_address(&self) -> Option<Address> {
+        if self.len() != Address::SIZE * 2 {
+            return None;
+        }
+
+        Some(Address::from(&self.bytes[..Address::SIZE]))
+    }
 }
 
 impl From<&Address> for KeyNibbles {
@@ -221,6 +231,10 @@ impl str::FromStr for KeyNibbles {
     type Err = hex::FromHexError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() > KeyNibbles::MAX_BYTES * 2 {
+            return Err(hex::FromHexError::InvalidStringLength);
+        }
+
         let mut bytes: [u8; KeyNibbles::MAX_BYTES] = [0; KeyNibbles::MAX_BYTES];
         if s.len() % 2 == 0 {
             hex::decode_to_slice(s, &mut bytes[..(s.len() / 2)])?;
@@ -231,12 +245,8 @@ impl str::FromStr for KeyNibbles {
                 length: s.len() as u8,
             })
         } else {
-            let last_complete_nibble_idx = s.len() - 2;
-            let last_complete_byte_idx = (last_complete_nibble_idx) / 2;
-            hex::decode_to_slice(
-                &s[..last_complete_nibble_idx + 1],
-                &mut bytes[..last_complete_byte_idx + 1],
-            )?;
+            let complete_bytes = s.len() / 2;
+            hex::decode_to_slice(&s[..s.len() - 1], &mut bytes[..complete_bytes])?;
 
             let last_nibble = s.chars().last().unwrap();
 
@@ -248,11 +258,11 @@ impl str::FromStr for KeyNibbles {
                         index: s.len() - 1,
                     })?;
 
-            bytes[last_complete_byte_idx + 1] = (last_nibble as u8) << 4;
+            bytes[complete_bytes] = (last_nibble as u8) << 4;
 
             Ok(KeyNibbles {
                 bytes,
-                bytes_length: (last_complete_byte_idx + 2) as u8,
+                bytes_length: (complete_bytes + 1) as u8,
                 length: s.len() as u8,
             })
         }
@@ -360,6 +370,16 @@ mod tests {
         let key: KeyNibbles = "cfb98637bcae43c13323eaa1731ced2b716962fd".parse().unwrap();
 
         assert_eq!(key.to_string(), "cfb98637bcae43c13323eaa1731ced2b716962fd");
+
+        let key: KeyNibbles = "c".parse().unwrap();
+        assert_eq!(key.to_string(), "c");
+
+        assert!("".parse::<KeyNibbles>().unwrap().is_empty());
+        assert!("0"
+            .repeat(KeyNibbles::MAX_BYTES * 2 + 1)
+            .parse::<KeyNibbles>()
+            .is_err());
+        assert!("cfx".parse::<KeyNibbles>().is_err());
     }
 
     #[test]
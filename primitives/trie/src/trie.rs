@@ -1,9 +1,13 @@
+use std::io;
 use std::marker::PhantomData;
+use std::sync::Mutex;
 
 use log::error;
 
 use beserial::{Deserialize, Serialize};
+use nimiq_database::cursor::{ReadCursor, WriteCursor};
 use nimiq_database::{Database, Environment, Transaction, WriteTransaction};
+use nimiq_database_value::{AsDatabaseBytes, FromDatabaseValue, IntoDatabaseValue};
 use nimiq_hash::{Blake2bHash, Hash};
 
 use crate::key_nibbles::KeyNibbles;
@@ -22,16 +26,59 @@ use crate::trie_proof::TrieProof;
 #[derive(Debug)]
 pub struct MerkleRadixTrie<A: Serialize + Deserialize + Clone> {
     db: Database,
+    // The snapshots of the trie. Only present if snapshots are enabled.
+    snapshots: Option<Snapshots>,
     _value: PhantomData<A>,
 }
 
+/// The databases of the copy-on-write snapshots of a Merkle Radix Trie.
+#[derive(Debug)]
+struct Snapshots {
+    // A database of the root hashes of the snapshots by their number.
+    index_db: Database,
+    // A database of the nodes as they were when a snapshot was taken, keyed by the number of the
+    // snapshot followed by the key of the node. Each snapshot only contains the nodes that were
+    // modified while it was the latest snapshot.
+    nodes_db: Database,
+    // The maximum number of snapshots that are kept.
+    max_snapshots: usize,
+    // The number of the latest snapshot that was taken or read, to avoid looking it up on every
+    // modification. It is only a hint, since the transaction that took it might have been aborted.
+    latest: Mutex<Option<u32>>,
+}
+
+impl Snapshots {
+    fn cache_latest(&self, number: u32) {
+        let mut latest = self.latest.lock().unwrap();
+        *latest = (*latest).max(Some(number));
+    }
+}
+
 impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
     /// Start a new Merkle Radix Trie with the given Environment and the given name.
     pub fn new(env: Environment, name: &str) -> Self {
+        Self::with_snapshots(env, name, None)
+    }
+
+    /// Start a new Merkle Radix Trie with the given Environment and the given name, which keeps
+    /// up to `max_snapshots` copy-on-write snapshots of its state. See `take_snapshot`.
+    pub fn new_with_snapshots(env: Environment, name: &str, max_snapshots: usize) -> Self {
+        let snapshots = Snapshots {
+            index_db: env.open_database(format!("{}SnapshotIndex", name)),
+            nodes_db: env.open_database(format!("{}Snapshots", name)),
+            max_snapshots,
+            latest: Mutex::new(None),
+        };
+
+        Self::with_snapshots(env, name, Some(snapshots))
+    }
+
+    fn with_snapshots(env: Environment, name: &str, snapshots: Option<Snapshots>) -> Self {
         let db = env.open_database(name.to_string());
 
         let tree = MerkleRadixTrie {
             db,
+            snapshots,
             _value: PhantomData,
         };
 
@@ -94,11 +141,27 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
     /// be a part of the trie, if it is then it will be part of the chunk) and contains at most
     /// `size` leaf nodes.
     pub fn get_chunk(&self, txn: &Transaction, start: &KeyNibbles, size: usize) -> Vec<A> {
-        let chunk = self.get_trie_chunk(txn, start, size);
+        let chunk = self.get_trie_chunk(txn, start, size, &[]);
 
         chunk.iter().map(|node| node.value().unwrap()).collect()
     }
 
+    /// Returns a chunk of the Merkle Radix Trie like `get_chunk`, but each value is returned
+    /// together with its key.
+    pub fn get_chunk_with_keys(
+        &self,
+        txn: &Transaction,
+        start: &KeyNibbles,
+        size: usize,
+    ) -> Vec<(KeyNibbles, A)> {
+        let chunk = self.get_trie_chunk(txn, start, size, &[]);
+
+        chunk
+            .into_iter()
+            .map(|node| (node.key().clone(), node.value().unwrap()))
+            .collect()
+    }
+
     /// Insert a value into the Merkle Radix Trie at the given key. If the key already exists then
     /// it will overwrite it. You can't use this function to check the existence of a given key.
     pub fn put(&self, txn: &mut WriteTransaction, key: &KeyNibbles, value: A) {
@@ -117,7 +180,7 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
             if !cur_node.key().is_prefix_of(key) {
                 // Create and store the new node.
                 let new_node = TrieNode::new_leaf(key.clone(), value);
-                txn.put_reserve(&self.db, key, &new_node);
+                self.put_node(txn, &new_node);
 
                 // Create and store the new parent node.
                 let new_parent = TrieNode::<A>::new_branch(cur_node.key().common_prefix(key))
@@ -125,7 +188,7 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
                     .unwrap()
                     .put_child(new_node.key(), new_node.hash())
                     .unwrap();
-                txn.put_reserve(&self.db, new_parent.key(), &new_parent);
+                self.put_node(txn, &new_parent);
 
                 // Push the parent node into the root path.
                 root_path.push(new_parent);
@@ -144,7 +207,7 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
 
                 // Update the node and store it.
                 cur_node = cur_node.put_value(value).unwrap();
-                txn.put_reserve(&self.db, key, &cur_node);
+                self.put_node(txn, &cur_node);
 
                 // Push the node into the root path.
                 root_path.push(cur_node);
@@ -158,11 +221,11 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
                 Err(_) => {
                     // Create and store the new node.
                     let new_node = TrieNode::<A>::new_leaf(key.clone(), value);
-                    txn.put_reserve(&self.db, key, &new_node);
+                    self.put_node(txn, &new_node);
 
                     // Update the parent node and store it.
                     cur_node = cur_node.put_child(new_node.key(), new_node.hash()).unwrap();
-                    txn.put_reserve(&self.db, cur_node.key(), &cur_node);
+                    self.put_node(txn, &cur_node);
 
                     // Push the parent node into the root path.
                     root_path.push(cur_node);
@@ -212,7 +275,7 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
                 );
 
                 // Remove the node from the database.
-                txn.remove(&self.db, key);
+                self.remove_node(txn, key);
 
                 break;
             }
@@ -248,7 +311,7 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
             // child.
             if num_children == 1 && parent_node.key() != &root_address {
                 // Remove the node from the database.
-                txn.remove(&self.db, parent_node.key());
+                self.remove_node(txn, parent_node.key());
 
                 // Get the node's only child and add it to the root path.
                 let only_child_key =
@@ -267,7 +330,7 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
             // parent node in the database and the root path. Then we update the keys and hashes of
             // of the root path.
             else if num_children > 0 || parent_node.key() == &root_address {
-                txn.put_reserve(&self.db, parent_node.key(), &parent_node);
+                self.put_node(txn, &parent_node);
 
                 root_path.push(parent_node);
 
@@ -298,12 +361,31 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
     ///     1. Unlike Merkle proofs we don't need the adjacent branch nodes. That's because our
     ///        branch nodes already include the hashes of its children.
     ///     2. The nodes are always returned in post-order.
-    /// If any of the given keys doesn't exist this function just returns None.
-    /// The exclusion (non-inclusion) of keys in the Merkle Radix Trie could also be proven, but it
-    /// requires some light refactoring to the way proofs work.
-    pub fn get_proof(&self, txn: &Transaction, mut keys: Vec<&KeyNibbles>) -> Option<TrieProof<A>> {
+    /// If any of the given keys doesn't exist or no keys are given, this function just returns
+    /// None. To also prove the exclusion (non-inclusion) of keys use `get_proof_with_exclusion`
+    /// instead.
+    pub fn get_proof(&self, txn: &Transaction, keys: Vec<&KeyNibbles>) -> Option<TrieProof<A>> {
+        self.get_proof_nodes(txn, keys, &[])
+    }
+
+    /// Produces a Merkle proof of the inclusion or exclusion of the given keys in the Merkle Radix
+    /// Trie. For keys that exist, the proof contains the path from the leaf node to the root, just
+    /// like in `get_proof`. For keys that don't exist, the proof contains the path from the node
+    /// that proves the exclusion to the root. That node is either:
+    ///     1. A branch node that doesn't have a child matching the key. Since branch nodes contain
+    ///        the keys of all its children, this is enough to show that the key is not in the trie.
+    ///     2. A leaf node whose key is a prefix of the key. Since leaf nodes don't have children,
+    ///        this also shows that the key is not in the trie.
+    /// The nodes are always returned in post-order. The values of the keys can be extracted from
+    /// the proof with `TrieProof::verify_values`.
+    pub fn get_proof_with_exclusion(
+        &self,
+        txn: &Transaction,
+        mut keys: Vec<&KeyNibbles>,
+    ) -> TrieProof<A> {
         // We sort the keys to simplify traversal in post-order.
         keys.sort();
+        keys.dedup();
 
         // Initialize the vector that will contain the proof.
         let mut proof_nodes = Vec::new();
@@ -317,57 +399,30 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
         // Initialize the root path.
         let mut root_path: Vec<TrieNode<A>> = vec![];
 
-        // Get the first key.
-        let mut cur_key = keys
-            .pop()
-            .expect("There must be at least one key to prove!");
+        // Get the first key. If there are no keys, the proof consists only of the root node.
+        let mut cur_key = match keys.pop() {
+            None => return TrieProof::new(vec![pointer_node]),
+            Some(key) => key,
+        };
 
         // Iterate over all the keys that we wish to prove.
         loop {
-            // Go down the trie until we find a node with our key or we can't go any further.
+            // Go down the trie until we find a node with our key or a node that proves that our
+            // key is not part of the trie.
             loop {
-                // If the key does not match, the requested key is not part of this trie. In
-                // this case, we can't produce a proof so we terminate now.
-                if !pointer_node.key().is_prefix_of(cur_key) {
-                    error!(
-                        "Pointer node with key {} is not a prefix to the current node with key {}.",
-                        pointer_node.key(),
-                        cur_key
-                    );
-                    return None;
-                }
-
-                // If the key fully matches, we have found the requested node. We must check that
-                // it is a leaf node, we don't want to prove branch nodes.
-                if pointer_node.key() == cur_key {
-                    if pointer_node.is_branch() {
-                        error!(
-                            "Pointer node with key {} is a branch node. We don't want to prove branch nodes.",
-                            pointer_node.key(),
-                        );
-                        return None;
-                    }
-
+                // If the key fully matches or we reached a leaf node, we can't go any further.
+                if pointer_node.key() == cur_key || pointer_node.is_leaf() {
                     break;
                 }
 
-                // Otherwise, try to find a child of the pointer node that matches our key.
+                // Otherwise, try to find a child of the pointer node that matches our key. If
+                // there's none, the pointer node proves the exclusion of our key.
                 match pointer_node.get_child_key(cur_key) {
-                    // If no matching child exists, then the requested key is not part of this
-                    // trie. Once again, we can't produce a proof so we terminate now.
-                    Err(_) => {
-                        error!(
-                            "Key {} is not a part of the trie. Can't produce the proof.",
-                            cur_key
-                        );
-                        return None;
-                    }
-                    // If there's a child, then we update the pointer node and the root path, and
-                    // continue down the trie.
-                    Ok(child_key) => {
+                    Ok(child_key) if child_key.is_prefix_of(cur_key) => {
                         root_path.push(pointer_node.clone());
                         pointer_node = txn.get(&self.db, &child_key).unwrap();
                     }
+                    _ => break,
                 }
             }
 
@@ -398,34 +453,276 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
         }
 
         // Return the proof.
-        Some(TrieProof::new(proof_nodes))
+        TrieProof::new(proof_nodes)
     }
 
     /// Creates a proof for the chunk of the Merkle Radix Trie that starts at the key `start` (which
     /// might or not be a part of the trie, if it is then it will be part of the chunk) and contains
-    /// at most `size` leaf nodes.
+    /// at most `size` leaf nodes. Returns None if the chunk is empty.
     pub fn get_chunk_proof(
         &self,
         txn: &Transaction,
         start: &KeyNibbles,
         size: usize,
     ) -> Option<TrieProof<A>> {
-        let chunk = self.get_trie_chunk(txn, start, size);
+        let chunk = self.get_trie_chunk(txn, start, size, &[]);
 
         let chunk_keys = chunk.iter().map(|node| node.key()).collect();
 
         self.get_proof(txn, chunk_keys)
     }
 
+    /// Takes a snapshot of the current state of the Merkle Radix Trie with the given number,
+    /// which must be higher than the numbers of the existing snapshots. From now on, the first
+    /// modification of each node stores a copy of the node as it was at this point, so the
+    /// snapshot remains readable while the trie changes. If there are more than the maximum
+    /// number of snapshots, the oldest ones are removed.
+    /// The root must be up to date when taking a snapshot. Does nothing if snapshots are disabled.
+    pub fn take_snapshot(&self, txn: &mut WriteTransaction, number: u32) {
+        if let Some(ref snapshots) = self.snapshots {
+            if let Some(latest) = self.latest_snapshot(txn) {
+                if latest >= number {
+                    error!(
+                        "Can't take snapshot {} of the Merkle Radix Trie, snapshot {} already exists.",
+                        number, latest
+                    );
+                    return;
+                }
+            }
+
+            let root_hash = self.root_hash(txn);
+            txn.put(&snapshots.index_db, &snapshot_index_key(number), &root_hash);
+            snapshots.cache_latest(number);
+
+            let numbers = self.snapshot_numbers(txn);
+            let num_removed = numbers.len().saturating_sub(snapshots.max_snapshots);
+            for number in &numbers[..num_removed] {
+                self.remove_snapshot(txn, *number);
+            }
+        }
+    }
+
+    /// Returns the numbers of the available snapshots of the Merkle Radix Trie, in ascending
+    /// order. It is empty if snapshots are disabled.
+    pub fn snapshot_numbers(&self, txn: &Transaction) -> Vec<u32> {
+        let mut numbers = vec![];
+
+        if let Some(ref snapshots) = self.snapshots {
+            let mut cursor = txn.cursor(&snapshots.index_db);
+            let mut pos: Option<(Vec<u8>, Blake2bHash)> = cursor.first();
+
+            while let Some((key, _)) = pos {
+                numbers.push(snapshot_number(&key));
+                pos = cursor.next();
+            }
+        }
+
+        numbers
+    }
+
+    /// Returns the root hash of the snapshot with the given number, if it is available.
+    pub fn snapshot_root_hash(&self, txn: &Transaction, number: u32) -> Option<Blake2bHash> {
+        let snapshots = self.snapshots.as_ref()?;
+
+        txn.get(&snapshots.index_db, &snapshot_index_key(number))
+    }
+
+    /// Returns a chunk of the snapshot with the given number. It works like `get_chunk`, but
+    /// returns None if the snapshot is not available.
+    pub fn get_snapshot_chunk(
+        &self,
+        txn: &Transaction,
+        number: u32,
+        start: &KeyNibbles,
+        size: usize,
+    ) -> Option<Vec<A>> {
+        let numbers = self.snapshots_since(txn, number)?;
+
+        let chunk = self.get_trie_chunk(txn, start, size, &numbers);
+
+        Some(chunk.iter().map(|node| node.value().unwrap()).collect())
+    }
+
+    /// Creates a proof for a chunk of the snapshot with the given number. It works like
+    /// `get_chunk_proof`, but also returns None if the snapshot is not available. The proof has to be
+    /// verified against the root hash of the snapshot.
+    pub fn get_snapshot_chunk_proof(
+        &self,
+        txn: &Transaction,
+        number: u32,
+        start: &KeyNibbles,
+        size: usize,
+    ) -> Option<TrieProof<A>> {
+        let numbers = self.snapshots_since(txn, number)?;
+
+        let chunk = self.get_trie_chunk(txn, start, size, &numbers);
+
+        let chunk_keys = chunk.iter().map(|node| node.key()).collect();
+
+        self.get_proof_nodes(txn, chunk_keys, &numbers)
+    }
+
+    /// Removes all snapshots of the Merkle Radix Trie.
+    pub fn clear_snapshots(&self, txn: &mut WriteTransaction) {
+        if let Some(ref snapshots) = self.snapshots {
+            for db in [&snapshots.index_db, &snapshots.nodes_db] {
+                let mut cursor = txn.write_cursor(db);
+                let mut pos: Option<(Vec<u8>, Vec<u8>)> = cursor.first();
+
+                while pos.is_some() {
+                    cursor.remove();
+                    pos = cursor.next();
+                }
+            }
+        }
+    }
+
     pub fn update_root(&self, txn: &mut WriteTransaction) {
         self.update_hashes(txn, &KeyNibbles::root());
     }
 
+    /// Removes all nodes from the Merkle Radix Trie and all of its snapshots, leaving an empty
+    /// trie with only the root node.
+    pub fn clear(&self, txn: &mut WriteTransaction) {
+        self.clear_snapshots(txn);
+
+        let mut cursor = txn.write_cursor(&self.db);
+        let mut pos: Option<(Vec<u8>, Vec<u8>)> = cursor.first();
+
+        while pos.is_some() {
+            cursor.remove();
+            pos = cursor.next();
+        }
+
+        let root = KeyNibbles::root();
+        txn.put_reserve(&self.db, &root, &TrieNode::<A>::new_branch(root.clone()));
+    }
+
     /// Returns the root node, if there is one.
     fn get_root(&self, txn: &Transaction) -> Option<TrieNode<A>> {
         txn.get(&self.db, &KeyNibbles::root())
     }
 
+    /// Returns the node with the given key, either from the current trie or from a snapshot. To
+    /// read a snapshot, `snapshots` contains the numbers of that snapshot and of all later ones in
+    /// ascending order, otherwise it is empty.
+    fn get_node(
+        &self,
+        txn: &Transaction,
+        key: &KeyNibbles,
+        snapshots: &[u32],
+    ) -> Option<TrieNode<A>> {
+        if let Some(ref snapshot_dbs) = self.snapshots {
+            // Nodes that were modified since the snapshot was taken have a copy in the snapshot
+            // that was the latest one when they were first modified.
+            for number in snapshots {
+                if let Some(SnapshotNode(node)) =
+                    txn.get(&snapshot_dbs.nodes_db, &snapshot_node_key(*number, key))
+                {
+                    return node;
+                }
+            }
+        }
+
+        txn.get(&self.db, key)
+    }
+
+    /// Stores the given node. If snapshots are enabled, the previous version of the node is
+    /// preserved first.
+    fn put_node(&self, txn: &mut WriteTransaction, node: &TrieNode<A>) {
+        self.preserve_node(txn, node.key());
+        txn.put_reserve(&self.db, node.key(), node);
+    }
+
+    /// Removes the node with the given key. If snapshots are enabled, the node is preserved first.
+    fn remove_node(&self, txn: &mut WriteTransaction, key: &KeyNibbles) {
+        self.preserve_node(txn, key);
+        txn.remove(&self.db, key);
+    }
+
+    /// Stores a copy of the node with the given key in the latest snapshot, as it was when the
+    /// snapshot was taken. This is only done on the first modification of the node after taking
+    /// the snapshot. Nodes that didn't exist are stored as empty copies.
+    fn preserve_node(&self, txn: &mut WriteTransaction, key: &KeyNibbles) {
+        // Without snapshots, there is nothing to preserve.
+        let snapshots = match self.snapshots {
+            Some(ref snapshots) => snapshots,
+            None => return,
+        };
+
+        let number = match self.latest_snapshot(txn) {
+            Some(number) => number,
+            None => return,
+        };
+
+        let node_key = snapshot_node_key(number, key);
+        if txn
+            .get::<_, SnapshotNode<A>>(&snapshots.nodes_db, &node_key)
+            .is_none()
+        {
+            let node = SnapshotNode(txn.get(&self.db, key));
+            txn.put_reserve(&snapshots.nodes_db, &node_key, &node);
+        }
+    }
+
+    /// Returns the number of the latest snapshot, if there is any.
+    fn latest_snapshot(&self, txn: &Transaction) -> Option<u32> {
+        let snapshots = self.snapshots.as_ref()?;
+
+        // Snapshot numbers only increase and every snapshot is taken by this trie, so the cached
+        // number is the latest snapshot if it exists in this transaction.
+        let cached = *snapshots.latest.lock().unwrap();
+        if let Some(number) = cached {
+            if txn
+                .get::<_, Blake2bHash>(&snapshots.index_db, &snapshot_index_key(number))
+                .is_some()
+            {
+                return Some(number);
+            }
+        }
+
+        let mut cursor = txn.cursor(&snapshots.index_db);
+        let (key, _): (Vec<u8>, Blake2bHash) = cursor.last()?;
+        let number = snapshot_number(&key);
+        snapshots.cache_latest(number);
+
+        Some(number)
+    }
+
+    /// Returns the numbers of the snapshot with the given number and of all later snapshots, which
+    /// are needed to read the snapshot. Returns None if the snapshot is not available.
+    fn snapshots_since(&self, txn: &Transaction, number: u32) -> Option<Vec<u32>> {
+        self.snapshot_root_hash(txn, number)?;
+
+        Some(
+            self.snapshot_numbers(txn)
+                .into_iter()
+                .filter(|later| *later >= number)
+                .collect(),
+        )
+    }
+
+    /// Removes the snapshot with the given number together with its node copies. Only the oldest
+    /// snapshot can be removed, since reading a snapshot requires the node copies of all later
+    /// snapshots.
+    fn remove_snapshot(&self, txn: &mut WriteTransaction, number: u32) {
+        if let Some(ref snapshots) = self.snapshots {
+            txn.remove(&snapshots.index_db, &snapshot_index_key(number));
+
+            let prefix = snapshot_index_key(number);
+            let mut cursor = txn.write_cursor(&snapshots.nodes_db);
+            let mut pos: Option<(Vec<u8>, Vec<u8>)> = cursor.seek_range_key(&prefix);
+
+            while let Some((key, _)) = pos {
+                if !key.starts_with(&prefix) {
+                    break;
+                }
+                cursor.remove();
+                pos = cursor.next();
+            }
+        }
+    }
+
     /// Updates the keys for a chain of nodes and marks those nodes as dirty. It assumes that the
     /// path starts at the root node and that each consecutive node is a child of the previous node.
     fn update_keys(&self, txn: &mut WriteTransaction, mut root_path: Vec<TrieNode<A>>) {
@@ -439,7 +736,7 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
                 // Mark this node as dirty by storing the default hash.
                 .put_child(child_node.key(), Blake2bHash::default())
                 .unwrap();
-            txn.put_reserve(&self.db, parent_node.key(), &parent_node);
+            self.put_node(txn, &parent_node);
 
             child_node = parent_node;
         }
@@ -460,17 +757,125 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
                 child.hash = self.update_hashes(txn, &(key + &child.suffix));
             }
         }
-        txn.put_reserve(&self.db, key, &node);
+        self.put_node(txn, &node);
         node.hash()
     }
 
-    /// Returns the nodes of the chunk of the Merkle Radix Trie that starts at the key `start` and
-    /// has size `size`. This is used by the `get_chunk` and `get_chunk_proof` functions.
+    /// Produces a Merkle proof of the inclusion of the given keys, either in the current trie or
+    /// in a snapshot (see `get_node`). This is used by the `get_proof` and
+    /// `get_snapshot_chunk_proof` functions.
+    fn get_proof_nodes(
+        &self,
+        txn: &Transaction,
+        mut keys: Vec<&KeyNibbles>,
+        snapshots: &[u32],
+    ) -> Option<TrieProof<A>> {
+        // We sort the keys to simplify traversal in post-order.
+        keys.sort();
+
+        // Initialize the vector that will contain the proof.
+        let mut proof_nodes = Vec::new();
+
+        // Initialize the pointer node, we will use it to go up and down the tree. We always start
+        // at the root.
+        let mut pointer_node = self
+            .get_node(txn, &KeyNibbles::root(), snapshots)
+            .expect("Merkle Radix Trie must have a root node!");
+
+        // Initialize the root path.
+        let mut root_path: Vec<TrieNode<A>> = vec![];
+
+        // Get the first key. Without any keys, there is nothing to prove.
+        let mut cur_key = keys.pop()?;
+
+        // Iterate over all the keys that we wish to prove.
+        loop {
+            // Go down the trie until we find a node with our key or we can't go any further.
+            loop {
+                // If the key does not match, the requested key is not part of this trie. In
+                // this case, we can't produce a proof so we terminate now.
+                if !pointer_node.key().is_prefix_of(cur_key) {
+                    error!(
+                        "Pointer node with key {} is not a prefix to the current node with key {}.",
+                        pointer_node.key(),
+                        cur_key
+                    );
+                    return None;
+                }
+
+                // If the key fully matches, we have found the requested node. We must check that
+                // it is a leaf node, we don't want to prove branch nodes.
+                if pointer_node.key() == cur_key {
+                    if pointer_node.is_branch() {
+                        error!(
+                            "Pointer node with key {} is a branch node. We don't want to prove branch nodes.",
+                            pointer_node.key(),
+                        );
+                        return None;
+                    }
+
+                    break;
+                }
+
+                // Otherwise, try to find a child of the pointer node that matches our key.
+                match pointer_node.get_child_key(cur_key) {
+                    // If no matching child exists, then the requested key is not part of this
+                    // trie. Once again, we can't produce a proof so we terminate now.
+                    Err(_) => {
+                        error!(
+                            "Key {} is not a part of the trie. Can't produce the proof.",
+                            cur_key
+                        );
+                        return None;
+                    }
+                    // If there's a child, then we update the pointer node and the root path, and
+                    // continue down the trie.
+                    Ok(child_key) => {
+                        root_path.push(pointer_node.clone());
+                        pointer_node = self.get_node(txn, &child_key, snapshots).unwrap();
+                    }
+                }
+            }
+
+            // Get the next key. If there's no next key then we get out of the loop.
+            match keys.pop() {
+                None => {
+                    // Add the remaining nodes in the root path to the proof. Evidently they must
+                    // be added in the reverse order.
+                    proof_nodes.push(pointer_node);
+                    root_path.reverse();
+                    proof_nodes.append(&mut root_path);
+
+                    // Exit the loop.
+                    break;
+                }
+                Some(key) => cur_key = key,
+            }
+
+            // Go up the root path until we get to a node that is a prefix to our current key.
+            // Add the nodes you remove to the proof.
+            while !pointer_node.key().is_prefix_of(cur_key) {
+                proof_nodes.push(pointer_node.clone());
+
+                pointer_node = root_path
+                    .pop()
+                    .expect("Root path must contain at least the root node!");
+            }
+        }
+
+        // Return the proof.
+        Some(TrieProof::new(proof_nodes))
+    }
+
+    /// Returns the nodes of the chunk of the Merkle Radix Trie (or a snapshot, see `get_node`) that
+    /// starts at the key `start` and has size `size`. This is used by the `get_chunk` and
+    /// `get_chunk_proof` functions and their snapshot counterparts.
     fn get_trie_chunk(
         &self,
         txn: &Transaction,
         start: &KeyNibbles,
         size: usize,
+        snapshots: &[u32],
     ) -> Vec<TrieNode<A>> {
         let mut chunk = Vec::new();
 
@@ -479,7 +884,7 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
         }
 
         let mut stack = vec![self
-            .get_root(txn)
+            .get_node(txn, &KeyNibbles::root(), snapshots)
             .expect("The Merkle Radix Trie didn't have a root node!")];
 
         while let Some(item) = stack.pop() {
@@ -489,7 +894,7 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
                         let combined = &key + &child.suffix;
 
                         if combined.is_prefix_of(start) || *start <= combined {
-                            stack.push(txn.get(&self.db, &combined)
+                            stack.push(self.get_node(txn, &combined, snapshots)
                                 .expect("Failed to find the child of a Merkle Radix Trie node. The database must be corrupt!"));
                         }
                     }
@@ -509,9 +914,52 @@ impl<A: Serialize + Deserialize + Clone> MerkleRadixTrie<A> {
     }
 }
 
+/// Returns the key of a snapshot in the snapshot index. Its number is stored in big-endian, so
+/// that the snapshots are ordered by their number.
+fn snapshot_index_key(number: u32) -> Vec<u8> {
+    number.to_be_bytes().to_vec()
+}
+
+/// Returns the number of a snapshot from its key in the snapshot index.
+fn snapshot_number(key: &[u8]) -> u32 {
+    u32::from_be_bytes(key[..4].try_into().unwrap())
+}
+
+/// Returns the key of the copy of a node in a snapshot.
+fn snapshot_node_key(number: u32, key: &KeyNibbles) -> Vec<u8> {
+    let mut node_key = snapshot_index_key(number);
+    node_key.extend_from_slice(&key.as_database_bytes());
+    node_key
+}
+
+/// A copy of a node in the snapshot of a Merkle Radix Trie. It is empty if the node didn't exist
+/// when the snapshot was taken.
+struct SnapshotNode<A: Serialize + Deserialize + Clone>(Option<TrieNode<A>>);
+
+impl<A: Serialize + Deserialize + Clone> IntoDatabaseValue for SnapshotNode<A> {
+    fn database_byte_size(&self) -> usize {
+        self.0.serialized_size()
+    }
+
+    fn copy_into_database(&self, mut bytes: &mut [u8]) {
+        Serialize::serialize(&self.0, &mut bytes).unwrap();
+    }
+}
+
+impl<A: Serialize + Deserialize + Clone> FromDatabaseValue for SnapshotNode<A> {
+    fn copy_from_database(bytes: &[u8]) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut cursor = io::Cursor::new(bytes);
+        Ok(SnapshotNode(Deserialize::deserialize(&mut cursor)?))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::MerkleRadixTrieError;
     use nimiq_test_log::test;
 
     #[test]
@@ -609,6 +1057,62 @@ mod tests {
         assert!(proof.is_none());
     }
 
+    #[test]
+    fn get_proof_with_exclusion_works() {
+        let key_1 = "cfb986f5a".parse().unwrap();
+        let key_2 = "cfb986ab9".parse().unwrap();
+        let key_3 = "cfb98e0f6".parse().unwrap();
+        // Missing key below an existing branch node.
+        let key_4 = "cfb98e0f5".parse().unwrap();
+        // Missing key that diverges from the path of a child.
+        let key_5 = "cfb981234".parse().unwrap();
+        // Missing key that diverges at the root.
+        let key_6 = "0123".parse().unwrap();
+
+        let env = nimiq_database::volatile::VolatileEnvironment::new(10).unwrap();
+        let trie = MerkleRadixTrie::new(env.clone(), "database");
+        let mut txn = WriteTransaction::new(&env);
+
+        trie.put(&mut txn, &key_1, 9);
+        trie.put(&mut txn, &key_2, 8);
+        trie.put(&mut txn, &key_3, 7);
+        trie.update_root(&mut txn);
+
+        let root_hash = trie.root_hash(&txn);
+
+        // Only included keys produce the same proof as `get_proof`.
+        let proof = trie.get_proof_with_exclusion(&txn, vec![&key_1, &key_3]);
+        assert_eq!(proof.nodes.len(), 5);
+        assert_eq!(
+            proof.verify_values(&[&key_1, &key_3], &root_hash),
+            Ok(vec![Some(9), Some(7)])
+        );
+
+        let proof = trie.get_proof_with_exclusion(&txn, vec![&key_4]);
+        assert_eq!(proof.verify(&root_hash), true);
+        assert_eq!(proof.verify_values(&[&key_4], &root_hash), Ok(vec![None]));
+
+        let proof = trie.get_proof_with_exclusion(&txn, vec![&key_5, &key_2, &key_6, &key_4]);
+        assert_eq!(proof.verify(&root_hash), true);
+        assert_eq!(
+            proof.verify_values(&[&key_2, &key_4, &key_5, &key_6], &root_hash),
+            Ok(vec![Some(8), None, None, None])
+        );
+
+        // The proof doesn't cover keys that weren't requested.
+        let proof = trie.get_proof_with_exclusion(&txn, vec![&key_6]);
+        assert_eq!(
+            proof.verify_values(&[&key_1], &root_hash),
+            Err(MerkleRadixTrieError::IncompleteProof)
+        );
+
+        // The proof is not valid for a different root hash.
+        assert_eq!(
+            proof.verify_values(&[&key_6], &Blake2bHash::default()),
+            Err(MerkleRadixTrieError::InvalidProof)
+        );
+    }
+
     #[test]
     fn get_chunk_works() {
         let key_1 = "cfb986f5a".parse().unwrap();
@@ -646,5 +1150,223 @@ mod tests {
         let chunk = trie.get_chunk_proof(&txn, &key_4, 100).unwrap();
         assert_eq!(chunk.nodes.len(), 3);
         assert_eq!(chunk.verify(&trie.root_hash(&txn)), true);
+
+        let chunk = trie.get_chunk_with_keys(&txn, &KeyNibbles::root(), 2);
+        assert_eq!(chunk, vec![(key_2, 8), (key_1.clone(), 9)]);
+
+        let chunk = trie.get_chunk_with_keys(&txn, &key_1, 100);
+        assert_eq!(chunk, vec![(key_1, 9), (key_3, 7)]);
+    }
+
+    #[test]
+    fn verify_chunk_works() {
+        let key_1 = "cfb986f5a".parse().unwrap();
+        let key_2 = "cfb986ab9".parse().unwrap();
+        let key_3 = "cfb98e0f6".parse().unwrap();
+
+        let env = nimiq_database::volatile::VolatileEnvironment::new(10).unwrap();
+        let trie = MerkleRadixTrie::new(env.clone(), "database");
+        let mut txn = WriteTransaction::new(&env);
+
+        trie.put(&mut txn, &key_1, 9);
+        trie.put(&mut txn, &key_2, 8);
+        trie.put(&mut txn, &key_3, 7);
+        trie.update_root(&mut txn);
+
+        let root_hash = trie.root_hash(&txn);
+        let root = KeyNibbles::root();
+
+        let chunk = trie.get_chunk_proof(&txn, &root, 100).unwrap();
+        assert_eq!(chunk.verify_chunk(&root_hash, &root, true), true);
+        assert_eq!(
+            chunk.verify_chunk(&Blake2bHash::default(), &root, true),
+            false
+        );
+
+        // The first two leaves are a valid chunk, but not the last one.
+        let chunk = trie.get_chunk_proof(&txn, &root, 2).unwrap();
+        assert_eq!(chunk.verify_chunk(&root_hash, &root, false), true);
+        assert_eq!(chunk.verify_chunk(&root_hash, &root, true), false);
+
+        let chunk = trie.get_chunk_proof(&txn, &key_1, 100).unwrap();
+        assert_eq!(chunk.verify_chunk(&root_hash, &key_1, true), true);
+        assert_eq!(chunk.verify_chunk(&root_hash, &root, true), false);
+
+        // Valid proofs that leave out leaves are not valid chunks.
+        let proof = trie.get_proof(&txn, vec![&key_2, &key_3]).unwrap();
+        assert_eq!(proof.verify(&root_hash), true);
+        assert_eq!(proof.verify_chunk(&root_hash, &root, false), false);
+
+        let proof = trie.get_proof(&txn, vec![&key_1]).unwrap();
+        assert_eq!(proof.verify_chunk(&root_hash, &root, false), false);
+        assert_eq!(proof.verify_chunk(&root_hash, &key_1, false), true);
+    }
+
+    #[test]
+    fn snapshot_works() {
+        let key_1 = "cfb986f5a".parse().unwrap();
+        let key_2 = "cfb986ab9".parse().unwrap();
+        let key_3 = "cfb98e0f6".parse().unwrap();
+        let key_4 = "cfb98e0f5".parse().unwrap();
+
+        let env = nimiq_database::volatile::VolatileEnvironment::new(10).unwrap();
+        let trie = MerkleRadixTrie::new_with_snapshots(env.clone(), "database", 2);
+        let plain_trie = MerkleRadixTrie::<i32>::new(env.clone(), "plain_database");
+        let mut txn = WriteTransaction::new(&env);
+
+        trie.put(&mut txn, &key_1, 9);
+        trie.put(&mut txn, &key_2, 8);
+        trie.put(&mut txn, &key_3, 7);
+        trie.update_root(&mut txn);
+        trie.take_snapshot(&mut txn, 1);
+
+        let snapshot_root_1 = trie.root_hash(&txn);
+        assert_eq!(
+            trie.snapshot_root_hash(&txn, 1),
+            Some(snapshot_root_1.clone())
+        );
+
+        // Modify the trie after taking the snapshot.
+        trie.put(&mut txn, &key_1, 6);
+        trie.put(&mut txn, &key_4, 5);
+        trie.remove(&mut txn, &key_2);
+        trie.update_root(&mut txn);
+
+        assert_ne!(trie.root_hash(&txn), snapshot_root_1);
+        assert_eq!(
+            trie.snapshot_root_hash(&txn, 1),
+            Some(snapshot_root_1.clone())
+        );
+        assert_eq!(
+            trie.get_chunk(&txn, &KeyNibbles::root(), 100),
+            vec![6, 5, 7]
+        );
+
+        // The snapshot still contains the old state.
+        let chunk = trie
+            .get_snapshot_chunk(&txn, 1, &KeyNibbles::root(), 100)
+            .unwrap();
+        assert_eq!(chunk, vec![8, 9, 7]);
+
+        let proof = trie
+            .get_snapshot_chunk_proof(&txn, 1, &KeyNibbles::root(), 100)
+            .unwrap();
+        assert_eq!(proof.verify(&snapshot_root_1), true);
+        assert_eq!(proof.verify(&trie.root_hash(&txn)), false);
+
+        // Taking a new snapshot keeps the previous one.
+        trie.take_snapshot(&mut txn, 2);
+        let snapshot_root_2 = trie.root_hash(&txn);
+        assert_eq!(trie.snapshot_numbers(&txn), vec![1, 2]);
+
+        trie.put(&mut txn, &key_1, 4);
+        trie.remove(&mut txn, &key_3);
+        trie.update_root(&mut txn);
+
+        assert_eq!(
+            trie.get_snapshot_chunk(&txn, 1, &KeyNibbles::root(), 100),
+            Some(vec![8, 9, 7])
+        );
+        assert_eq!(
+            trie.get_snapshot_chunk(&txn, 2, &KeyNibbles::root(), 100),
+            Some(vec![6, 5, 7])
+        );
+        let proof = trie
+            .get_snapshot_chunk_proof(&txn, 2, &KeyNibbles::root(), 100)
+            .unwrap();
+        assert_eq!(proof.verify(&snapshot_root_2), true);
+        assert_eq!(trie.get_chunk(&txn, &KeyNibbles::root(), 100), vec![4, 5]);
+
+        // Only the latest snapshots are kept.
+        trie.take_snapshot(&mut txn, 3);
+        assert_eq!(trie.snapshot_numbers(&txn), vec![2, 3]);
+        assert_eq!(trie.snapshot_root_hash(&txn, 1), None);
+        assert_eq!(
+            trie.get_snapshot_chunk(&txn, 1, &KeyNibbles::root(), 100),
+            None
+        );
+        assert_eq!(
+            trie.get_snapshot_chunk(&txn, 2, &KeyNibbles::root(), 100),
+            Some(vec![6, 5, 7])
+        );
+        assert_eq!(
+            trie.get_snapshot_chunk(&txn, 3, &KeyNibbles::root(), 100),
+            Some(vec![4, 5])
+        );
+
+        // Snapshots can't be taken out of order.
+        trie.take_snapshot(&mut txn, 3);
+        trie.take_snapshot(&mut txn, 1);
+        assert_eq!(trie.snapshot_numbers(&txn), vec![2, 3]);
+
+        // Snapshots are not available if they are disabled.
+        assert_eq!(plain_trie.snapshot_root_hash(&txn, 1), None);
+        assert_eq!(
+            plain_trie.get_snapshot_chunk(&txn, 1, &KeyNibbles::root(), 100),
+            None
+        );
+    }
+
+    #[test]
+    fn aborted_snapshots_are_ignored() {
+        let key_1 = "cfb986f5a".parse().unwrap();
+        let key_2 = "cfb986ab9".parse().unwrap();
+
+        let env = nimiq_database::volatile::VolatileEnvironment::new(10).unwrap();
+        let trie = MerkleRadixTrie::new_with_snapshots(env.clone(), "database", 2);
+
+        let mut txn = WriteTransaction::new(&env);
+        trie.put(&mut txn, &key_1, 9);
+        trie.put(&mut txn, &key_2, 8);
+        trie.update_root(&mut txn);
+        trie.take_snapshot(&mut txn, 1);
+        txn.commit();
+
+        let mut txn = WriteTransaction::new(&env);
+        trie.take_snapshot(&mut txn, 2);
+        txn.abort();
+
+        // The modifications are preserved in the latest snapshot that actually exists.
+        let mut txn = WriteTransaction::new(&env);
+        trie.put(&mut txn, &key_1, 6);
+        trie.update_root(&mut txn);
+
+        assert_eq!(trie.snapshot_numbers(&txn), vec![1]);
+        assert_eq!(
+            trie.get_snapshot_chunk(&txn, 1, &KeyNibbles::root(), 100),
+            Some(vec![8, 9])
+        );
+    }
+
+    #[test]
+    fn clear_works() {
+        let key_1 = "cfb986f5a".parse().unwrap();
+        let key_2 = "cfb986ab9".parse().unwrap();
+        let key_3 = "cfb98e0f6".parse().unwrap();
+
+        let env = nimiq_database::volatile::VolatileEnvironment::new(10).unwrap();
+        let trie = MerkleRadixTrie::new_with_snapshots(env.clone(), "database", 2);
+        let mut txn = WriteTransaction::new(&env);
+
+        let empty_root = trie.root_hash(&txn);
+
+        trie.put(&mut txn, &key_1, 9);
+        trie.put(&mut txn, &key_2, 8);
+        trie.update_root(&mut txn);
+        trie.take_snapshot(&mut txn, 1);
+        trie.put(&mut txn, &key_3, 7);
+        trie.update_root(&mut txn);
+
+        trie.clear(&mut txn);
+
+        assert_eq!(trie.size(&txn), 0);
+        assert_eq!(trie.get(&txn, &key_1), None);
+        assert_eq!(trie.root_hash(&txn), empty_root);
+        assert!(trie.snapshot_numbers(&txn).is_empty());
+
+        // The trie is usable again after clearing it.
+        trie.put(&mut txn, &key_3, 7);
+        trie.update_root(&mut txn);
+        assert_eq!(trie.get_chunk(&txn, &KeyNibbles::root(), 100), vec![7]);
     }
 }
@@ -1,8 +1,11 @@
+use std::collections::BTreeMap;
+
 use log::error;
 
 use beserial::{Deserialize, Serialize};
 use nimiq_hash::{Blake2bHash, Hash};
 
+use crate::error::MerkleRadixTrieError;
 use crate::key_nibbles::KeyNibbles;
 use crate::trie_node::TrieNode;
 
@@ -21,9 +24,9 @@ use crate::trie_node::TrieNode;
 ///     1. Unlike Merkle proofs we don't need the adjacent branch nodes. That's because our
 ///        branch nodes already include the hashes of its children.
 ///     2. The nodes are always returned in post-order.
-/// If any of the given keys doesn't exist this function just returns None.
-/// The exclusion (non-inclusion) of keys in the Merkle Radix Trie could also be proven, but it
-/// requires some light refactoring to the way proofs work.
+/// A proof can also show the exclusion (non-inclusion) of keys. In that case, instead of the leaf
+/// node, the proof contains the node that shows that the key can't be part of the trie (see
+/// `MerkleRadixTrie::get_proof_with_exclusion`).
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TrieProof<A: Serialize + Deserialize + Clone> {
     #[beserial(len_type(u16))]
@@ -137,6 +140,116 @@ impl<A: Serialize + Deserialize + Clone> TrieProof<A> {
         // The proof is valid!
         true
     }
+
+    /// Verifies a proof for a chunk of the trie, as created by `MerkleRadixTrie::get_chunk_proof`,
+    /// against the given root hash. On top of `verify`, it checks that the leaves of the proof are
+    /// sorted, that none of them is before `start` and that no leaf between `start` and the last
+    /// leaf of the proof was left out. If `is_last_chunk` is set, it also checks that there are no
+    /// leaves after the last one.
+    /// `start` must either be the root key or the key of a leaf of the trie.
+    pub fn verify_chunk(
+        &self,
+        root_hash: &Blake2bHash,
+        start: &KeyNibbles,
+        is_last_chunk: bool,
+    ) -> bool {
+        if !self.verify(root_hash) {
+            return false;
+        }
+
+        let leaves = self.leaf_nodes();
+        if !leaves.windows(2).all(|pair| pair[0].key() < pair[1].key()) {
+            error!("The leaves of the chunk are not sorted.");
+            return false;
+        }
+        if let Some(first) = leaves.first() {
+            if first.key() < start {
+                error!("The chunk starts at {}, before {}.", first.key(), start);
+                return false;
+            }
+        }
+        let last = leaves.last().map(|leaf| leaf.key());
+
+        // Since the proof contains the path from each leaf to the root, every child of a branch
+        // node in the proof that can contain leaves of the chunk must be part of the proof too.
+        // Otherwise, the leaves of that child were left out.
+        for node in self.nodes.iter().filter(|node| node.is_branch()) {
+            for child in node.iter_children() {
+                let child_key = node.key() + &child.suffix;
+
+                let is_included = self.nodes.iter().any(|node| node.key() == &child_key);
+                let is_before_start = child_key < *start && !child_key.is_prefix_of(start);
+                let is_after_last = match last {
+                    Some(last) => !is_last_chunk && child_key > *last,
+                    None => false,
+                };
+
+                if !is_included && !is_before_start && !is_after_last {
+                    error!(
+                        "The chunk doesn't contain the leaves of the node {}.",
+                        child_key
+                    );
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Verifies a proof against the given root hash and returns the value for each of the given
+    /// keys, in the same order as the keys. A key whose exclusion is proven gets `None`.
+    /// It returns an error if the proof is invalid or if it doesn't contain enough nodes to prove
+    /// the inclusion or exclusion of any of the keys.
+    pub fn verify_values(
+        &self,
+        keys: &[&KeyNibbles],
+        root_hash: &Blake2bHash,
+    ) -> Result<Vec<Option<A>>, MerkleRadixTrieError> {
+        if !self.verify(root_hash) {
+            return Err(MerkleRadixTrieError::InvalidProof);
+        }
+
+        // After verifying the proof, all of its nodes are known to be part of the trie. Index them
+        // by key so that we can walk down the trie.
+        let nodes: BTreeMap<&KeyNibbles, &TrieNode<A>> =
+            self.nodes.iter().map(|node| (node.key(), node)).collect();
+
+        keys.iter()
+            .map(|key| {
+                // Start at the root node, it is always the last node of a valid proof.
+                let mut pointer_node = self.nodes.last().unwrap();
+
+                loop {
+                    // A leaf node either is the node that we want or proves that our key is not
+                    // part of the trie.
+                    if pointer_node.is_leaf() {
+                        return Ok(
+                            (pointer_node.key() == *key).then(|| pointer_node.value().unwrap())
+                        );
+                    }
+
+                    // A branch node can't have a value.
+                    if pointer_node.key() == *key {
+                        return Ok(None);
+                    }
+
+                    // If there's no child that matches our key, the branch node proves that our key
+                    // is not part of the trie.
+                    let child_key = match pointer_node.get_child_key(key) {
+                        Ok(child_key) if child_key.is_prefix_of(key) => child_key,
+                        _ => return Ok(None),
+                    };
+
+                    // Otherwise, the child must be included in the proof.
+                    pointer_node = nodes.get(&child_key).copied().ok_or_else(|| {
+                        error!("The proof doesn't contain the node with key {}.", child_key);
+                        MerkleRadixTrieError::IncompleteProof
+                    })?;
+                }
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
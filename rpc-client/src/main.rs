@@ -62,6 +62,10 @@ enum Command {
     /// Shows the zkp information.
     #[clap(flatten)]
     Zkp(ZKPComponentCommand),
+
+    /// Creates multisig addresses and signs transactions from multisig accounts.
+    #[clap(flatten)]
+    Multisig(MultisigCommand),
 }
 
 impl Command {
@@ -75,6 +79,7 @@ impl Command {
             Command::Mempool(command) => command.handle_subcommand(client).await,
             Command::Validator(command) => command.handle_subcommand(client).await,
             Command::Zkp(command) => command.handle_subcommand(client).await,
+            Command::Multisig(command) => command.handle_subcommand(client).await,
         }
     }
 }
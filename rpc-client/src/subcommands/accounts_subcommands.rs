@@ -1,4 +1,7 @@
-use anyhow::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{bail, Error};
 use async_trait::async_trait;
 
 use clap::Parser;
@@ -94,10 +97,94 @@ pub enum AccountCommand {
         is_hex: bool,
     },
 
+    /// Creates a new HD wallet and prints its mnemonic. The mnemonic is the only backup of the
+    /// wallet and all of its accounts.
+    NewHdWallet {
+        /// Encryption password.
+        #[clap(short = 'P', long)]
+        password: Option<String>,
+    },
+
+    /// Imports an HD wallet from its mnemonic. No accounts are derived by this operation.
+    ImportHdWallet {
+        #[clap(short = 'P', long)]
+        password: Option<String>,
+
+        /// The words of the mnemonic.
+        #[clap(required = true)]
+        mnemonic: Vec<String>,
+    },
+
+    /// Lists the ids of all HD wallets.
+    ListHdWallets {},
+
+    /// Lists the accounts that were derived from an HD wallet.
+    ListHdAccounts {
+        /// The id of the HD wallet.
+        wallet_id: Address,
+    },
+
+    /// Derives an account from an HD wallet and imports it. The account is encrypted with the
+    /// password of the HD wallet and remains locked after this operation.
+    DeriveHdAccount {
+        #[clap(short = 'P', long)]
+        password: Option<String>,
+
+        /// The id of the HD wallet.
+        wallet_id: Address,
+
+        /// The index of the account, which is derived at `m/44'/242'/0'/<index>'`.
+        #[clap(long, conflicts_with = "path", required_unless_present = "path")]
+        index: Option<u32>,
+
+        /// The derivation path of the account.
+        #[clap(long)]
+        path: Option<String>,
+    },
+
+    /// Exports accounts to an encrypted keystore file. If no addresses are given, all accounts are
+    /// exported.
+    Export {
+        /// The password of the accounts.
+        #[clap(short = 'P', long)]
+        password: Option<String>,
+
+        /// The password to encrypt the keystore with.
+        #[clap(short = 'K', long)]
+        keystore_password: String,
+
+        /// The file to write the keystore to.
+        #[clap(short, long)]
+        output: PathBuf,
+
+        /// The addresses of the accounts to export.
+        addresses: Vec<Address>,
+    },
+
+    /// Imports all accounts of a keystore file. The accounts remain locked after this operation.
+    /// Accounts that are already imported are skipped.
+    ImportKeystore {
+        /// The password to encrypt the imported accounts with.
+        #[clap(short = 'P', long)]
+        password: Option<String>,
+
+        /// The password the keystore is encrypted with.
+        #[clap(short = 'K', long)]
+        keystore_password: String,
+
+        /// The keystore file.
+        file: PathBuf,
+    },
+
     /// Queries the account state (e.g. account balance for basic accounts).
     Get {
         /// The account's address.
         address: Address,
+
+        /// Query the account state as it was right after this block. Only blocks since the last
+        /// macro block are supported.
+        #[clap(long)]
+        at_block: Option<u32>,
     },
 }
 
@@ -113,7 +200,7 @@ impl HandleSubcommand for AccountCommand {
                     } else {
                         let account = client
                             .blockchain
-                            .get_account_by_address(address.clone())
+                            .get_account_by_address(address.clone(), None)
                             .await?;
                         println!("{}: {:#?}", address.to_user_friendly_address(), account);
                     }
@@ -171,10 +258,96 @@ impl HandleSubcommand for AccountCommand {
                         .await?
                 );
             }
-            AccountCommand::Get { address } => {
+            AccountCommand::NewHdWallet { password } => {
+                println!("{:#?}", client.wallet.create_hd_wallet(password).await?);
+            }
+            AccountCommand::ImportHdWallet { password, mnemonic } => {
+                let wallet_id = client
+                    .wallet
+                    .import_hd_wallet(mnemonic.join(" "), password)
+                    .await?;
+                println!("{:#?}", wallet_id);
+            }
+            AccountCommand::ListHdWallets {} => {
+                let wallet_ids = client.wallet.list_hd_wallets().await?.data;
+                for wallet_id in &wallet_ids {
+                    println!("{}", wallet_id.to_user_friendly_address());
+                }
+            }
+            AccountCommand::ListHdAccounts { wallet_id } => {
+                let accounts = client.wallet.list_hd_wallet_accounts(wallet_id).await?.data;
+                for account in &accounts {
+                    println!(
+                        "{}: {}",
+                        account.path,
+                        account.address.to_user_friendly_address()
+                    );
+                }
+            }
+            AccountCommand::DeriveHdAccount {
+                password,
+                wallet_id,
+                index,
+                path,
+            } => {
+                let account = match (index, path) {
+                    (Some(index), _) => {
+                        client
+                            .wallet
+                            .derive_hd_account_by_index(wallet_id, index, password)
+                            .await?
+                    }
+                    (None, Some(path)) => {
+                        client
+                            .wallet
+                            .derive_hd_account(wallet_id, path, password)
+                            .await?
+                    }
+                    (None, None) => bail!("Either an index or a derivation path is required"),
+                };
+                println!("{:#?}", account);
+            }
+            AccountCommand::Export {
+                password,
+                keystore_password,
+                output,
+                addresses,
+            } => {
+                let addresses = if addresses.is_empty() {
+                    None
+                } else {
+                    Some(addresses)
+                };
+                let keystore = client
+                    .wallet
+                    .export_accounts(addresses, password, keystore_password)
+                    .await?
+                    .data;
+                fs::write(&output, keystore)?;
+                println!("Keystore written to {}", output.display());
+            }
+            AccountCommand::ImportKeystore {
+                password,
+                keystore_password,
+                file,
+            } => {
+                let keystore = fs::read_to_string(file)?;
+                let addresses = client
+                    .wallet
+                    .import_accounts(keystore, keystore_password, password)
+                    .await?
+                    .data;
+                for address in &addresses {
+                    println!("{}", address.to_user_friendly_address());
+                }
+            }
+            AccountCommand::Get { address, at_block } => {
                 println!(
                     "{:#?}",
-                    client.blockchain.get_account_by_address(address).await?
+                    client
+                        .blockchain
+                        .get_account_by_address(address, at_block)
+                        .await?
                 );
             }
         }
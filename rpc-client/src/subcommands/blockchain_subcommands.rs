@@ -6,7 +6,7 @@ use futures::StreamExt;
 use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
 use nimiq_rpc_interface::blockchain::BlockchainInterface;
-use nimiq_rpc_interface::types::LogType;
+use nimiq_rpc_interface::types::{AccountType, LogType, TransactionOrder};
 
 use crate::Client;
 
@@ -95,6 +95,15 @@ pub enum BlockchainCommand {
         #[clap(long)]
         max: Option<u16>,
 
+        /// Only fetch the transactions that come after the transaction with this hash. Used to
+        /// continue from the last transaction of a previous query.
+        #[clap(long)]
+        start_at: Option<Blake2bHash>,
+
+        /// The order in which the transactions are returned. If absent it defaults to descending.
+        #[clap(long, value_enum)]
+        order: Option<TransactionOrder>,
+
         /// If set true only the hash of the transactions will be fetched. Otherwise the full transactions will be retrieved.
         #[clap(short = 'h')]
         just_hash: bool,
@@ -132,17 +141,61 @@ pub enum BlockchainCommand {
         /// Include the stakers of the validator.
         #[clap(short = 's')]
         include_stakers: Option<bool>,
+
+        /// Fetch the validator as it was right after this block. Only blocks since the last macro
+        /// block are supported.
+        #[clap(long)]
+        at_block: Option<u32>,
     },
 
     /// Tries to fetch a staker information given its address.
     Staker {
         /// The address to query by.
         address: Address,
+
+        /// Fetch the staker as it was right after this block. Only blocks since the last macro
+        /// block are supported.
+        #[clap(long)]
+        at_block: Option<u32>,
     },
 
     /// Lists the current stakes from the staking contract.
     Stakes {},
 
+    /// Lists the accounts of the given type, ordered by address. It has an option to specify the
+    /// maximum number of accounts to fetch, it defaults to 500. The number of accounts scanned
+    /// per query is limited, use the returned `continue_at` key to continue the listing.
+    Accounts {
+        /// The type of the accounts to list.
+        #[clap(value_enum)]
+        account_type: AccountType,
+
+        /// Only fetch the accounts after this key. Used to continue from the `continue_at` key of
+        /// a previous query. An address in hex format can be given as well.
+        #[clap(long)]
+        start_at: Option<String>,
+
+        /// Max number of accounts to fetch. If absent it defaults to 500.
+        #[clap(long)]
+        max: Option<u16>,
+    },
+
+    /// Lists the stakers that are delegating to the given validator, ordered by address. It has
+    /// an option to specify the maximum number of stakers to fetch, it defaults to 500.
+    StakersByValidator {
+        /// The address of the validator.
+        validator_address: Address,
+
+        /// Only fetch the stakers with an address after this one. Used to continue from the last
+        /// staker of a previous query.
+        #[clap(long)]
+        start_at: Option<Address>,
+
+        /// Max number of stakers to fetch. If absent it defaults to 500.
+        #[clap(long)]
+        max: Option<u16>,
+    },
+
     /// Follow the head of the blockchain.
     FollowHead {
         /// Show the full block instead of only the hash.
@@ -268,6 +321,8 @@ impl HandleSubcommand for BlockchainCommand {
             BlockchainCommand::TransactionsByAddress {
                 address,
                 max,
+                start_at,
+                order,
                 just_hash,
             } => {
                 if just_hash {
@@ -275,7 +330,7 @@ impl HandleSubcommand for BlockchainCommand {
                         "{:#?}",
                         client
                             .blockchain
-                            .get_transaction_hashes_by_address(address, max)
+                            .get_transaction_hashes_by_address(address, max, start_at, order)
                             .await?
                     )
                 } else {
@@ -283,7 +338,7 @@ impl HandleSubcommand for BlockchainCommand {
                         "{:#?}",
                         client
                             .blockchain
-                            .get_transactions_by_address(address, max)
+                            .get_transactions_by_address(address, max, start_at, order)
                             .await?
                     )
                 }
@@ -307,24 +362,56 @@ impl HandleSubcommand for BlockchainCommand {
             BlockchainCommand::ValidatorByAddress {
                 address,
                 include_stakers,
+                at_block,
             } => println!(
                 "{:#?}",
                 client
                     .blockchain
-                    .get_validator_by_address(address, include_stakers)
+                    .get_validator_by_address(address, include_stakers, at_block)
                     .await?
             ),
 
-            BlockchainCommand::Staker { address } => {
+            BlockchainCommand::Staker { address, at_block } => {
                 println!(
                     "{:#?}",
-                    client.blockchain.get_staker_by_address(address).await?
+                    client
+                        .blockchain
+                        .get_staker_by_address(address, at_block)
+                        .await?
                 )
             }
             BlockchainCommand::Stakes {} => {
                 println!("{:#?}", client.blockchain.get_active_validators().await?);
             }
 
+            BlockchainCommand::Accounts {
+                account_type,
+                start_at,
+                max,
+            } => {
+                println!(
+                    "{:#?}",
+                    client
+                        .blockchain
+                        .list_accounts_by_type(account_type, start_at, max)
+                        .await?
+                );
+            }
+
+            BlockchainCommand::StakersByValidator {
+                validator_address,
+                start_at,
+                max,
+            } => {
+                println!(
+                    "{:#?}",
+                    client
+                        .blockchain
+                        .list_stakers_by_validator(validator_address, start_at, max)
+                        .await?
+                );
+            }
+
             BlockchainCommand::FollowHead { block: show_block } => {
                 if show_block {
                     let mut stream = client
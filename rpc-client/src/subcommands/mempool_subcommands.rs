@@ -1,7 +1,10 @@
 use anyhow::Error;
 use async_trait::async_trait;
 use clap::Parser;
+use futures::StreamExt;
+use nimiq_keys::Address;
 use nimiq_rpc_interface::mempool::MempoolInterface;
+use nimiq_rpc_interface::types::MempoolEventType;
 
 use super::accounts_subcommands::HandleSubcommand;
 use crate::Client;
@@ -30,6 +33,27 @@ pub enum MempoolCommand {
 
     /// Returns the minimum fee per byte of the local mempool.
     MinFeePerByte {},
+
+    /// Estimates the fee per byte needed for a transaction to be included within the given number
+    /// of blocks.
+    EstimateFee {
+        /// The number of blocks within which the transaction should be included.
+        #[clap(short = 'b', long, default_value = "1")]
+        blocks: u32,
+    },
+
+    /// Follow the transactions entering or leaving the local mempool that are sent by or to any
+    /// of the specified addresses and of any of the event types given.
+    /// If no addresses or no event types are provided it follows all transactions.
+    FollowTransactions {
+        /// List of all addresses to follow. If empty it does not filter by address.
+        #[clap(short = 'a', long)]
+        addresses: Vec<Address>,
+
+        /// List of all event types to select. If empty it does not filter by event type.
+        #[clap(short = 'e', long, value_enum)]
+        event_types: Vec<MempoolEventType>,
+    },
 }
 
 #[async_trait]
@@ -66,6 +90,22 @@ impl HandleSubcommand for MempoolCommand {
             MempoolCommand::MinFeePerByte {} => {
                 println!("{:#?}", client.mempool.get_min_fee_per_byte().await?);
             }
+            MempoolCommand::EstimateFee { blocks } => {
+                println!("{:#?}", client.mempool.estimate_fee_per_byte(blocks).await?);
+            }
+            MempoolCommand::FollowTransactions {
+                addresses,
+                event_types,
+            } => {
+                let mut stream = client
+                    .mempool
+                    .subscribe_for_mempool_transactions(addresses, event_types)
+                    .await?;
+
+                while let Some(event) = stream.next().await {
+                    println!("{:#?}", event);
+                }
+            }
         }
         Ok(())
     }
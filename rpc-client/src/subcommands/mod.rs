@@ -2,6 +2,7 @@ pub use accounts_subcommands::AccountCommand;
 pub use accounts_subcommands::HandleSubcommand;
 pub use blockchain_subcommands::BlockchainCommand;
 pub use mempool_subcommands::MempoolCommand;
+pub use multisig_subcommands::MultisigCommand;
 pub use network_subcommands::NetworkCommand;
 pub use policy_subcommands::PolicyCommand;
 pub use transactions_subcommands::TransactionCommand;
@@ -11,6 +12,7 @@ pub use zkp_component_subcommands::ZKPComponentCommand;
 mod accounts_subcommands;
 mod blockchain_subcommands;
 mod mempool_subcommands;
+mod multisig_subcommands;
 mod network_subcommands;
 mod policy_subcommands;
 mod transactions_subcommands;
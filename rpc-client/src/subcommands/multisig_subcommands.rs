@@ -0,0 +1,199 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use clap::Parser;
+
+use nimiq_keys::multisig::{Commitment, PartialSignature};
+use nimiq_keys::{Address, PublicKey};
+use nimiq_primitives::coin::Coin;
+use nimiq_rpc_interface::{
+    consensus::ConsensusInterface, types::ValidityStartHeight, wallet::WalletInterface,
+};
+
+use crate::Client;
+
+use super::accounts_subcommands::HandleSubcommand;
+
+#[derive(Debug, Parser)]
+pub enum MultisigCommand {
+    /// Derives the address of a multisig account that requires `min_signatures` signatures of
+    /// the given public keys.
+    MultisigAddress {
+        /// The number of signatures that are required to sign a transaction.
+        min_signatures: usize,
+
+        /// The public keys of all owners of the multisig account.
+        #[clap(required = true)]
+        public_keys: Vec<PublicKey>,
+    },
+
+    /// Creates an unsigned basic transaction from a multisig account, to be signed by its owners.
+    MultisigTransaction {
+        /// The address of the multisig account.
+        sender: Address,
+
+        /// Recipient for this transaction. This must be a basic account.
+        recipient: Address,
+
+        /// The amount of NIM to be sent.
+        value: Coin,
+
+        /// The associated transaction fee to be payed. If absent it defaults to 0 NIM.
+        #[clap(short, long, default_value = "0")]
+        fee: Coin,
+
+        /// The block height from which on the transaction could be applied.
+        /// If absent it defaults to the current block height at time of processing.
+        #[clap(short, long, default_value_t)]
+        validity_start_height: ValidityStartHeight,
+    },
+
+    /// Creates a commitment for signing a multisig transaction, which must be shared with the
+    /// other signers. The corresponding random secret is kept by the node and can only be used
+    /// for a single partial signature.
+    MultisigCommitment {},
+
+    /// Creates the partial signature of the given wallet for a multisig transaction. The wallet
+    /// must already be unlocked.
+    MultisigPartialSign {
+        /// The unsigned transaction as hex string.
+        raw_tx: String,
+
+        /// The address of the signing wallet.
+        wallet: Address,
+
+        /// The public keys of all owners of the multisig account.
+        #[clap(long, required = true, value_delimiter = ',')]
+        public_keys: Vec<PublicKey>,
+
+        /// The public keys of the owners that sign the transaction.
+        #[clap(long, required = true, value_delimiter = ',')]
+        signers: Vec<PublicKey>,
+
+        /// The commitment that was created by this node for the signature.
+        #[clap(long)]
+        commitment: Commitment,
+
+        /// The commitments of all signers.
+        #[clap(long, required = true, value_delimiter = ',')]
+        commitments: Vec<Commitment>,
+    },
+
+    /// Aggregates the partial signatures of all signers and outputs the signed transaction as
+    /// hex string.
+    MultisigAggregate {
+        /// The unsigned transaction as hex string.
+        raw_tx: String,
+
+        /// The public keys of all owners of the multisig account.
+        #[clap(long, required = true, value_delimiter = ',')]
+        public_keys: Vec<PublicKey>,
+
+        /// The public keys of the owners that sign the transaction.
+        #[clap(long, required = true, value_delimiter = ',')]
+        signers: Vec<PublicKey>,
+
+        /// The commitments of all signers.
+        #[clap(long, required = true, value_delimiter = ',')]
+        commitments: Vec<Commitment>,
+
+        /// The partial signatures of all signers.
+        #[clap(long, required = true, value_delimiter = ',')]
+        partial_signatures: Vec<PartialSignature>,
+
+        /// Send the signed transaction to the network.
+        #[clap(long)]
+        send: bool,
+    },
+}
+
+#[async_trait]
+impl HandleSubcommand for MultisigCommand {
+    async fn handle_subcommand(self, mut client: Client) -> Result<(), Error> {
+        match self {
+            MultisigCommand::MultisigAddress {
+                min_signatures,
+                public_keys,
+            } => {
+                let address = client
+                    .wallet
+                    .create_multisig_address(public_keys, min_signatures)
+                    .await?
+                    .data;
+                println!("{}", address.to_user_friendly_address());
+            }
+            MultisigCommand::MultisigTransaction {
+                sender,
+                recipient,
+                value,
+                fee,
+                validity_start_height,
+            } => {
+                let raw_tx = client
+                    .consensus
+                    .create_multisig_basic_transaction(
+                        sender,
+                        recipient,
+                        value,
+                        fee,
+                        validity_start_height,
+                    )
+                    .await?
+                    .data;
+                println!("{}", raw_tx);
+            }
+            MultisigCommand::MultisigCommitment {} => {
+                let commitment = client.wallet.create_multisig_commitment().await?.data;
+                println!("{}", commitment.to_hex());
+            }
+            MultisigCommand::MultisigPartialSign {
+                raw_tx,
+                wallet,
+                public_keys,
+                signers,
+                commitment,
+                commitments,
+            } => {
+                let partial_signature = client
+                    .wallet
+                    .create_multisig_partial_signature(
+                        raw_tx,
+                        wallet,
+                        public_keys,
+                        signers,
+                        commitment,
+                        commitments,
+                    )
+                    .await?
+                    .data;
+                println!("{}", partial_signature.to_hex());
+            }
+            MultisigCommand::MultisigAggregate {
+                raw_tx,
+                public_keys,
+                signers,
+                commitments,
+                partial_signatures,
+                send,
+            } => {
+                let raw_tx = client
+                    .wallet
+                    .aggregate_multisig_signatures(
+                        raw_tx,
+                        public_keys,
+                        signers,
+                        commitments,
+                        partial_signatures,
+                    )
+                    .await?
+                    .data;
+                if send {
+                    let txid = client.consensus.send_raw_transaction(raw_tx).await?;
+                    println!("{:#?}", txid);
+                } else {
+                    println!("{}", raw_tx);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -18,6 +18,13 @@ pub enum NetworkCommand {
         #[clap(short, long)]
         count: bool,
     },
+
+    /// Returns detailed information about a connected peer, or about all of them if no peer ID is
+    /// given.
+    PeerInfo {
+        /// The peer ID of the connected peer.
+        peer_id: Option<String>,
+    },
 }
 
 #[async_trait]
@@ -34,6 +41,14 @@ impl HandleSubcommand for NetworkCommand {
                     println!("{:#?}", client.network.get_peer_list().await?);
                 }
             }
+            NetworkCommand::PeerInfo { peer_id } => match peer_id {
+                Some(peer_id) => {
+                    println!("{:#?}", client.network.get_peer_info(peer_id).await?);
+                }
+                None => {
+                    println!("{:#?}", client.network.get_peer_info_list().await?);
+                }
+            },
         }
         Ok(())
     }
@@ -5,8 +5,9 @@ use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
 
 use crate::types::{
-    Account, Block, BlockLog, BlockchainState, ExecutedTransaction, Inherent, LogType, ParkedSet,
-    RPCData, RPCResult, SlashedSlots, Slot, Staker, Validator,
+    Account, AccountType, AccountsPage, Block, BlockLog, BlockchainState, ExecutedTransaction,
+    Inherent, LogType, ParkedSet, RPCData, RPCResult, SlashedSlots, Slot, Staker, TransactionOrder,
+    Validator,
 };
 
 #[nimiq_jsonrpc_derive::proxy(name = "BlockchainProxy", rename_all = "camelCase")]
@@ -73,19 +74,31 @@ pub trait BlockchainInterface {
         &mut self,
         address: Address,
         max: Option<u16>,
+        start_at: Option<Blake2bHash>,
+        order: Option<TransactionOrder>,
     ) -> RPCResult<Vec<Blake2bHash>, (), Self::Error>;
 
     async fn get_transactions_by_address(
         &mut self,
         address: Address,
         max: Option<u16>,
+        start_at: Option<Blake2bHash>,
+        order: Option<TransactionOrder>,
     ) -> RPCResult<Vec<ExecutedTransaction>, (), Self::Error>;
 
     async fn get_account_by_address(
         &mut self,
         address: Address,
+        at_block: Option<u32>,
     ) -> RPCResult<Account, BlockchainState, Self::Error>;
 
+    async fn list_accounts_by_type(
+        &mut self,
+        account_type: AccountType,
+        start_at: Option<String>,
+        max: Option<u16>,
+    ) -> RPCResult<AccountsPage, BlockchainState, Self::Error>;
+
     async fn get_active_validators(
         &mut self,
     ) -> RPCResult<Vec<Validator>, BlockchainState, Self::Error>;
@@ -105,13 +118,22 @@ pub trait BlockchainInterface {
         &mut self,
         address: Address,
         include_stakers: Option<bool>,
+        at_block: Option<u32>,
     ) -> RPCResult<Validator, BlockchainState, Self::Error>;
 
     async fn get_staker_by_address(
         &mut self,
         address: Address,
+        at_block: Option<u32>,
     ) -> RPCResult<Staker, BlockchainState, Self::Error>;
 
+    async fn list_stakers_by_validator(
+        &mut self,
+        validator_address: Address,
+        start_at: Option<Address>,
+        max: Option<u16>,
+    ) -> RPCResult<Vec<Staker>, BlockchainState, Self::Error>;
+
     #[stream]
     async fn subscribe_for_head_block(
         &mut self,
@@ -43,6 +43,15 @@ pub trait ConsensusInterface {
         validity_start_height: ValidityStartHeight,
     ) -> RPCResult<Blake2bHash, (), Self::Error>;
 
+    async fn create_multisig_basic_transaction(
+        &mut self,
+        sender: Address,
+        recipient: Address,
+        value: Coin,
+        fee: Coin,
+        validity_start_height: ValidityStartHeight,
+    ) -> RPCResult<String, (), Self::Error>;
+
     async fn create_basic_transaction_with_data(
         &mut self,
         wallet: Address,
@@ -1,6 +1,8 @@
-use crate::types::{HashOrTx, MempoolInfo, RPCResult};
+use crate::types::{HashOrTx, MempoolEvent, MempoolEventType, MempoolInfo, RPCData, RPCResult};
 use async_trait::async_trait;
+use futures::stream::BoxStream;
 use nimiq_hash::Blake2bHash;
+use nimiq_keys::Address;
 
 #[nimiq_jsonrpc_derive::proxy(name = "MempoolProxy", rename_all = "camelCase")]
 #[async_trait]
@@ -23,4 +25,16 @@ pub trait MempoolInterface {
     async fn mempool(&mut self) -> RPCResult<MempoolInfo, (), Self::Error>;
 
     async fn get_min_fee_per_byte(&mut self) -> RPCResult<f64, (), Self::Error>;
+
+    async fn estimate_fee_per_byte(
+        &mut self,
+        target_blocks: u32,
+    ) -> RPCResult<f64, (), Self::Error>;
+
+    #[stream]
+    async fn subscribe_for_mempool_transactions(
+        &mut self,
+        addresses: Vec<Address>,
+        event_types: Vec<MempoolEventType>,
+    ) -> Result<BoxStream<'static, RPCData<MempoolEvent, ()>>, Self::Error>;
 }
@@ -1,4 +1,4 @@
-use crate::types::RPCResult;
+use crate::types::{BannedIp, PeerInfo, RPCResult};
 use async_trait::async_trait;
 
 #[nimiq_jsonrpc_derive::proxy(name = "NetworkProxy", rename_all = "camelCase")]
@@ -11,4 +11,21 @@ pub trait NetworkInterface {
     async fn get_peer_count(&mut self) -> RPCResult<usize, (), Self::Error>;
 
     async fn get_peer_list(&mut self) -> RPCResult<Vec<String>, (), Self::Error>;
+
+    async fn get_peer_info(&mut self, peer_id: String) -> RPCResult<PeerInfo, (), Self::Error>;
+
+    async fn get_peer_info_list(&mut self) -> RPCResult<Vec<PeerInfo>, (), Self::Error>;
+
+    async fn ban_peer(
+        &mut self,
+        peer_id: String,
+        duration: Option<u64>,
+    ) -> RPCResult<String, (), Self::Error>;
+
+    async fn ban_ip(&mut self, ip: String, duration: Option<u64>)
+        -> RPCResult<(), (), Self::Error>;
+
+    async fn unban_ip(&mut self, ip: String) -> RPCResult<bool, (), Self::Error>;
+
+    async fn list_banned(&mut self) -> RPCResult<Vec<BannedIp>, (), Self::Error>;
 }
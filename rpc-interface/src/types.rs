@@ -14,6 +14,7 @@ use serde_with::{serde_as, DeserializeFromStr, DisplayFromStr, SerializeDisplay}
 use beserial::Serialize as BeSerialize;
 use nimiq_account::{BlockLog as BBlockLog, Log, TransactionLog};
 use nimiq_block::{MicroJustification, MultiSignature};
+use nimiq_blockchain::HistoryOrder;
 
 use nimiq_blockchain_proxy::BlockchainReadProxy;
 use nimiq_bls::CompressedPublicKey;
@@ -108,6 +109,43 @@ impl Into<HTLCContractHashAlgorithm> for HashAlgorithm {
     }
 }
 
+/// The order in which the transactions of an address are returned.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum TransactionOrder {
+    /// From the least recent to the most recent transaction.
+    Ascending,
+    /// From the most recent to the least recent transaction.
+    Descending,
+}
+
+impl From<TransactionOrder> for HistoryOrder {
+    fn from(order: TransactionOrder) -> Self {
+        match order {
+            TransactionOrder::Ascending => HistoryOrder::Ascending,
+            TransactionOrder::Descending => HistoryOrder::Descending,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum AccountType {
+    Basic,
+    Vesting,
+    Htlc,
+}
+
+impl From<AccountType> for nimiq_primitives::account::AccountType {
+    fn from(account_type: AccountType) -> Self {
+        match account_type {
+            AccountType::Basic => nimiq_primitives::account::AccountType::Basic,
+            AccountType::Vesting => nimiq_primitives::account::AccountType::Vesting,
+            AccountType::Htlc => nimiq_primitives::account::AccountType::HTLC,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Block {
@@ -670,6 +708,16 @@ impl Account {
     }
 }
 
+/// A page of accounts returned by a listing that scans the accounts trie.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountsPage {
+    pub accounts: Vec<Account>,
+    /// The key at which the scan stopped. If present, the listing can be continued by passing it
+    /// as `startAt`. It is absent once the end of the accounts trie has been reached.
+    pub continue_at: Option<String>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Staker {
@@ -1133,3 +1181,130 @@ impl MempoolInfo {
         info
     }
 }
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum MempoolEventType {
+    Added,
+    Included,
+    BlockBuilding,
+    Expired,
+    Evicted,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RemovalReason {
+    /// The transaction was included in a block of the chain.
+    Included,
+    /// The transaction was taken from the mempool to build a block, which is not necessarily part
+    /// of the chain yet.
+    BlockBuilding,
+    Expired,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", tag = "type")]
+pub enum MempoolEvent {
+    Added {
+        transaction: Transaction,
+    },
+    Removed {
+        reason: RemovalReason,
+        transaction: Transaction,
+    },
+    Evicted {
+        transaction: Transaction,
+    },
+    /// The subscription fell behind and missed events. The subscriber needs to resync its view of
+    /// the mempool, e.g. by requesting the transactions that are in the mempool.
+    #[serde(rename_all = "camelCase")]
+    Lagged {
+        missed_events: u64,
+    },
+}
+
+impl MempoolEvent {
+    /// Returns the type of the event, or `None` for `Lagged` events.
+    pub fn event_type(&self) -> Option<MempoolEventType> {
+        match self {
+            MempoolEvent::Added { .. } => Some(MempoolEventType::Added),
+            MempoolEvent::Removed {
+                reason: RemovalReason::Included,
+                ..
+            } => Some(MempoolEventType::Included),
+            MempoolEvent::Removed {
+                reason: RemovalReason::BlockBuilding,
+                ..
+            } => Some(MempoolEventType::BlockBuilding),
+            MempoolEvent::Removed {
+                reason: RemovalReason::Expired,
+                ..
+            } => Some(MempoolEventType::Expired),
+            MempoolEvent::Evicted { .. } => Some(MempoolEventType::Evicted),
+            MempoolEvent::Lagged { .. } => None,
+        }
+    }
+
+    /// Returns the transaction of the event, or `None` for `Lagged` events.
+    pub fn transaction(&self) -> Option<&Transaction> {
+        match self {
+            MempoolEvent::Added { transaction }
+            | MempoolEvent::Removed { transaction, .. }
+            | MempoolEvent::Evicted { transaction } => Some(transaction),
+            MempoolEvent::Lagged { .. } => None,
+        }
+    }
+
+    /// Checks if the transaction of this event is sent by or to any of the given addresses and if
+    /// the event is of any of the given types. An empty list of addresses or event types matches
+    /// any address or event type respectively. `Lagged` events always match, since the missed
+    /// events might have matched.
+    pub fn matches(&self, addresses: &[Address], event_types: &[MempoolEventType]) -> bool {
+        let (transaction, event_type) = match (self.transaction(), self.event_type()) {
+            (Some(transaction), Some(event_type)) => (transaction, event_type),
+            _ => return true,
+        };
+
+        (addresses.is_empty()
+            || addresses
+                .iter()
+                .any(|address| *address == transaction.from || *address == transaction.to))
+            && (event_types.is_empty() || event_types.contains(&event_type))
+    }
+}
+
+/// An IP address or subnet that is banned from connecting to us.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BannedIp {
+    /// The banned IP address or subnet, e.g. `192.168.1.0/24`.
+    pub ip: String,
+    /// The UNIX timestamp in milliseconds until which the IP address or subnet is banned.
+    pub banned_until: u64,
+}
+
+/// Information about a connected peer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerInfo {
+    pub peer_id: String,
+    /// The address of the peer that the connection was established with.
+    pub address: String,
+    /// Either `inbound` if the peer connected to us, or `outbound` if we connected to the peer.
+    pub direction: String,
+    /// The bitmask of services advertised by the peer, if it has completed discovery.
+    pub services: Option<u32>,
+    /// The user agent sent by the peer.
+    pub user_agent: Option<String>,
+    /// The protocol version sent by the peer.
+    pub protocol_version: Option<String>,
+    /// The gossipsub score of the peer.
+    pub score: Option<f64>,
+    /// The age of the connection in seconds.
+    pub connection_age: u64,
+    /// The number of requests that we sent to the peer.
+    pub requests_sent: u64,
+    /// The number of requests that we received from the peer.
+    pub requests_received: u64,
+}
@@ -17,4 +17,8 @@ pub trait ValidatorInterface {
         &mut self,
         automatic_reactivate: bool,
     ) -> RPCResult<(), (), Self::Error>;
+
+    async fn export_slashing_protection(&mut self) -> RPCResult<String, (), Self::Error>;
+
+    async fn import_slashing_protection(&mut self, data: String) -> RPCResult<(), (), Self::Error>;
 }
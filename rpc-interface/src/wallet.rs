@@ -1,5 +1,6 @@
 use crate::types::RPCResult;
 use async_trait::async_trait;
+use nimiq_keys::multisig::{Commitment, PartialSignature};
 use nimiq_keys::{Address, PrivateKey, PublicKey, Signature};
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -17,6 +18,20 @@ pub struct ReturnAccount {
     pub private_key: PrivateKey,
 }
 
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReturnHdWallet {
+    pub id: Address,
+    pub mnemonic: String,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReturnHdWalletAccount {
+    pub path: String,
+    pub address: Address,
+}
+
 #[nimiq_jsonrpc_derive::proxy(name = "WalletProxy", rename_all = "camelCase")]
 #[async_trait]
 pub trait WalletInterface {
@@ -67,4 +82,77 @@ pub trait WalletInterface {
         signature: Signature,
         is_hex: bool,
     ) -> RPCResult<bool, (), Self::Error>;
+
+    async fn create_multisig_address(
+        &mut self,
+        public_keys: Vec<PublicKey>,
+        min_signatures: usize,
+    ) -> RPCResult<Address, (), Self::Error>;
+
+    async fn create_multisig_commitment(&mut self) -> RPCResult<Commitment, (), Self::Error>;
+
+    async fn create_multisig_partial_signature(
+        &mut self,
+        raw_tx: String,
+        address: Address,
+        public_keys: Vec<PublicKey>,
+        signers: Vec<PublicKey>,
+        commitment: Commitment,
+        commitments: Vec<Commitment>,
+    ) -> RPCResult<PartialSignature, (), Self::Error>;
+
+    async fn aggregate_multisig_signatures(
+        &mut self,
+        raw_tx: String,
+        public_keys: Vec<PublicKey>,
+        signers: Vec<PublicKey>,
+        commitments: Vec<Commitment>,
+        partial_signatures: Vec<PartialSignature>,
+    ) -> RPCResult<String, (), Self::Error>;
+
+    async fn create_hd_wallet(
+        &mut self,
+        passphrase: Option<String>,
+    ) -> RPCResult<ReturnHdWallet, (), Self::Error>;
+
+    async fn import_hd_wallet(
+        &mut self,
+        mnemonic: String,
+        passphrase: Option<String>,
+    ) -> RPCResult<Address, (), Self::Error>;
+
+    async fn list_hd_wallets(&mut self) -> RPCResult<Vec<Address>, (), Self::Error>;
+
+    async fn list_hd_wallet_accounts(
+        &mut self,
+        wallet_id: Address,
+    ) -> RPCResult<Vec<ReturnHdWalletAccount>, (), Self::Error>;
+
+    async fn derive_hd_account(
+        &mut self,
+        wallet_id: Address,
+        path: String,
+        passphrase: Option<String>,
+    ) -> RPCResult<ReturnHdWalletAccount, (), Self::Error>;
+
+    async fn derive_hd_account_by_index(
+        &mut self,
+        wallet_id: Address,
+        index: u32,
+        passphrase: Option<String>,
+    ) -> RPCResult<ReturnHdWalletAccount, (), Self::Error>;
+
+    async fn export_accounts(
+        &mut self,
+        addresses: Option<Vec<Address>>,
+        passphrase: Option<String>,
+        keystore_passphrase: String,
+    ) -> RPCResult<String, (), Self::Error>;
+
+    async fn import_accounts(
+        &mut self,
+        keystore: String,
+        keystore_passphrase: String,
+        passphrase: Option<String>,
+    ) -> RPCResult<Vec<Address>, (), Self::Error>;
 }
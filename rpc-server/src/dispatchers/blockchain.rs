@@ -1,31 +1,40 @@
 use async_trait::async_trait;
 use futures::{future, stream::BoxStream, StreamExt};
 
-use nimiq_account::{BlockLog as BBlockLog, StakingContract, TransactionLog};
+use nimiq_account::{AccountsTrie, BlockLog as BBlockLog, StakingContract, TransactionLog};
+use nimiq_blockchain::{Blockchain, HistoryOrder};
 use nimiq_blockchain_interface::{AbstractBlockchain, BlockchainEvent};
 use nimiq_blockchain_proxy::{BlockchainProxy, BlockchainReadProxy};
+use nimiq_consensus::ConsensusProxy;
+use nimiq_database::Transaction as DBTransaction;
 use nimiq_hash::Blake2bHash;
 use nimiq_keys::Address;
+use nimiq_network_libp2p::Network;
 use nimiq_primitives::policy::Policy;
 use nimiq_rpc_interface::types::{
-    is_of_log_type_and_related_to_addresses, BlockLog, BlockchainState, ParkedSet, RPCData,
-    RPCResult, Validator,
+    is_of_log_type_and_related_to_addresses, AccountType, AccountsPage, BlockLog, BlockchainState,
+    ParkedSet, RPCData, RPCResult, TransactionOrder, Validator,
 };
 use nimiq_rpc_interface::{
     blockchain::BlockchainInterface,
     types::{Account, Block, ExecutedTransaction, Inherent, LogType, SlashedSlots, Slot, Staker},
 };
+use nimiq_trie::key_nibbles::KeyNibbles;
 use tokio_stream::wrappers::BroadcastStream;
 
 use crate::error::Error;
 
 pub struct BlockchainDispatcher {
     blockchain: BlockchainProxy,
+    consensus: ConsensusProxy<Network>,
 }
 
 impl BlockchainDispatcher {
-    pub fn new(blockchain: BlockchainProxy) -> Self {
-        Self { blockchain }
+    pub fn new(consensus: ConsensusProxy<Network>) -> Self {
+        Self {
+            blockchain: consensus.blockchain.clone(),
+            consensus,
+        }
     }
 }
 
@@ -50,49 +59,140 @@ fn get_block_by_hash(
 
 /// Tries to fetch a validator information given its address. It has an option to include a collection
 /// containing the addresses and stakes of all the stakers that are delegating to the validator.
+/// If a block number is given, the validator is fetched as it was right after that block.
 /// This function requires the read lock acquisition prior to its execution
 fn get_validator_by_address(
     blockchain_proxy: &BlockchainReadProxy,
     address: &Address,
     include_stakers: Option<bool>,
+    at_block: Option<u32>,
 ) -> RPCResult<Validator, BlockchainState, Error> {
     if let BlockchainReadProxy::Full(blockchain) = blockchain_proxy {
-        let accounts_tree = &blockchain.state().accounts.tree;
-        let db_txn = blockchain.read_transaction();
-        let validator = StakingContract::get_validator(accounts_tree, &db_txn, address);
+        with_accounts_at(blockchain, at_block, |accounts_tree, db_txn, state| {
+            let validator = StakingContract::get_validator(accounts_tree, db_txn, address);
 
-        if validator.is_none() {
-            return Err(Error::ValidatorNotFound(address.clone()));
-        }
+            if validator.is_none() {
+                return Err(Error::ValidatorNotFound(address.clone()));
+            }
 
-        let mut stakers = None;
+            let mut stakers = None;
 
-        if include_stakers == Some(true) {
-            let staker_addresses =
-                StakingContract::get_validator_stakers(accounts_tree, &db_txn, address);
+            if include_stakers == Some(true) {
+                let staker_addresses =
+                    StakingContract::get_validator_stakers(accounts_tree, db_txn, address);
 
-            let mut stakers_list: Vec<Staker> = vec![];
+                let mut stakers_list: Vec<Staker> = vec![];
 
-            for address in staker_addresses {
-                let mut staker =
-                    StakingContract::get_staker(accounts_tree, &db_txn, &address).unwrap();
-                // Delegation is unnecessary because the address is in the parent struct.
-                staker.delegation = None;
-                stakers_list.push(Staker::from_staker(&staker));
-            }
+                for address in staker_addresses {
+                    let mut staker =
+                        StakingContract::get_staker(accounts_tree, db_txn, &address).unwrap();
+                    // Delegation is unnecessary because the address is in the parent struct.
+                    staker.delegation = None;
+                    stakers_list.push(Staker::from_staker(&staker));
+                }
 
-            stakers = Some(stakers_list);
-        }
+                stakers = Some(stakers_list);
+            }
 
-        Ok(RPCData::with_blockchain(
-            Validator::from_validator(&validator.unwrap(), stakers),
-            blockchain_proxy,
-        ))
+            Ok(RPCData {
+                data: Validator::from_validator(&validator.unwrap(), stakers),
+                metadata: state,
+            })
+        })?
     } else {
         Err(Error::NotSupportedForLightBlockchain)
     }
 }
 
+/// Returns at most `max` accounts of the given type together with their addresses, ordered by
+/// address, starting after the key `start_key`. The accounts trie is scanned in chunks, since
+/// accounts of other types and the subtrie of the staking contract may be in between. To bound
+/// the work done while the blockchain is locked, at most `MAX_SCANNED_NODES` nodes are scanned.
+/// Returns the key at which the scan stopped, unless the end of the trie was reached.
+fn get_accounts_by_type(
+    accounts_tree: &AccountsTrie,
+    db_txn: &DBTransaction,
+    account_type: AccountType,
+    start_key: KeyNibbles,
+    max: usize,
+) -> (Vec<(Address, nimiq_account::Account)>, Option<KeyNibbles>) {
+    const CHUNK_SIZE: usize = 1000;
+    const MAX_SCANNED_NODES: usize = 10 * CHUNK_SIZE;
+
+    let account_type: nimiq_primitives::account::AccountType = account_type.into();
+    let mut accounts = vec![];
+
+    if max == 0 {
+        return (accounts, Some(start_key));
+    }
+
+    let mut start_key = start_key;
+    let mut scanned_nodes = 0;
+
+    loop {
+        let chunk = accounts_tree.get_chunk_with_keys(db_txn, &start_key, CHUNK_SIZE);
+        let is_last_chunk = chunk.len() < CHUNK_SIZE;
+        scanned_nodes += chunk.len();
+
+        for (key, account) in chunk {
+            // Chunks start at (and include) the key at which the previous one ended.
+            if key == start_key {
+                continue;
+            }
+
+            // Only accounts that are stored at an address have a key of the length of an address,
+            // the other keys belong to the subtrie of the staking contract.
+            if let Some(address) = key.to_address() {
+                if account.account_type() == account_type {
+                    accounts.push((address, account));
+
+                    if accounts.len() >= max {
+                        return (accounts, Some(key));
+                    }
+                }
+            }
+
+            start_key = key;
+        }
+
+        if is_last_chunk {
+            return (accounts, None);
+        }
+
+        if scanned_nodes >= MAX_SCANNED_NODES {
+            return (accounts, Some(start_key));
+        }
+    }
+}
+
+/// Calls `f` with the accounts tree, a database transaction and the corresponding blockchain state.
+/// If a block number is given, the accounts are reconstructed at that block. Otherwise, the
+/// current head is used.
+fn with_accounts_at<F, R>(blockchain: &Blockchain, at_block: Option<u32>, f: F) -> Result<R, Error>
+where
+    F: FnOnce(&AccountsTrie, &DBTransaction, BlockchainState) -> R,
+{
+    match at_block {
+        None => {
+            let db_txn = blockchain.read_transaction();
+            let state = BlockchainState::new(blockchain.block_number(), blockchain.head_hash());
+            Ok(f(&blockchain.state().accounts.tree, &db_txn, state))
+        }
+        Some(block_number) => {
+            let block_hash = blockchain
+                .get_block_at(block_number, false, None)
+                .map_err(|_| Error::BlockNotFound(block_number))?
+                .hash();
+            let state = BlockchainState::new(block_number, block_hash);
+            Ok(
+                blockchain.with_accounts_at(block_number, |accounts, db_txn| {
+                    f(&accounts.tree, db_txn, state)
+                })?,
+            )
+        }
+    }
+}
+
 #[nimiq_jsonrpc_derive::service(rename_all = "camelCase")]
 #[async_trait]
 impl BlockchainInterface for BlockchainDispatcher {
@@ -405,16 +505,26 @@ impl BlockchainInterface for BlockchainDispatcher {
     /// where the given address is listed as a recipient or as a sender are considered. Reward
     /// transactions are also returned. It has an option to specify the maximum number of hashes to
     /// fetch, it defaults to 500.
+    /// The history can be walked page by page by passing the last hash of the previous page as
+    /// `start_at`. The order defaults to descending, i.e. most recent transactions first.
     async fn get_transaction_hashes_by_address(
         &mut self,
         address: Address,
         max: Option<u16>,
+        start_at: Option<Blake2bHash>,
+        order: Option<TransactionOrder>,
     ) -> RPCResult<Vec<Blake2bHash>, (), Self::Error> {
         if let BlockchainProxy::Full(blockchain) = &self.blockchain {
             Ok(blockchain
                 .read()
                 .history_store
-                .get_tx_hashes_by_address(&address, max.unwrap_or(500), None)
+                .get_tx_hashes_by_address_paginated(
+                    &address,
+                    max.unwrap_or(500),
+                    start_at.as_ref(),
+                    order.map_or(HistoryOrder::Descending, Into::into),
+                    None,
+                )
                 .into())
         } else {
             Err(Error::NotSupportedForLightBlockchain)
@@ -425,16 +535,25 @@ impl BlockchainInterface for BlockchainDispatcher {
     /// where the given address is listed as a recipient or as a sender are considered. Reward
     /// transactions are also returned. It has an option to specify the maximum number of transactions
     /// to fetch, it defaults to 500.
+    /// The history can be walked page by page by passing the hash of the last transaction of the
+    /// previous page as `start_at`. The order defaults to descending, i.e. most recent transactions
+    /// first.
     async fn get_transactions_by_address(
         &mut self,
         address: Address,
         max: Option<u16>,
+        start_at: Option<Blake2bHash>,
+        order: Option<TransactionOrder>,
     ) -> RPCResult<Vec<ExecutedTransaction>, (), Self::Error> {
-        if let BlockchainReadProxy::Full(blockchain) = self.blockchain.read() {
+        if let BlockchainProxy::Full(blockchain) = &self.blockchain {
+            let blockchain = blockchain.read();
+
             // Get the transaction hashes for this address.
-            let tx_hashes = blockchain.history_store.get_tx_hashes_by_address(
+            let tx_hashes = blockchain.history_store.get_tx_hashes_by_address_paginated(
                 &address,
                 max.unwrap_or(500),
+                start_at.as_ref(),
+                order.map_or(HistoryOrder::Descending, Into::into),
                 None,
             );
 
@@ -472,31 +591,128 @@ impl BlockchainInterface for BlockchainDispatcher {
 
             Ok(txs.into())
         } else {
-            Err(Error::NotSupportedForLightBlockchain)
+            // Light blockchains request the transactions from their peers, which can't be
+            // paginated.
+            if start_at.is_some() || matches!(order, Some(TransactionOrder::Ascending)) {
+                return Err(Error::NotSupportedForLightBlockchain);
+            }
+
+            let ext_txs = self
+                .consensus
+                .request_transactions_by_address(address, max.unwrap_or(500))
+                .await?;
+            let head_height = self.blockchain.read().block_number();
+
+            Ok(ext_txs
+                .into_iter()
+                .filter_map(|ext_tx| {
+                    let block_number = ext_tx.block_number;
+                    let timestamp = ext_tx.block_time;
+                    ext_tx.into_transaction().ok().map(|tx| {
+                        ExecutedTransaction::from_blockchain(
+                            tx,
+                            block_number,
+                            timestamp,
+                            head_height,
+                        )
+                    })
+                })
+                .collect::<Vec<_>>()
+                .into())
         }
     }
 
-    /// Tries to fetch the account at the given address.
+    /// Tries to fetch the account at the given address. It has an option to fetch the account as
+    /// it was right after the given block. This is only possible for blocks since the last macro
+    /// block, i.e. within the current batch. Older blocks fail with an error that contains the
+    /// earliest available block number.
     async fn get_account_by_address(
         &mut self,
         address: Address,
+        at_block: Option<u32>,
     ) -> RPCResult<Account, BlockchainState, Self::Error> {
+        if let BlockchainProxy::Full(blockchain) = &self.blockchain {
+            let blockchain = blockchain.read();
+            let key = Blockchain::get_account_key(&address);
+
+            with_accounts_at(&blockchain, at_block, |accounts_tree, db_txn, state| {
+                match accounts_tree.get(db_txn, &key) {
+                    Some(account) => {
+                        Account::try_from_account(address, account, state).map_err(Error::Core)
+                    }
+                    None => Ok(RPCData {
+                        data: Account::empty(address),
+                        metadata: state,
+                    }),
+                }
+            })?
+        } else {
+            // Light blockchains request the account from their peers, which can only prove it at
+            // their head.
+            if at_block.is_some() {
+                return Err(Error::NotSupportedForLightBlockchain);
+            }
+
+            let (block, mut accounts) = self
+                .consensus
+                .request_accounts_by_addresses(vec![address.clone()])
+                .await?;
+            let state = BlockchainState::new(block.block_number(), block.hash());
+
+            match accounts.pop().flatten() {
+                Some(account) => {
+                    Account::try_from_account(address, account, state).map_err(Error::Core)
+                }
+                None => Ok(RPCData {
+                    data: Account::empty(address),
+                    metadata: state,
+                }),
+            }
+        }
+    }
+
+    /// Returns the accounts of the given type, ordered by address. It has an option to specify the
+    /// maximum number of accounts to fetch, it defaults to 500. The number of accounts scanned per
+    /// call is limited, so fewer accounts may be returned even if there are more. The returned
+    /// `continueAt` key can be passed as `start_at` to continue the listing, until it is absent.
+    /// An address can be passed as `start_at` as well, to list the accounts after it.
+    async fn list_accounts_by_type(
+        &mut self,
+        account_type: AccountType,
+        start_at: Option<String>,
+        max: Option<u16>,
+    ) -> RPCResult<AccountsPage, BlockchainState, Self::Error> {
+        let start_key = match start_at {
+            Some(start_at) => start_at.parse::<KeyNibbles>()?,
+            None => KeyNibbles::root(),
+        };
+
         let blockchain_proxy = self.blockchain.read();
         if let BlockchainReadProxy::Full(ref blockchain) = blockchain_proxy {
-            let result = blockchain.get_account(&address);
+            with_accounts_at(blockchain, None, |accounts_tree, db_txn, state| {
+                let (found_accounts, continue_at) = get_accounts_by_type(
+                    accounts_tree,
+                    db_txn,
+                    account_type,
+                    start_key,
+                    max.unwrap_or(500) as usize,
+                );
+
+                let mut accounts = vec![];
+                for (address, account) in found_accounts {
+                    let rpc_account = Account::try_from_account(address, account, state.clone())
+                        .map_err(Error::Core)?;
+                    accounts.push(rpc_account.data);
+                }
 
-            match result {
-                Some(account) => Account::try_from_account(
-                    address,
-                    account,
-                    BlockchainState::new(blockchain.block_number(), blockchain.head_hash()),
-                )
-                .map_err(Error::Core),
-                None => Ok(RPCData::with_blockchain(
-                    Account::empty(address),
-                    &blockchain_proxy,
-                )),
-            }
+                Ok(RPCData {
+                    data: AccountsPage {
+                        accounts,
+                        continue_at: continue_at.map(|key| key.to_string()),
+                    },
+                    metadata: state,
+                })
+            })?
         } else {
             Err(Error::NotSupportedForLightBlockchain)
         }
@@ -513,7 +729,8 @@ impl BlockchainInterface for BlockchainDispatcher {
             let mut active_validators = vec![];
 
             for (address, _) in staking_contract.active_validators {
-                if let Ok(rpc_result) = get_validator_by_address(&blockchain_proxy, &address, None)
+                if let Ok(rpc_result) =
+                    get_validator_by_address(&blockchain_proxy, &address, None, None)
                 {
                     active_validators.push(rpc_result.data);
                 }
@@ -599,32 +816,90 @@ impl BlockchainInterface for BlockchainDispatcher {
 
     /// Tries to fetch a validator information given its address. It has an option to include a map
     /// containing the addresses and stakes of all the stakers that are delegating to the validator.
+    /// It also has an option to fetch the validator as it was right after the given block. This is
+    /// only possible for blocks since the last macro block, i.e. within the current batch. Older
+    /// blocks fail with an error that contains the earliest available block number.
     async fn get_validator_by_address(
         &mut self,
         address: Address,
         include_stakers: Option<bool>,
+        at_block: Option<u32>,
     ) -> RPCResult<Validator, BlockchainState, Self::Error> {
-        get_validator_by_address(&self.blockchain.read(), &address, include_stakers)
+        get_validator_by_address(&self.blockchain.read(), &address, include_stakers, at_block)
     }
 
-    /// Tries to fetch a staker information given its address.
+    /// Tries to fetch a staker information given its address. It has an option to fetch the staker
+    /// as it was right after the given block. This is only possible for blocks since the last macro
+    /// block, i.e. within the current batch. Older blocks fail with an error that contains the
+    /// earliest available block number.
     async fn get_staker_by_address(
         &mut self,
         address: Address,
+        at_block: Option<u32>,
     ) -> RPCResult<Staker, BlockchainState, Self::Error> {
         let blockchain_proxy = self.blockchain.read();
         if let BlockchainReadProxy::Full(ref blockchain) = blockchain_proxy {
-            let accounts_tree = &blockchain.state().accounts.tree;
-            let db_txn = blockchain.read_transaction();
-            let staker = StakingContract::get_staker(accounts_tree, &db_txn, &address);
-
-            match staker {
-                Some(s) => Ok(RPCData::with_blockchain(
-                    Staker::from_staker(&s),
-                    &blockchain_proxy,
-                )),
-                None => Err(Error::StakerNotFound(address)),
-            }
+            with_accounts_at(blockchain, at_block, |accounts_tree, db_txn, state| {
+                match StakingContract::get_staker(accounts_tree, db_txn, &address) {
+                    Some(s) => Ok(RPCData {
+                        data: Staker::from_staker(&s),
+                        metadata: state,
+                    }),
+                    None => Err(Error::StakerNotFound(address)),
+                }
+            })?
+        } else {
+            Err(Error::NotSupportedForLightBlockchain)
+        }
+    }
+
+    /// Returns the stakers that are delegating to the given validator, ordered by address. It has
+    /// an option to specify the maximum number of stakers to fetch, it defaults to 500. If
+    /// `start_at` is given, only the stakers with an address after it are returned, which allows
+    /// to continue from the last staker of a previous query.
+    async fn list_stakers_by_validator(
+        &mut self,
+        validator_address: Address,
+        start_at: Option<Address>,
+        max: Option<u16>,
+    ) -> RPCResult<Vec<Staker>, BlockchainState, Self::Error> {
+        let blockchain_proxy = self.blockchain.read();
+        if let BlockchainReadProxy::Full(ref blockchain) = blockchain_proxy {
+            with_accounts_at(blockchain, None, |accounts_tree, db_txn, state| {
+                if StakingContract::get_validator(accounts_tree, db_txn, &validator_address)
+                    .is_none()
+                {
+                    return Err(Error::ValidatorNotFound(validator_address));
+                }
+
+                let max = max.unwrap_or(500) as usize;
+
+                // The start address is inclusive, so we fetch one more staker in case we need to
+                // skip it.
+                let staker_addresses = StakingContract::get_validator_stakers_from(
+                    accounts_tree,
+                    db_txn,
+                    &validator_address,
+                    start_at.as_ref().unwrap_or(&Address::from([0; 20])),
+                    max + 1,
+                );
+
+                let stakers = staker_addresses
+                    .iter()
+                    .filter(|address| Some(*address) != start_at.as_ref())
+                    .take(max)
+                    .map(|address| {
+                        let staker =
+                            StakingContract::get_staker(accounts_tree, db_txn, address).unwrap();
+                        Staker::from_staker(&staker)
+                    })
+                    .collect();
+
+                Ok(RPCData {
+                    data: stakers,
+                    metadata: state,
+                })
+            })?
         } else {
             Err(Error::NotSupportedForLightBlockchain)
         }
@@ -691,7 +966,7 @@ impl BlockchainInterface for BlockchainDispatcher {
                 let result = match event {
                     BlockchainEvent::EpochFinalized(..) => {
                         let blockchain_rg = blockchain.read();
-                        get_validator_by_address(&blockchain_rg, &address, Some(false))
+                        get_validator_by_address(&blockchain_rg, &address, Some(false), None)
                             .map_or_else(|_| None, Some)
                     }
                     _ => None,
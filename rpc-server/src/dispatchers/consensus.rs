@@ -137,6 +137,28 @@ impl ConsensusInterface for ConsensusDispatcher {
         self.send_raw_transaction(raw_tx).await
     }
 
+    /// Returns a serialized, unsigned basic transaction from a multisig account. The signers of
+    /// the multisig account have to sign it before it can be sent.
+    async fn create_multisig_basic_transaction(
+        &mut self,
+        sender: Address,
+        recipient: Address,
+        value: Coin,
+        fee: Coin,
+        validity_start_height: ValidityStartHeight,
+    ) -> RPCResult<String, (), Self::Error> {
+        let proof_builder = TransactionBuilder::new_basic_multisig(
+            sender,
+            recipient,
+            value,
+            fee,
+            self.validity_start_height(validity_start_height),
+            self.get_network_id(),
+        )?;
+
+        Ok(transaction_to_hex_string(&proof_builder.transaction).into())
+    }
+
     /// Returns a serialized basic transaction with an arbitrary data field.
     async fn create_basic_transaction_with_data(
         &mut self,
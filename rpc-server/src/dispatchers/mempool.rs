@@ -2,14 +2,21 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use beserial::Deserialize;
+use futures::{future, stream::BoxStream, StreamExt};
 
 use nimiq_hash::{Blake2bHash, Hash};
+use nimiq_keys::Address;
 use nimiq_mempool::mempool::Mempool;
+use nimiq_mempool::mempool_events::{
+    MempoolEvent as MMempoolEvent, RemovalReason as MRemovalReason,
+};
 
 use nimiq_mempool::mempool_transactions::TxPriority;
 use nimiq_rpc_interface::mempool::MempoolInterface;
-use nimiq_rpc_interface::types::RPCResult;
-use nimiq_rpc_interface::types::{HashOrTx, MempoolInfo};
+use nimiq_rpc_interface::types::{
+    HashOrTx, MempoolEvent, MempoolEventType, MempoolInfo, RPCData, RPCResult, RemovalReason,
+    Transaction,
+};
 
 use crate::error::Error;
 
@@ -92,4 +99,59 @@ impl MempoolInterface for MempoolDispatcher {
     async fn get_min_fee_per_byte(&mut self) -> RPCResult<f64, (), Self::Error> {
         Ok(self.mempool.get_rules().tx_fee_per_byte.into())
     }
+
+    async fn estimate_fee_per_byte(
+        &mut self,
+        target_blocks: u32,
+    ) -> RPCResult<f64, (), Self::Error> {
+        Ok(self.mempool.estimate_fee_per_byte(target_blocks).into())
+    }
+
+    /// Subscribes to transactions entering or leaving the local mempool that are sent by or to
+    /// any of the given addresses and of any of the given event types. If addresses or event
+    /// types are empty, it does not filter by addresses or event types respectively. If the
+    /// subscriber falls behind, a `lagged` event reports the number of missed events.
+    #[stream]
+    async fn subscribe_for_mempool_transactions(
+        &mut self,
+        addresses: Vec<Address>,
+        event_types: Vec<MempoolEventType>,
+    ) -> Result<BoxStream<'static, RPCData<MempoolEvent, ()>>, Self::Error> {
+        let stream = self.mempool.notifier_as_stream();
+
+        Ok(stream
+            .filter_map(move |event| {
+                let event = match event {
+                    MMempoolEvent::Added(tx) => MempoolEvent::Added {
+                        transaction: Transaction::from_transaction(tx),
+                    },
+                    MMempoolEvent::Removed(tx, MRemovalReason::Included) => MempoolEvent::Removed {
+                        reason: RemovalReason::Included,
+                        transaction: Transaction::from_transaction(tx),
+                    },
+                    MMempoolEvent::Removed(tx, MRemovalReason::BlockBuilding) => {
+                        MempoolEvent::Removed {
+                            reason: RemovalReason::BlockBuilding,
+                            transaction: Transaction::from_transaction(tx),
+                        }
+                    }
+                    MMempoolEvent::Removed(tx, MRemovalReason::Expired) => MempoolEvent::Removed {
+                        reason: RemovalReason::Expired,
+                        transaction: Transaction::from_transaction(tx),
+                    },
+                    MMempoolEvent::Removed(tx, MRemovalReason::Evicted) => MempoolEvent::Evicted {
+                        transaction: Transaction::from_transaction(tx),
+                    },
+                    MMempoolEvent::Lagged(missed_events) => MempoolEvent::Lagged { missed_events },
+                };
+
+                let result = if event.matches(&addresses, &event_types) {
+                    Some(event.into())
+                } else {
+                    None
+                };
+                future::ready(result)
+            })
+            .boxed())
+    }
 }
@@ -1,11 +1,13 @@
+use std::net::IpAddr;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
 
 use nimiq_network_interface::network::Network as InterfaceNetwork;
-use nimiq_network_libp2p::Network;
+use nimiq_network_libp2p::{ConnectionDirection, IpNetwork, Network, PeerId};
 use nimiq_rpc_interface::network::NetworkInterface;
-use nimiq_rpc_interface::types::RPCResult;
+use nimiq_rpc_interface::types::{BannedIp, PeerInfo, RPCResult};
 
 use crate::error::Error;
 
@@ -19,6 +21,40 @@ impl NetworkDispatcher {
     }
 }
 
+/// Parses an IP address, e.g. `192.168.1.1`, or a subnet, e.g. `192.168.1.0/24`.
+fn parse_ip_network(ip: &str) -> Result<IpNetwork, Error> {
+    if ip.contains('/') {
+        ip.parse::<IpNetwork>()
+            .map_err(|_| Error::InvalidIp(ip.to_string()))
+    } else {
+        ip.parse::<IpAddr>()
+            .map(IpNetwork::from)
+            .map_err(|_| Error::InvalidIp(ip.to_string()))
+    }
+}
+
+/// Converts the peer information of the network into its RPC representation.
+fn peer_info(peer_info: nimiq_network_libp2p::PeerInfo) -> PeerInfo {
+    PeerInfo {
+        peer_id: peer_info.peer_id.to_string(),
+        address: peer_info.address.to_string(),
+        direction: match peer_info.direction {
+            ConnectionDirection::Inbound => "inbound".to_string(),
+            ConnectionDirection::Outbound => "outbound".to_string(),
+        },
+        services: peer_info.services.map(|services| services.bits()),
+        user_agent: peer_info.user_agent,
+        protocol_version: peer_info.protocol_version,
+        score: peer_info.score,
+        connection_age: SystemTime::now()
+            .duration_since(peer_info.connected_since)
+            .unwrap_or_default()
+            .as_secs(),
+        requests_sent: peer_info.requests_sent,
+        requests_received: peer_info.requests_received,
+    }
+}
+
 #[nimiq_jsonrpc_derive::service(rename_all = "camelCase")]
 #[async_trait]
 impl NetworkInterface for NetworkDispatcher {
@@ -44,4 +80,92 @@ impl NetworkInterface for NetworkDispatcher {
             .collect::<Vec<_>>()
             .into())
     }
+
+    /// Returns detailed information about a connected peer.
+    async fn get_peer_info(&mut self, peer_id: String) -> RPCResult<PeerInfo, (), Self::Error> {
+        let parsed_peer_id = peer_id
+            .parse::<PeerId>()
+            .map_err(|_| Error::InvalidPeerId(peer_id.clone()))?;
+
+        let info = self
+            .network
+            .peer_info(parsed_peer_id)
+            .await?
+            .ok_or(Error::PeerNotConnected(peer_id))?;
+
+        Ok(peer_info(info).into())
+    }
+
+    /// Returns detailed information about all connected peers.
+    async fn get_peer_info_list(&mut self) -> RPCResult<Vec<PeerInfo>, (), Self::Error> {
+        Ok(self
+            .network
+            .peer_infos()
+            .await?
+            .into_iter()
+            .map(peer_info)
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    /// Bans the IP address of a connected peer for the given duration in seconds, or for a default
+    /// duration if none is given. Returns the banned IP address.
+    async fn ban_peer(
+        &mut self,
+        peer_id: String,
+        duration: Option<u64>,
+    ) -> RPCResult<String, (), Self::Error> {
+        let parsed_peer_id = peer_id
+            .parse::<PeerId>()
+            .map_err(|_| Error::InvalidPeerId(peer_id.clone()))?;
+
+        let ip = self
+            .network
+            .ban_peer(parsed_peer_id, duration.map(Duration::from_secs))
+            .await?
+            .ok_or(Error::PeerIpUnknown(peer_id))?;
+
+        Ok(ip.to_string().into())
+    }
+
+    /// Bans an IP address or subnet for the given duration in seconds, or for a default duration if
+    /// none is given. Connected peers within the banned range are disconnected.
+    async fn ban_ip(
+        &mut self,
+        ip: String,
+        duration: Option<u64>,
+    ) -> RPCResult<(), (), Self::Error> {
+        let ip = parse_ip_network(&ip)?;
+
+        self.network
+            .ban_ip(ip, duration.map(Duration::from_secs))
+            .await?;
+
+        Ok(().into())
+    }
+
+    /// Lifts the ban of an IP address or subnet. Returns whether it was banned.
+    async fn unban_ip(&mut self, ip: String) -> RPCResult<bool, (), Self::Error> {
+        let ip = parse_ip_network(&ip)?;
+
+        Ok(self.network.unban_ip(ip).await?.into())
+    }
+
+    /// Returns the banned IP addresses and subnets.
+    async fn list_banned(&mut self) -> RPCResult<Vec<BannedIp>, (), Self::Error> {
+        Ok(self
+            .network
+            .banned_ips()
+            .await?
+            .into_iter()
+            .map(|(ip, until)| BannedIp {
+                ip: ip.to_string(),
+                banned_until: until
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as u64,
+            })
+            .collect::<Vec<_>>()
+            .into())
+    }
 }
@@ -1,7 +1,7 @@
 use std::sync::atomic::Ordering;
 
 use async_trait::async_trait;
-use beserial::Serialize;
+use beserial::{Deserialize, Serialize};
 
 use nimiq_keys::Address;
 use nimiq_rpc_interface::types::RPCResult;
@@ -59,4 +59,26 @@ impl ValidatorInterface for ValidatorDispatcher {
         log::debug!("Automatic reactivation set to {}.", automatic_reactivate);
         Ok(().into())
     }
+
+    /// Returns the latest messages signed by our validator, hex-encoded. These can be imported
+    /// into another node before moving the validator there.
+    async fn export_slashing_protection(&mut self) -> RPCResult<String, (), Self::Error> {
+        Ok(hex::encode(
+            self.validator
+                .slashing_protection
+                .export()
+                .serialize_to_vec(),
+        )
+        .into())
+    }
+
+    /// Imports the latest messages signed by our validator on another node. Our validator won't
+    /// sign any message that conflicts with them.
+    async fn import_slashing_protection(&mut self, data: String) -> RPCResult<(), (), Self::Error> {
+        let state = Deserialize::deserialize_from_vec(&hex::decode(data)?)?;
+        self.validator.slashing_protection.import(state);
+
+        log::info!("Imported slashing protection state.");
+        Ok(().into())
+    }
 }
@@ -1,14 +1,24 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use parking_lot::RwLock;
 
-use beserial::Deserialize;
+use beserial::{Deserialize, Serialize};
+use nimiq_keys::multisig::{Commitment, PartialSignature};
 use nimiq_keys::{Address, KeyPair, PrivateKey, PublicKey, Signature};
+use nimiq_mnemonic::Mnemonic;
 use nimiq_rpc_interface::types::RPCResult;
-use nimiq_rpc_interface::wallet::{ReturnAccount, ReturnSignature, WalletInterface};
-use nimiq_utils::otp::Locked;
-use nimiq_wallet::{WalletAccount, WalletStore};
+use nimiq_rpc_interface::wallet::{
+    ReturnAccount, ReturnHdWallet, ReturnHdWalletAccount, ReturnSignature, WalletInterface,
+};
+use nimiq_transaction::Transaction;
+use nimiq_transaction_builder::proof::BasicProofBuilder;
+use nimiq_utils::otp::{Locked, Unlocked};
+use nimiq_wallet::{
+    HdWallet, HdWalletAccount, HdWalletError, Keystore, LockedHdWallet, MultiSigAccount,
+    PendingCommitments, WalletAccount, WalletStore,
+};
 
 use crate::{error::Error, wallets::UnlockedWallets};
 
@@ -20,9 +30,19 @@ fn message_from_maybe_hex(s: String, is_hex: bool) -> Result<Vec<u8>, Error> {
     }
 }
 
+fn hd_wallet_account_to_return(account: HdWalletAccount) -> ReturnHdWalletAccount {
+    ReturnHdWalletAccount {
+        path: account.path,
+        address: account.address,
+    }
+}
+
 pub struct WalletDispatcher {
     wallet_store: Arc<WalletStore>,
     pub unlocked_wallets: Arc<RwLock<UnlockedWallets>>,
+    /// The random secrets of the multisig commitments created by this node. They never leave the
+    /// node and are removed once they have been used for a partial signature.
+    pending_commitments: PendingCommitments,
 }
 
 impl WalletDispatcher {
@@ -30,7 +50,23 @@ impl WalletDispatcher {
         Self {
             wallet_store,
             unlocked_wallets: Arc::new(RwLock::new(UnlockedWallets::default())),
+            pending_commitments: PendingCommitments::default(),
+        }
+    }
+
+    /// Stores a new HD wallet, unless a wallet with the same id already exists.
+    fn store_hd_wallet(&self, wallet: HdWallet, passphrase: &str) -> Result<Address, Error> {
+        let id = wallet.id.clone();
+        if self.wallet_store.get_hd_wallet(&id, None).is_none() {
+            let locked_wallet =
+                LockedHdWallet::new(Locked::with_defaults(wallet, passphrase.as_bytes())?);
+
+            let mut txn = self.wallet_store.create_write_transaction();
+            self.wallet_store
+                .put_hd_wallet(&id, &locked_wallet, &mut txn);
+            txn.commit();
         }
+        Ok(id)
     }
 }
 
@@ -177,4 +213,251 @@ impl WalletInterface for WalletDispatcher {
         let message = message_from_maybe_hex(message, is_hex)?;
         Ok(WalletAccount::verify_message(&public_key, &message, &signature).into())
     }
+
+    async fn create_multisig_address(
+        &mut self,
+        public_keys: Vec<PublicKey>,
+        min_signatures: usize,
+    ) -> RPCResult<Address, (), Self::Error> {
+        let account = MultiSigAccount::new(min_signatures, &public_keys)?;
+        Ok(account.address.into())
+    }
+
+    /// Creates a commitment for signing a multisig transaction. The corresponding random secret
+    /// is kept by the node and can be used for a single partial signature.
+    async fn create_multisig_commitment(&mut self) -> RPCResult<Commitment, (), Self::Error> {
+        Ok(self.pending_commitments.create_commitment().into())
+    }
+
+    async fn create_multisig_partial_signature(
+        &mut self,
+        raw_tx: String,
+        address: Address,
+        public_keys: Vec<PublicKey>,
+        signers: Vec<PublicKey>,
+        commitment: Commitment,
+        commitments: Vec<Commitment>,
+    ) -> RPCResult<PartialSignature, (), Self::Error> {
+        let transaction: Transaction = Deserialize::deserialize_from_vec(&hex::decode(raw_tx)?)?;
+        let account = MultiSigAccount::new(signers.len(), &public_keys)?;
+
+        let unlocked_wallets = self.unlocked_wallets.read();
+        let wallet = unlocked_wallets
+            .get(&address)
+            .ok_or_else(|| Error::UnlockedWalletNotFound(address.clone()))?;
+
+        let partial_signature = account.partially_sign_transaction(
+            &transaction,
+            &wallet.key_pair,
+            &signers,
+            &commitment,
+            &commitments,
+            &mut self.pending_commitments,
+        )?;
+
+        Ok(partial_signature.into())
+    }
+
+    async fn aggregate_multisig_signatures(
+        &mut self,
+        raw_tx: String,
+        public_keys: Vec<PublicKey>,
+        signers: Vec<PublicKey>,
+        commitments: Vec<Commitment>,
+        partial_signatures: Vec<PartialSignature>,
+    ) -> RPCResult<String, (), Self::Error> {
+        let transaction: Transaction = Deserialize::deserialize_from_vec(&hex::decode(raw_tx)?)?;
+        let account = MultiSigAccount::new(signers.len(), &public_keys)?;
+
+        let proof = account.create_signature_proof(
+            &transaction,
+            &signers,
+            &commitments,
+            &partial_signatures,
+        )?;
+
+        let mut proof_builder = BasicProofBuilder::new(transaction);
+        proof_builder.with_signature_proof(proof);
+        let transaction = proof_builder.generate().unwrap();
+
+        Ok(hex::encode(transaction.serialize_to_vec()).into())
+    }
+
+    async fn create_hd_wallet(
+        &mut self,
+        passphrase: Option<String>,
+    ) -> RPCResult<ReturnHdWallet, (), Self::Error> {
+        let passphrase = passphrase.unwrap_or_default();
+        let (wallet, mnemonic) = HdWallet::generate();
+        let id = self.store_hd_wallet(wallet, &passphrase)?;
+
+        Ok(ReturnHdWallet {
+            id,
+            mnemonic: mnemonic.to_string(),
+        }
+        .into())
+    }
+
+    async fn import_hd_wallet(
+        &mut self,
+        mnemonic: String,
+        passphrase: Option<String>,
+    ) -> RPCResult<Address, (), Self::Error> {
+        let passphrase = passphrase.unwrap_or_default();
+        let mnemonic: Mnemonic = mnemonic
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .parse()
+            .map_err(|_| HdWalletError::InvalidMnemonic)?;
+        let wallet = HdWallet::from_mnemonic(&mnemonic, None)?;
+
+        Ok(self.store_hd_wallet(wallet, &passphrase)?.into())
+    }
+
+    async fn list_hd_wallets(&mut self) -> RPCResult<Vec<Address>, (), Self::Error> {
+        Ok(self.wallet_store.list_hd_wallets(None).into())
+    }
+
+    async fn list_hd_wallet_accounts(
+        &mut self,
+        wallet_id: Address,
+    ) -> RPCResult<Vec<ReturnHdWalletAccount>, (), Self::Error> {
+        let wallet = self
+            .wallet_store
+            .get_hd_wallet(&wallet_id, None)
+            .ok_or(Error::HdWalletNotFound(wallet_id))?;
+
+        Ok(wallet
+            .accounts
+            .into_iter()
+            .map(hd_wallet_account_to_return)
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    /// Derives the account at the given path from an HD wallet and imports it, encrypted with the
+    /// passphrase of the HD wallet. The account remains locked after this operation.
+    async fn derive_hd_account(
+        &mut self,
+        wallet_id: Address,
+        path: String,
+        passphrase: Option<String>,
+    ) -> RPCResult<ReturnHdWalletAccount, (), Self::Error> {
+        let passphrase = passphrase.unwrap_or_default();
+        let LockedHdWallet { wallet, accounts } = self
+            .wallet_store
+            .get_hd_wallet(&wallet_id, None)
+            .ok_or_else(|| Error::HdWalletNotFound(wallet_id.clone()))?;
+
+        let wallet = wallet
+            .unlock(passphrase.as_bytes())
+            .map_err(|_locked| Error::WrongPassphrase)?;
+        let account = wallet.derive_account(&path)?;
+        let hd_account = HdWalletAccount {
+            path,
+            address: account.address.clone(),
+        };
+
+        let mut locked_wallet = LockedHdWallet {
+            wallet: Unlocked::lock(wallet),
+            accounts,
+        };
+        locked_wallet.add_account(hd_account.clone());
+        let locked_account = Locked::with_defaults(account, passphrase.as_bytes())?;
+
+        let mut txn = self.wallet_store.create_write_transaction();
+        self.wallet_store
+            .put(&hd_account.address, &locked_account, &mut txn);
+        self.wallet_store
+            .put_hd_wallet(&wallet_id, &locked_wallet, &mut txn);
+        txn.commit();
+
+        Ok(hd_wallet_account_to_return(hd_account).into())
+    }
+
+    /// Derives the account with the given index from an HD wallet, see `derive_hd_account`.
+    async fn derive_hd_account_by_index(
+        &mut self,
+        wallet_id: Address,
+        index: u32,
+        passphrase: Option<String>,
+    ) -> RPCResult<ReturnHdWalletAccount, (), Self::Error> {
+        self.derive_hd_account(wallet_id, HdWallet::derivation_path(index), passphrase)
+            .await
+    }
+
+    /// Exports the given accounts, or all accounts if none are given, to a keystore that is
+    /// encrypted with `keystore_passphrase`. The keystore is returned as hex string.
+    async fn export_accounts(
+        &mut self,
+        addresses: Option<Vec<Address>>,
+        passphrase: Option<String>,
+        keystore_passphrase: String,
+    ) -> RPCResult<String, (), Self::Error> {
+        let passphrase = passphrase.unwrap_or_default();
+        let addresses = addresses.unwrap_or_else(|| self.wallet_store.list(None));
+
+        let mut keystore = Keystore::default();
+        for address in addresses {
+            let account = self
+                .wallet_store
+                .get(&address, None)
+                .ok_or_else(|| Error::AccountNotFound(address.clone()))?
+                .unlock(passphrase.as_bytes())
+                .map_err(|_locked| Error::WrongPassphrase)?;
+
+            keystore.add_account(
+                Unlocked::into_unlocked_data(account),
+                keystore_passphrase.as_bytes(),
+            )?;
+        }
+
+        Ok(keystore.to_hex().into())
+    }
+
+    /// Imports all accounts of a keystore. The accounts are encrypted with `passphrase` and remain
+    /// locked after this operation. Accounts that are already imported are skipped instead of
+    /// overwritten. Returns the addresses of the imported accounts.
+    async fn import_accounts(
+        &mut self,
+        keystore: String,
+        keystore_passphrase: String,
+        passphrase: Option<String>,
+    ) -> RPCResult<Vec<Address>, (), Self::Error> {
+        let passphrase = passphrase.unwrap_or_default();
+        let accounts = Keystore::from_hex(&keystore)?.unlock(keystore_passphrase.as_bytes())?;
+
+        // Encrypting the accounts is slow, so it is done before opening the write transaction.
+        let mut locked_accounts = Vec::with_capacity(accounts.len());
+        let mut seen_addresses = HashSet::new();
+        let read_txn = self.wallet_store.create_read_transaction();
+        for account in accounts {
+            let address = account.address.clone();
+            if !seen_addresses.insert(address.clone())
+                || self.wallet_store.get(&address, Some(&read_txn)).is_some()
+            {
+                continue;
+            }
+
+            let locked_account = Locked::with_defaults(account, passphrase.as_bytes())?;
+            locked_accounts.push((address, locked_account));
+        }
+        read_txn.close();
+
+        let mut addresses = Vec::with_capacity(locked_accounts.len());
+        let mut txn = self.wallet_store.create_write_transaction();
+        for (address, locked_account) in locked_accounts {
+            // The account might have been imported in the meantime.
+            if self.wallet_store.get(&address, Some(&txn)).is_some() {
+                continue;
+            }
+
+            self.wallet_store.put(&address, &locked_account, &mut txn);
+            addresses.push(address);
+        }
+        txn.commit();
+
+        Ok(addresses.into())
+    }
 }
@@ -16,6 +16,9 @@ pub enum Error {
     #[error("Mempool rejected transaction: {0}")]
     MempoolError(VerifyErr),
 
+    #[error("{0}")]
+    Blockchain(#[from] nimiq_blockchain_interface::BlockchainError),
+
     #[error("Block not found: {0}")]
     BlockNotFound(u32),
 
@@ -37,12 +40,27 @@ pub enum Error {
     #[error("Unexpected macro block: {0}")]
     UnexpectedMacroBlockByHash(Blake2bHash),
 
+    #[error("Invalid peer ID: {0}")]
+    InvalidPeerId(String),
+
+    #[error("Invalid IP address or subnet: {0}")]
+    InvalidIp(String),
+
+    #[error("Peer is not connected: {0}")]
+    PeerNotConnected(String),
+
+    #[error("IP address of peer is unknown: {0}")]
+    PeerIpUnknown(String),
+
     #[error("Method not implemented")]
     NotImplemented,
 
     #[error("Method not supported for a light blockchain")]
     NotSupportedForLightBlockchain,
 
+    #[error("Proof request failed: {0}")]
+    Proof(#[from] nimiq_consensus::ProofError),
+
     #[error("Invalid combination of transaction parameters")]
     InvalidTransactionParameters,
 
@@ -64,6 +82,18 @@ pub enum Error {
     #[error("No unlocked wallet with address: {0}")]
     UnlockedWalletNotFound(Address),
 
+    #[error("{0}")]
+    MultiSig(#[from] nimiq_wallet::MultiSigError),
+
+    #[error("{0}")]
+    HdWallet(#[from] nimiq_wallet::HdWalletError),
+
+    #[error("No HD wallet with id: {0}")]
+    HdWalletNotFound(Address),
+
+    #[error("{0}")]
+    Keystore(#[from] nimiq_wallet::KeystoreError),
+
     #[error("Invalid hex: {0}")]
     HexError(#[from] hex::FromHexError),
 
@@ -162,7 +162,7 @@ async fn main_inner() -> Result<(), Error> {
     initialize_panic_reporting();
 
     // Initialize signal handler
-    initialize_signal_handler();
+    let shutdown_handle = initialize_signal_handler();
 
     // Create config builder and apply command line and config file.
     // You usually want the command line to override config settings, so the order is important.
@@ -177,6 +177,7 @@ async fn main_inner() -> Result<(), Error> {
     // Clone config for RPC and metrics server
     let rpc_config = config.rpc_server.clone();
     let metrics_config = config.metrics_server.clone();
+    let min_peers = config.consensus.min_peers;
 
     // Get the private key used to sign the transactions (the associated address must have funds).
     let validator_settings = &config_file
@@ -199,6 +200,7 @@ async fn main_inner() -> Result<(), Error> {
     log::info!("Initializing client");
     let mut client: Client = Client::from_config(config).await?;
     log::info!("Client initialized");
+    shutdown_handle.set_network(client.network());
 
     // Initialize RPC server
     if let Some(rpc_config) = rpc_config {
@@ -229,6 +231,8 @@ async fn main_inner() -> Result<(), Error> {
             client.mempool(),
             client.consensus_proxy(),
             client.network(),
+            None,
+            min_peers,
             &[],
         )
     }
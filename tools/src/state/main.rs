@@ -0,0 +1,555 @@
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::process::exit;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::Error;
+use clap::{
+    crate_authors, crate_description, crate_version, value_parser, Arg, ArgMatches, Command,
+};
+use thiserror::Error;
+
+use beserial::{Deserialize, Serialize};
+use nimiq_account::{Account, Accounts};
+use nimiq_block::MacroBlock;
+use nimiq_blockchain::{Blockchain, BlockchainConfig};
+use nimiq_database::mdbx::MdbxEnvironment;
+use nimiq_database::{Environment, WriteTransaction};
+use nimiq_hash::{Blake2bHash, Blake2bHasher, Hash, Hasher};
+use nimiq_primitives::networks::NetworkId;
+use nimiq_trie::key_nibbles::KeyNibbles;
+use nimiq_trie::trie_node::TrieNode;
+use nimiq_trie::trie_proof::TrieProof;
+use nimiq_utils::time::OffsetTime;
+
+/// Identifies a state snapshot file.
+const MAGIC: [u8; 8] = *b"NIMSTATE";
+/// The version of the state snapshot file format.
+const VERSION: u16 = 1;
+
+/// Marks that another chunk of accounts follows.
+const CHUNK_TAG: u8 = 1;
+/// Marks the end of the chunks.
+const END_TAG: u8 = 0;
+
+/// The maximum number of accounts per chunk. The proof of a chunk can contain at most
+/// `u16::MAX` nodes.
+const MAX_CHUNK_SIZE: usize = 10_000;
+
+// The database defaults of the client.
+const DEFAULT_DB_SIZE: usize = 1024 * 1024 * 1024 * 1024;
+const DEFAULT_MAX_DBS: u32 = 16;
+
+/// Writes all data to the inner writer and keeps a checksum of it.
+struct ChecksumWriter<W: Write> {
+    inner: W,
+    hasher: Blake2bHasher,
+}
+
+impl<W: Write> Write for ChecksumWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.write_all(&buf[..n])?;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reads data from the inner reader and keeps a checksum of it.
+struct ChecksumReader<R: Read> {
+    inner: R,
+    hasher: Blake2bHasher,
+}
+
+impl<R: Read> Read for ChecksumReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.write_all(&buf[..n])?;
+        Ok(n)
+    }
+}
+
+fn open_environment(matches: &ArgMatches) -> Result<Environment, Error> {
+    let path = matches.get_one::<String>("db").unwrap();
+    let size = *matches.get_one::<usize>("db_size").unwrap();
+    let max_dbs = *matches.get_one::<u32>("max_dbs").unwrap();
+
+    Ok(MdbxEnvironment::new(path, size, max_dbs)?)
+}
+
+fn network_id(matches: &ArgMatches) -> Result<NetworkId, Error> {
+    Ok(NetworkId::from_str(
+        matches.get_one::<String>("network").unwrap(),
+    )?)
+}
+
+/// Summarizes an exported or imported state snapshot.
+struct StateSummary {
+    block_number: u32,
+    block_hash: Blake2bHash,
+    state_root: Blake2bHash,
+    num_accounts: u64,
+    checksum: Blake2bHash,
+}
+
+impl StateSummary {
+    fn print(&self) {
+        println!("Block:     #{} {}", self.block_number, self.block_hash);
+        println!("State:     {}", self.state_root);
+        println!("Accounts:  {}", self.num_accounts);
+        println!("Checksum:  {}", self.checksum);
+    }
+}
+
+/// Returns the root hash of a trie without any accounts.
+fn empty_state_root() -> Blake2bHash {
+    TrieNode::<Account>::new_branch(KeyNibbles::root()).hash()
+}
+
+/// Writes the accounts at the current election block to the output file. Unless the head of the
+/// chain is the election block, this requires the node to keep an accounts snapshot.
+fn export(matches: &ArgMatches) -> Result<(), Error> {
+    let db_path = matches.get_one::<String>("db").unwrap();
+    if !Path::new(db_path).exists() {
+        return Err(AppError::DatabaseNotFound(db_path.clone()).into());
+    }
+
+    let network_id = network_id(matches)?;
+    let chunk_size = *matches.get_one::<usize>("chunk_size").unwrap();
+    if !(2..=MAX_CHUNK_SIZE).contains(&chunk_size) {
+        return Err(AppError::ChunkSize.into());
+    }
+
+    let env = open_environment(matches)?;
+    // The snapshot must be enabled here, otherwise loading the blockchain would discard it.
+    let config = BlockchainConfig {
+        keep_accounts_snapshot: true,
+        ..Default::default()
+    };
+    let blockchain = Blockchain::new(env, config, network_id, Arc::new(OffsetTime::new()))?;
+
+    let output = matches.get_one::<String>("output").unwrap();
+    let file = File::create(output)?;
+    let summary = match export_state(&blockchain, BufWriter::new(file), chunk_size) {
+        Ok(summary) => summary,
+        Err(e) => {
+            // Don't leave an incomplete file behind.
+            let _ = fs::remove_file(output);
+            return Err(e);
+        }
+    };
+    summary.print();
+
+    Ok(())
+}
+
+/// Writes the accounts at the current election block of the blockchain to the writer, in chunks
+/// of `chunk_size` accounts.
+fn export_state<W: Write>(
+    blockchain: &Blockchain,
+    writer: W,
+    chunk_size: usize,
+) -> Result<StateSummary, Error> {
+    let state = blockchain.state();
+    let election_block = state.election_head.clone();
+    let state_root = election_block.header.state_root.clone();
+
+    // There are no chunks to prove for a state without accounts.
+    let is_empty = state_root == empty_state_root();
+
+    let use_snapshot = state.head_hash != state.election_head_hash;
+    if use_snapshot
+        && !is_empty
+        && blockchain
+            .get_accounts_snapshot_chunk(&state.election_head_hash, &KeyNibbles::root(), 1, None)
+            .is_none()
+    {
+        return Err(AppError::StateNotAvailable.into());
+    }
+
+    let mut writer = ChecksumWriter {
+        inner: writer,
+        hasher: Blake2bHasher::new(),
+    };
+
+    writer.write_all(&MAGIC)?;
+    VERSION.serialize(&mut writer)?;
+    blockchain.network_id.serialize(&mut writer)?;
+    election_block.serialize(&mut writer)?;
+
+    let txn = blockchain.read_transaction();
+    let mut start = KeyNibbles::root();
+    let mut num_accounts: u64 = 0;
+
+    while !is_empty {
+        let proof = if use_snapshot {
+            blockchain
+                .get_accounts_snapshot_chunk(
+                    &state.election_head_hash,
+                    &start,
+                    chunk_size,
+                    Some(&txn),
+                )
+                .map(|(_, proof)| proof)
+        } else {
+            state
+                .accounts
+                .tree
+                .get_chunk_proof(&txn, &start, chunk_size)
+        }
+        .ok_or(AppError::StateNotAvailable)?;
+
+        let leaves = proof.leaf_nodes();
+        let is_last_chunk = leaves.len() < chunk_size;
+        if !proof.verify_chunk(&state_root, &start, is_last_chunk) {
+            return Err(AppError::InvalidChunk.into());
+        }
+
+        // Consecutive chunks overlap by one account, since the start key is inclusive.
+        num_accounts += leaves.iter().filter(|leaf| leaf.key() != &start).count() as u64;
+
+        CHUNK_TAG.serialize(&mut writer)?;
+        proof.serialize(&mut writer)?;
+
+        match leaves.last() {
+            Some(leaf) if !is_last_chunk => start = leaf.key().clone(),
+            _ => break,
+        }
+    }
+
+    END_TAG.serialize(&mut writer)?;
+    num_accounts.serialize(&mut writer)?;
+
+    let ChecksumWriter { mut inner, hasher } = writer;
+    let checksum = hasher.finish();
+    checksum.serialize(&mut inner)?;
+    inner.flush()?;
+
+    Ok(StateSummary {
+        block_number: election_block.header.block_number,
+        block_hash: election_block.hash(),
+        state_root,
+        num_accounts,
+        checksum,
+    })
+}
+
+/// Reads the accounts from the input file into an empty database and sets up the chain, such that
+/// a node can start from the election block in the file.
+fn import(matches: &ArgMatches) -> Result<(), Error> {
+    let network_id = network_id(matches)?;
+    // The file can't prove that the election block is part of the chain, so it must match the
+    // hash of a block that the user trusts.
+    let block_hash = Blake2bHash::from_str(matches.get_one::<String>("block_hash").unwrap())?;
+
+    let file = File::open(matches.get_one::<String>("input").unwrap())?;
+    let env = open_environment(matches)?;
+
+    let summary = import_state(env, BufReader::new(file), network_id, &block_hash)?;
+    summary.print();
+
+    Ok(())
+}
+
+/// Reads a state snapshot from the reader into the empty environment. The snapshot must be at the
+/// election block with hash `block_hash`.
+fn import_state<R: Read>(
+    env: Environment,
+    reader: R,
+    network_id: NetworkId,
+    block_hash: &Blake2bHash,
+) -> Result<StateSummary, Error> {
+    let mut reader = ChecksumReader {
+        inner: reader,
+        hasher: Blake2bHasher::new(),
+    };
+
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        return Err(AppError::InvalidFile.into());
+    }
+
+    let version = u16::deserialize(&mut reader)?;
+    if version != VERSION {
+        return Err(AppError::UnsupportedVersion(version).into());
+    }
+
+    let file_network_id = NetworkId::deserialize(&mut reader)?;
+    if file_network_id != network_id {
+        return Err(AppError::WrongNetwork(file_network_id).into());
+    }
+
+    let election_block = MacroBlock::deserialize(&mut reader)?;
+    if election_block.hash() != *block_hash {
+        return Err(AppError::UnexpectedBlock(election_block.hash()).into());
+    }
+    let state_root = election_block.header.state_root.clone();
+
+    let accounts = Accounts::new(env.clone());
+    let mut txn = WriteTransaction::new(&env);
+
+    let mut start = KeyNibbles::root();
+    let mut num_accounts: u64 = 0;
+    let mut tag = u8::deserialize(&mut reader)?;
+
+    while tag == CHUNK_TAG {
+        let proof: TrieProof<Account> = Deserialize::deserialize(&mut reader)?;
+
+        // Only the last chunk may end before the last account.
+        tag = u8::deserialize(&mut reader)?;
+        if !proof.verify_chunk(&state_root, &start, tag == END_TAG) {
+            return Err(AppError::InvalidChunk.into());
+        }
+
+        let leaves = proof.leaf_nodes();
+        for leaf in &leaves {
+            if leaf.key() == &start {
+                continue;
+            }
+
+            accounts.tree.put(&mut txn, leaf.key(), leaf.value()?);
+            num_accounts += 1;
+        }
+
+        // Each chunk must start at the last account of the previous one.
+        match leaves.last() {
+            Some(leaf) if leaf.key() != &start => start = leaf.key().clone(),
+            _ if tag == END_TAG => {}
+            _ => return Err(AppError::InvalidChunk.into()),
+        }
+    }
+    if tag != END_TAG {
+        return Err(AppError::InvalidFile.into());
+    }
+
+    let expected_accounts = u64::deserialize(&mut reader)?;
+
+    let ChecksumReader { mut inner, hasher } = reader;
+    let checksum = hasher.finish();
+    if Blake2bHash::deserialize(&mut inner)? != checksum {
+        return Err(AppError::ChecksumMismatch.into());
+    }
+    if expected_accounts != num_accounts {
+        return Err(AppError::InvalidFile.into());
+    }
+
+    accounts.tree.update_root(&mut txn);
+    if accounts.get_root(Some(&txn)) != state_root {
+        return Err(AppError::StateRootMismatch.into());
+    }
+    Blockchain::init_with_election_block(env, &mut txn, network_id, election_block.clone())?;
+    txn.commit();
+
+    Ok(StateSummary {
+        block_number: election_block.header.block_number,
+        block_hash: block_hash.clone(),
+        state_root,
+        num_accounts,
+        checksum,
+    })
+}
+
+fn run_app() -> Result<(), Error> {
+    let db_args = [
+        Arg::new("db")
+            .short('d')
+            .long("db")
+            .value_name("PATH")
+            .required(true)
+            .help("Path to the consensus database."),
+        Arg::new("db_size")
+            .long("db-size")
+            .value_name("BYTES")
+            .value_parser(value_parser!(usize))
+            .default_value(DEFAULT_DB_SIZE.to_string())
+            .help("Size of the mapped memory of the database."),
+        Arg::new("max_dbs")
+            .long("max-dbs")
+            .value_name("NUMBER")
+            .value_parser(value_parser!(u32))
+            .default_value(DEFAULT_MAX_DBS.to_string())
+            .help("Maximum number of databases."),
+        Arg::new("network")
+            .short('N')
+            .long("network")
+            .value_name("NETWORK")
+            .required(true)
+            .help("Set network ID"),
+    ];
+
+    let matches = Command::new("nimiq-state")
+        .version(crate_version!())
+        .author(crate_authors!())
+        .about(crate_description!())
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("export")
+                .about("Exports the accounts state at the current election block to a file.")
+                .args(db_args.clone())
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("FILE")
+                        .required(true)
+                        .help("File to write the state snapshot to."),
+                )
+                .arg(
+                    Arg::new("chunk_size")
+                        .long("chunk-size")
+                        .value_name("ACCOUNTS")
+                        .value_parser(value_parser!(usize))
+                        .default_value("1000")
+                        .help("Number of accounts per chunk."),
+                ),
+        )
+        .subcommand(
+            Command::new("import")
+                .about("Initializes an empty database from a state snapshot file.")
+                .args(db_args)
+                .arg(
+                    Arg::new("input")
+                        .short('i')
+                        .long("input")
+                        .value_name("FILE")
+                        .required(true)
+                        .help("File to read the state snapshot from."),
+                )
+                .arg(
+                    Arg::new("block_hash")
+                        .long("block-hash")
+                        .value_name("HASH")
+                        .required(true)
+                        .help("Hash of the trusted election block the snapshot must be at."),
+                ),
+        )
+        .get_matches();
+
+    match matches.subcommand() {
+        Some(("export", matches)) => export(matches),
+        Some(("import", matches)) => import(matches),
+        _ => unreachable!(),
+    }
+}
+
+fn main() {
+    exit(match run_app() {
+        Ok(_) => 0,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            1
+        }
+    });
+}
+
+#[derive(Debug, Error)]
+enum AppError {
+    #[error("Database not found at {0}")]
+    DatabaseNotFound(String),
+    #[error("Chunk size must be between 2 and {}", MAX_CHUNK_SIZE)]
+    ChunkSize,
+    #[error("Accounts state at the election block is not available. The head must be the election block or the node must keep an accounts snapshot")]
+    StateNotAvailable,
+    #[error("Invalid chunk of accounts")]
+    InvalidChunk,
+    #[error("Not a valid state snapshot file")]
+    InvalidFile,
+    #[error("Unsupported state snapshot version {0}")]
+    UnsupportedVersion(u16),
+    #[error("State snapshot is for network {0}")]
+    WrongNetwork(NetworkId),
+    #[error("State snapshot is at unexpected block {0}")]
+    UnexpectedBlock(Blake2bHash),
+    #[error("Imported accounts don't match the state root of the election block")]
+    StateRootMismatch,
+    #[error("Checksum mismatch")]
+    ChecksumMismatch,
+}
+
+#[cfg(test)]
+mod tests {
+    use parking_lot::RwLock;
+
+    use nimiq_block_production::BlockProducer;
+    use nimiq_database::volatile::VolatileEnvironment;
+    use nimiq_primitives::policy::Policy;
+    use nimiq_test_log::test;
+    use nimiq_test_utils::blockchain::{produce_macro_blocks, signing_key, voting_key};
+
+    use super::*;
+
+    #[test]
+    fn it_can_export_and_import_the_state() {
+        let time = Arc::new(OffsetTime::new());
+        let env = VolatileEnvironment::new(DEFAULT_MAX_DBS).unwrap();
+        let blockchain = Arc::new(RwLock::new(
+            Blockchain::new(
+                env,
+                BlockchainConfig::default(),
+                NetworkId::UnitAlbatross,
+                Arc::clone(&time),
+            )
+            .unwrap(),
+        ));
+
+        // Produce a full epoch, such that the head is an election block.
+        let producer = BlockProducer::new(signing_key(), voting_key());
+        produce_macro_blocks(&producer, &blockchain, Policy::batches_per_epoch() as usize);
+
+        let blockchain = blockchain.read();
+        let block_hash = blockchain.state().election_head_hash.clone();
+        let num_accounts = {
+            let txn = blockchain.read_transaction();
+            blockchain.state().accounts.tree.size(&txn) as u64
+        };
+        // Use a small chunk size to export the accounts in several chunks.
+        assert!(num_accounts > 2);
+
+        let mut file = Vec::new();
+        let exported = export_state(&blockchain, &mut file, 2).unwrap();
+        assert_eq!(exported.block_hash, block_hash);
+        assert_eq!(exported.num_accounts, num_accounts);
+
+        // The file must be at a trusted block.
+        let wrong_env = VolatileEnvironment::new(DEFAULT_MAX_DBS).unwrap();
+        assert!(import_state(
+            wrong_env,
+            &file[..],
+            NetworkId::UnitAlbatross,
+            &Blake2bHash::default(),
+        )
+        .is_err());
+
+        let env = VolatileEnvironment::new(DEFAULT_MAX_DBS).unwrap();
+        let imported = import_state(
+            env.clone(),
+            &file[..],
+            NetworkId::UnitAlbatross,
+            &block_hash,
+        )
+        .unwrap();
+        assert_eq!(imported.state_root, exported.state_root);
+        assert_eq!(imported.num_accounts, num_accounts);
+        assert_eq!(imported.checksum, exported.checksum);
+
+        // A node can start from the imported state.
+        let imported_blockchain = Blockchain::new(
+            env,
+            BlockchainConfig::default(),
+            NetworkId::UnitAlbatross,
+            time,
+        )
+        .unwrap();
+        assert_eq!(imported_blockchain.state().head_hash, block_hash);
+        assert_eq!(
+            imported_blockchain.state().accounts.get_root(None),
+            exported.state_root
+        );
+    }
+}
@@ -8,6 +8,8 @@ use nimiq_primitives::{account::AccountType, coin::Coin, networks::NetworkId};
 use nimiq_transaction::account::htlc_contract::{AnyHash, HashAlgorithm};
 use nimiq_transaction::{SignatureProof, Transaction};
 
+use crate::proof::BasicProofBuilder;
+
 pub use crate::proof::TransactionProofBuilder;
 pub use crate::recipient::Recipient;
 
@@ -513,6 +515,44 @@ impl TransactionBuilder {
         }
     }
 
+    /// Creates an unsigned basic transaction from a multisig account to a basic `recipient`.
+    /// Once the signers of the multisig account aggregated their partial signatures into a
+    /// signature proof, it can be attached using [`BasicProofBuilder::with_signature_proof`].
+    ///
+    /// # Arguments
+    ///
+    ///  - `sender`:                The address of the multisig account that sends the funds.
+    ///  - `recipient`:             The address of the basic account that will receive the funds.
+    ///  - `value`:                 The value that will be sent to the recipient account.
+    ///  - `fee`:                   Transaction fee.
+    ///  - `validity_start_height`: Block height from which this transaction is valid.
+    ///  - `network_id`:            ID of network for which the transaction is meant.
+    ///
+    /// # Returns
+    ///
+    /// The proof builder holding the unsigned transaction.
+    ///
+    /// [`BasicProofBuilder::with_signature_proof`]: proof/struct.BasicProofBuilder.html#method.with_signature_proof
+    pub fn new_basic_multisig(
+        sender: Address,
+        recipient: Address,
+        value: Coin,
+        fee: Coin,
+        validity_start_height: u32,
+        network_id: NetworkId,
+    ) -> Result<BasicProofBuilder, TransactionBuilderError> {
+        let mut builder = Self::new();
+        builder
+            .with_sender(sender)
+            .with_recipient(Recipient::new_basic(recipient))
+            .with_value(value)
+            .with_fee(fee)
+            .with_validity_start_height(validity_start_height)
+            .with_network_id(network_id);
+
+        Ok(builder.generate()?.unwrap_basic())
+    }
+
     /// Creates a basic transaction with an arbitrary data field.
     ///
     /// # Arguments
@@ -347,6 +347,8 @@ impl BasicProofBuilder {
     /// Manually sets the required `signature` proof for the builder.
     /// In most cases, it is not necessary to call this method.
     /// Instead, it is recommended to automatically generate the signature using [`sign_with_key_pair`].
+    /// For multisig accounts, this sets the signature proof aggregated from the signers'
+    /// partial signatures.
     ///
     /// [`sign_with_key_pair`]: struct.BasicProofBuilder.html#method.sign_with_key_pair
     pub fn with_signature_proof(&mut self, signature: SignatureProof) -> &mut Self {
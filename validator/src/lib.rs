@@ -5,5 +5,9 @@ pub mod aggregation;
 mod r#macro;
 mod micro;
 mod slash;
+pub mod slashing_protection;
 mod tendermint;
 pub mod validator;
+/// Validator metrics
+#[cfg(feature = "metrics")]
+pub mod validator_metrics;
@@ -16,7 +16,10 @@ use nimiq_tendermint::{TendermintOutsideDeps, TendermintReturn, TendermintState}
 use nimiq_validator_network::ValidatorNetwork;
 use nimiq_vrf::VrfSeed;
 
+use crate::slashing_protection::SlashingProtection;
 use crate::tendermint::TendermintInterface;
+#[cfg(feature = "metrics")]
+use crate::validator_metrics::ValidatorMetrics;
 
 pub(crate) struct PersistedMacroState<TValidatorNetwork: ValidatorNetwork + 'static>(
     pub  TendermintState<
@@ -69,6 +72,8 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> ProduceMacroBlock<TValidator
         blockchain: Arc<RwLock<Blockchain>>,
         network: Arc<TValidatorNetwork>,
         block_producer: BlockProducer,
+        slashing_protection: Arc<SlashingProtection>,
+        #[cfg(feature = "metrics")] metrics: Arc<ValidatorMetrics>,
         validator_slot_band: u16,
         active_validators: Validators,
         prev_seed: VrfSeed,
@@ -92,6 +97,9 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> ProduceMacroBlock<TValidator
             network,
             blockchain,
             block_producer,
+            slashing_protection,
+            #[cfg(feature = "metrics")]
+            metrics,
             proposal_stream,
             initial_round,
         );
@@ -2,6 +2,8 @@ use std::cmp;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+#[cfg(feature = "metrics")]
+use std::time::Instant;
 use std::time::{Duration, SystemTime};
 
 use futures::{future::BoxFuture, ready, FutureExt, Stream};
@@ -12,12 +14,16 @@ use nimiq_block::{Block, ForkProof, MicroBlock, SkipBlockInfo};
 use nimiq_block_production::BlockProducer;
 use nimiq_blockchain::Blockchain;
 use nimiq_blockchain_interface::{AbstractBlockchain, PushResult};
+use nimiq_hash::Hash;
 use nimiq_mempool::mempool::Mempool;
 use nimiq_utils::time::systemtime_to_timestamp;
 use nimiq_validator_network::ValidatorNetwork;
 use nimiq_vrf::VrfSeed;
 
 use crate::aggregation::skip_block::SkipBlockAggregation;
+use crate::slashing_protection::SlashingProtection;
+#[cfg(feature = "metrics")]
+use crate::validator_metrics::ValidatorMetrics;
 
 // Ignoring this clippy warning since size difference is not that much (320
 // bytes) and we probably don't want the performance penalty of the allocation.
@@ -32,12 +38,15 @@ struct NextProduceMicroBlockEvent<TValidatorNetwork> {
     mempool: Arc<Mempool>,
     network: Arc<TValidatorNetwork>,
     block_producer: BlockProducer,
+    slashing_protection: Arc<SlashingProtection>,
     validator_slot_band: u16,
     fork_proofs: Vec<ForkProof>,
     prev_seed: VrfSeed,
     block_number: u32,
     producer_timeout: Duration,
     block_separation_time: Duration,
+    #[cfg(feature = "metrics")]
+    metrics: Arc<ValidatorMetrics>,
 }
 
 impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<TValidatorNetwork> {
@@ -49,24 +58,29 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<T
         mempool: Arc<Mempool>,
         network: Arc<TValidatorNetwork>,
         block_producer: BlockProducer,
+        slashing_protection: Arc<SlashingProtection>,
         validator_slot_band: u16,
         fork_proofs: Vec<ForkProof>,
         prev_seed: VrfSeed,
         block_number: u32,
         producer_timeout: Duration,
         block_separation_time: Duration,
+        #[cfg(feature = "metrics")] metrics: Arc<ValidatorMetrics>,
     ) -> Self {
         Self {
             blockchain,
             mempool,
             network,
             block_producer,
+            slashing_protection,
             validator_slot_band,
             fork_proofs,
             prev_seed,
             block_number,
             producer_timeout,
             block_separation_time,
+            #[cfg(feature = "metrics")]
+            metrics,
         }
     }
 
@@ -111,6 +125,15 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<T
                         );
 
                         let block = self.produce_micro_block(&blockchain);
+
+                        // Never produce two different micro blocks at the same height.
+                        if !self
+                            .slashing_protection
+                            .sign_micro_block(block.header.block_number, block.hash())
+                        {
+                            break Some(None);
+                        }
+
                         let num_transactions = block
                             .body
                             .as_ref()
@@ -135,8 +158,14 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<T
                             Blockchain::push(blockchain, Block::Micro(block))
                         };
 
-                        if let Err(e) = &result {
-                            error!("Failed to push our own block onto the chain: {:?}", e);
+                        match &result {
+                            Ok(_) => {
+                                #[cfg(feature = "metrics")]
+                                self.metrics.note_micro_block_produced();
+                            }
+                            Err(e) => {
+                                error!("Failed to push our own block onto the chain: {:?}", e)
+                            }
                         }
 
                         let event = result
@@ -198,6 +227,17 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<T
             vrf_entropy: self.prev_seed.entropy(),
         };
 
+        // Never contribute to two different skip blocks at the same height.
+        if !self
+            .slashing_protection
+            .sign_skip_block(self.block_number, skip_block_info.hash())
+        {
+            return (None, self);
+        }
+
+        #[cfg(feature = "metrics")]
+        let aggregation_start = Instant::now();
+
         let (_, skip_block_proof) = SkipBlockAggregation::start(
             skip_block_info.clone(),
             self.block_producer.voting_key.clone(),
@@ -207,6 +247,10 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> NextProduceMicroBlockEvent<T
         )
         .await;
 
+        #[cfg(feature = "metrics")]
+        self.metrics
+            .note_skip_block_aggregation(aggregation_start.elapsed());
+
         let result = {
             // Acquire blockchain.upgradable_read() to prevent further changes to the blockchain while
             // we're constructing the block. Check if we're still in the correct state, abort otherwise.
@@ -335,24 +379,29 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> ProduceMicroBlock<TValidator
         mempool: Arc<Mempool>,
         network: Arc<TValidatorNetwork>,
         block_producer: BlockProducer,
+        slashing_protection: Arc<SlashingProtection>,
         validator_slot_band: u16,
         fork_proofs: Vec<ForkProof>,
         prev_seed: VrfSeed,
         block_number: u32,
         producer_timeout: Duration,
         block_separation_time: Duration,
+        #[cfg(feature = "metrics")] metrics: Arc<ValidatorMetrics>,
     ) -> Self {
         let next_event = NextProduceMicroBlockEvent::new(
             blockchain,
             mempool,
             network,
             block_producer,
+            slashing_protection,
             validator_slot_band,
             fork_proofs,
             prev_seed,
             block_number,
             producer_timeout,
             block_separation_time,
+            #[cfg(feature = "metrics")]
+            metrics,
         )
         .next()
         .boxed();
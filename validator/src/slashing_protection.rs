@@ -0,0 +1,252 @@
+use std::{cmp::Ordering, io};
+
+use parking_lot::Mutex;
+
+use beserial::{Deserialize, Serialize};
+use nimiq_database::{Database, Environment, ReadTransaction, WriteTransaction};
+use nimiq_database_value::{FromDatabaseValue, IntoDatabaseValue};
+use nimiq_hash::{Blake2bHash, Blake2sHash};
+use nimiq_tendermint::Step;
+
+/// A block that was signed by the validator, either by producing a micro block or by contributing
+/// to a skip block.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SignedBlock {
+    pub block_number: u32,
+    pub hash: Blake2bHash,
+}
+
+/// A Tendermint vote that was signed by the validator. A vote for `None` is a vote for nil.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SignedTendermintVote {
+    pub block_number: u32,
+    pub round: u32,
+    pub step: Step,
+    pub proposal_hash: Option<Blake2sHash>,
+}
+
+/// The latest messages that were signed by the validator.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SlashingProtectionState {
+    /// The micro block that was produced last.
+    pub micro_block: Option<SignedBlock>,
+    /// The skip block that was contributed to last.
+    pub skip_block: Option<SignedBlock>,
+    /// The Tendermint votes that were cast for the latest block that we voted on. All of them are
+    /// kept, so that votes of earlier rounds and steps can be rebroadcast after a restart.
+    #[beserial(len_type(u16))]
+    pub tendermint_votes: Vec<SignedTendermintVote>,
+}
+
+impl SlashingProtectionState {
+    /// Merges another state into this one, keeping the latest message of each kind.
+    fn merge(&mut self, other: SlashingProtectionState) {
+        if let Some(block) = other.micro_block {
+            if is_later(&self.micro_block, |last| last.block_number, &block) {
+                self.micro_block = Some(block);
+            }
+        }
+
+        if let Some(block) = other.skip_block {
+            if is_later(&self.skip_block, |last| last.block_number, &block) {
+                self.skip_block = Some(block);
+            }
+        }
+
+        for vote in other.tendermint_votes {
+            self.record_tendermint_vote(vote);
+        }
+    }
+
+    /// Returns the latest Tendermint vote that was cast.
+    fn latest_tendermint_vote(&self) -> Option<&SignedTendermintVote> {
+        self.tendermint_votes
+            .iter()
+            .max_by_key(|vote| vote.position())
+    }
+
+    /// Returns whether the given Tendermint vote may be cast. A vote may be cast if it comes after
+    /// the latest vote, or if it is identical to a vote that was cast for the same block, round
+    /// and step before.
+    fn may_sign_tendermint_vote(&self, vote: &SignedTendermintVote) -> bool {
+        match self
+            .tendermint_votes
+            .iter()
+            .find(|signed| signed.position() == vote.position())
+        {
+            Some(signed) => signed == vote,
+            None => match self.latest_tendermint_vote() {
+                Some(latest) => vote.position() > latest.position(),
+                None => true,
+            },
+        }
+    }
+
+    /// Records the given Tendermint vote. Votes for earlier blocks are ignored, and a vote for a
+    /// later block replaces all votes recorded so far.
+    fn record_tendermint_vote(&mut self, vote: SignedTendermintVote) {
+        if let Some(latest_block_number) = self.latest_tendermint_vote().map(|v| v.block_number) {
+            match vote.block_number.cmp(&latest_block_number) {
+                Ordering::Less => return,
+                Ordering::Greater => self.tendermint_votes.clear(),
+                Ordering::Equal => {}
+            }
+        }
+
+        if !self
+            .tendermint_votes
+            .iter()
+            .any(|signed| signed.position() == vote.position())
+        {
+            self.tendermint_votes.push(vote);
+        }
+    }
+}
+
+impl SignedTendermintVote {
+    fn position(&self) -> (u32, u32, Step) {
+        (self.block_number, self.round, self.step)
+    }
+}
+
+impl IntoDatabaseValue for SlashingProtectionState {
+    fn database_byte_size(&self) -> usize {
+        self.serialized_size()
+    }
+
+    fn copy_into_database(&self, mut bytes: &mut [u8]) {
+        Serialize::serialize(&self, &mut bytes).unwrap();
+    }
+}
+
+impl FromDatabaseValue for SlashingProtectionState {
+    fn copy_from_database(bytes: &[u8]) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut cursor = io::Cursor::new(bytes);
+        Ok(Deserialize::deserialize(&mut cursor)?)
+    }
+}
+
+/// Returns whether the message comes strictly after the last message.
+fn is_later<T, K: Ord>(last: &Option<T>, position: impl Fn(&T) -> K, message: &T) -> bool {
+    match last {
+        Some(last) => position(message) > position(last),
+        None => true,
+    }
+}
+
+/// Returns whether the message may be signed, given the last message of the same kind. A message
+/// may be signed if it comes after the last message, or if it is identical to the last message.
+fn may_sign<T: Eq, K: Ord>(last: &Option<T>, position: impl Fn(&T) -> K, message: &T) -> bool {
+    match last {
+        Some(last) if position(message) == position(last) => message == last,
+        _ => is_later(last, position, message),
+    }
+}
+
+/// A local database that records the latest messages signed by the validator. Before signing a
+/// message, the validator checks that it doesn't conflict with a message that it signed before,
+/// even if the validator was restarted or moved to another machine in the meantime.
+pub struct SlashingProtection {
+    env: Environment,
+    database: Database,
+    state: Mutex<SlashingProtectionState>,
+}
+
+impl SlashingProtection {
+    const DB_NAME: &'static str = "ValidatorSlashingProtection";
+    const STATE_KEY: &'static str = "slashingProtectionState";
+
+    pub fn new(env: Environment) -> Self {
+        let database = env.open_database(Self::DB_NAME.to_string());
+
+        let state = ReadTransaction::new(&env)
+            .get(&database, Self::STATE_KEY)
+            .unwrap_or_default();
+
+        Self {
+            env,
+            database,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Checks whether the micro block with the given number and hash may be produced and records
+    /// it if so.
+    pub fn sign_micro_block(&self, block_number: u32, hash: Blake2bHash) -> bool {
+        let block = SignedBlock { block_number, hash };
+        let mut state = self.state.lock();
+
+        if !may_sign(&state.micro_block, |last| last.block_number, &block) {
+            warn!(
+                block_number,
+                "Refusing to produce a micro block that conflicts with an earlier one"
+            );
+            return false;
+        }
+
+        state.micro_block = Some(block);
+        self.persist(&state);
+        true
+    }
+
+    /// Checks whether we may contribute to the skip block with the given number and hash and
+    /// records it if so.
+    pub fn sign_skip_block(&self, block_number: u32, hash: Blake2bHash) -> bool {
+        let block = SignedBlock { block_number, hash };
+        let mut state = self.state.lock();
+
+        if !may_sign(&state.skip_block, |last| last.block_number, &block) {
+            warn!(
+                block_number,
+                "Refusing to contribute to a skip block that conflicts with an earlier one"
+            );
+            return false;
+        }
+
+        state.skip_block = Some(block);
+        self.persist(&state);
+        true
+    }
+
+    /// Checks whether the given Tendermint vote may be cast and records it if so. Rebroadcasting a
+    /// vote that was cast before is allowed.
+    pub fn sign_tendermint_vote(&self, vote: SignedTendermintVote) -> bool {
+        let mut state = self.state.lock();
+
+        if !state.may_sign_tendermint_vote(&vote) {
+            warn!(
+                block_number = vote.block_number,
+                round = vote.round,
+                step = ?vote.step,
+                "Refusing to cast a Tendermint vote that conflicts with an earlier one"
+            );
+            return false;
+        }
+
+        state.record_tendermint_vote(vote);
+        self.persist(&state);
+        true
+    }
+
+    /// Exports the latest signed messages, to be imported on another machine.
+    pub fn export(&self) -> SlashingProtectionState {
+        self.state.lock().clone()
+    }
+
+    /// Imports the latest signed messages from another machine. Messages that are older than the
+    /// ones that we already signed are ignored.
+    pub fn import(&self, other: SlashingProtectionState) {
+        let mut state = self.state.lock();
+        state.merge(other);
+        self.persist(&state);
+    }
+
+    fn persist(&self, state: &SlashingProtectionState) {
+        let mut txn = WriteTransaction::new(&self.env);
+        txn.put_reserve(&self.database, Self::STATE_KEY, state);
+        txn.commit();
+    }
+}
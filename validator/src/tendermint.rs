@@ -1,5 +1,7 @@
 use std::sync::Arc;
 use std::time::Duration;
+#[cfg(feature = "metrics")]
+use std::time::Instant;
 
 use async_trait::async_trait;
 use beserial::Serialize;
@@ -26,7 +28,10 @@ use nimiq_validator_network::ValidatorNetwork;
 use nimiq_vrf::VrfSeed;
 
 use crate::aggregation::tendermint::HandelTendermintAdapter;
+use crate::slashing_protection::{SignedTendermintVote, SlashingProtection};
 use crate::validator::ProposalTopic;
+#[cfg(feature = "metrics")]
+use crate::validator_metrics::ValidatorMetrics;
 
 /// The struct that interfaces with the Tendermint crate. It only has to implement the
 /// TendermintOutsideDeps trait in order to do this.
@@ -49,6 +54,11 @@ pub struct TendermintInterface<TValidatorNetwork: ValidatorNetwork> {
     pub blockchain: Arc<RwLock<Blockchain>>,
     // The aggregation adapter allows Tendermint to use Handel functions and networking.
     pub aggregation_adapter: HandelTendermintAdapter<TValidatorNetwork>,
+    // The slashing protection prevents us from casting conflicting votes.
+    pub slashing_protection: Arc<SlashingProtection>,
+    // The validator metrics record timeouts and aggregation durations.
+    #[cfg(feature = "metrics")]
+    pub metrics: Arc<ValidatorMetrics>,
 
     proposal_stream: BoxStream<
         'static,
@@ -309,6 +319,8 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> TendermintOutsideDeps
             Ok(v) => v,
             Err(err) => {
                 debug!("Tendermint - await_proposal: Timed out: {:?}", err);
+                #[cfg(feature = "metrics")]
+                self.metrics.note_tendermint_timeout();
                 return Ok((self, ProposalResult::Timeout));
             }
         };
@@ -423,10 +435,23 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> TendermintOutsideDeps
         proposal_hash: Option<Self::ProposalHashTy>,
     ) -> Result<(Self, AggregationResult<Self::ProposalHashTy, Self::ProofTy>), TendermintError>
     {
-        self.aggregation_adapter
+        if !self.may_vote(round, step, &proposal_hash) {
+            return Err(TendermintError::AggregationError);
+        }
+
+        #[cfg(feature = "metrics")]
+        let aggregation_start = Instant::now();
+
+        let result = self
+            .aggregation_adapter
             .broadcast_and_aggregate(round, step, proposal_hash)
-            .await
-            .map(|result| (self, result))
+            .await;
+
+        #[cfg(feature = "metrics")]
+        self.metrics
+            .note_tendermint_aggregation(aggregation_start.elapsed());
+
+        result.map(|result| (self, result))
     }
 
     fn rebroadcast_and_aggregate(
@@ -435,6 +460,10 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> TendermintOutsideDeps
         step: Step,
         proposal_hash: Option<Self::ProposalHashTy>,
     ) {
+        if !self.may_vote(round, step, &proposal_hash) {
+            return;
+        }
+
         self.aggregation_adapter
             .rebroadcast_and_aggregate(round, step, proposal_hash)
     }
@@ -523,6 +552,18 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> TendermintInterface<TValidat
         unreachable!()
     }
 
+    /// Checks with the slashing protection whether we may cast the given vote, which doesn't
+    /// conflict with any vote that we cast before.
+    fn may_vote(&self, round: u32, step: Step, proposal_hash: &Option<Blake2sHash>) -> bool {
+        self.slashing_protection
+            .sign_tendermint_vote(SignedTendermintVote {
+                block_number: self.block_height,
+                round,
+                step,
+                proposal_hash: proposal_hash.clone(),
+            })
+    }
+
     pub fn new(
         validator_slot_band: u16,
         active_validators: Validators,
@@ -531,6 +572,8 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> TendermintInterface<TValidat
         network: Arc<TValidatorNetwork>,
         blockchain: Arc<RwLock<Blockchain>>,
         block_producer: BlockProducer,
+        slashing_protection: Arc<SlashingProtection>,
+        #[cfg(feature = "metrics")] metrics: Arc<ValidatorMetrics>,
         proposal_stream: BoxStream<
             'static,
             (
@@ -560,6 +603,9 @@ impl<TValidatorNetwork: ValidatorNetwork + 'static> TendermintInterface<TValidat
             current_validators: active_validators,
             blockchain,
             aggregation_adapter,
+            slashing_protection,
+            #[cfg(feature = "metrics")]
+            metrics,
             proposal_stream,
             initial_round,
         }
@@ -18,6 +18,9 @@ use tokio_stream::wrappers::BroadcastStream;
 use crate::micro::{ProduceMicroBlock, ProduceMicroBlockEvent};
 use crate::r#macro::{PersistedMacroState, ProduceMacroBlock};
 use crate::slash::ForkProofPool;
+use crate::slashing_protection::SlashingProtection;
+#[cfg(feature = "metrics")]
+use crate::validator_metrics::ValidatorMetrics;
 use nimiq_account::StakingContract;
 use nimiq_block::{Block, BlockType, SignedTendermintProposal};
 use nimiq_block_production::BlockProducer;
@@ -88,6 +91,11 @@ pub struct ValidatorProxy {
     pub voting_key: Arc<RwLock<BlsKeyPair>>,
     pub fee_key: Arc<RwLock<SchnorrKeyPair>>,
     pub automatic_reactivate: Arc<AtomicBool>,
+    pub slashing_protection: Arc<SlashingProtection>,
+    /// Whether the validator is part of the validator set of the current epoch.
+    pub active: Arc<AtomicBool>,
+    #[cfg(feature = "metrics")]
+    pub metrics: Arc<ValidatorMetrics>,
 }
 
 impl Clone for ValidatorProxy {
@@ -98,6 +106,10 @@ impl Clone for ValidatorProxy {
             voting_key: Arc::clone(&self.voting_key),
             fee_key: Arc::clone(&self.fee_key),
             automatic_reactivate: Arc::clone(&self.automatic_reactivate),
+            slashing_protection: Arc::clone(&self.slashing_protection),
+            active: Arc::clone(&self.active),
+            #[cfg(feature = "metrics")]
+            metrics: Arc::clone(&self.metrics),
         }
     }
 }
@@ -115,6 +127,8 @@ pub struct Validator<TNetwork: Network, TValidatorNetwork: ValidatorNetwork + 's
     voting_key: Arc<RwLock<BlsKeyPair>>,
     fee_key: Arc<RwLock<SchnorrKeyPair>>,
 
+    slashing_protection: Arc<SlashingProtection>,
+
     proposal_receiver: ProposalReceiver<TValidatorNetwork>,
 
     consensus_event_rx: BroadcastStream<ConsensusEvent>,
@@ -122,6 +136,7 @@ pub struct Validator<TNetwork: Network, TValidatorNetwork: ValidatorNetwork + 's
     fork_event_rx: BroadcastStream<ForkEvent>,
 
     epoch_state: Option<ActiveEpochState>,
+    active: Arc<AtomicBool>,
     blockchain_state: BlockchainState,
     validator_state: Option<ValidatorState>,
     automatic_reactivate: Arc<AtomicBool>,
@@ -137,6 +152,8 @@ pub struct Validator<TNetwork: Network, TValidatorNetwork: ValidatorNetwork + 's
     mempool_monitor: TaskMonitor,
     #[cfg(feature = "metrics")]
     control_mempool_monitor: TaskMonitor,
+    #[cfg(feature = "metrics")]
+    metrics: Arc<ValidatorMetrics>,
 }
 
 impl<TNetwork: Network, TValidatorNetwork: ValidatorNetwork>
@@ -179,6 +196,8 @@ impl<TNetwork: Network, TValidatorNetwork: ValidatorNetwork>
             read_transaction.get(&database, Self::MACRO_STATE_KEY)
         };
 
+        let slashing_protection = Arc::new(SlashingProtection::new(env.clone()));
+
         let network1 = Arc::clone(&network);
         let (proposal_sender, proposal_receiver) = ProposalBuffer::new();
 
@@ -200,6 +219,8 @@ impl<TNetwork: Network, TValidatorNetwork: ValidatorNetwork>
             voting_key: Arc::new(RwLock::new(voting_key)),
             fee_key: Arc::new(RwLock::new(fee_key)),
 
+            slashing_protection,
+
             proposal_receiver,
 
             consensus_event_rx,
@@ -207,6 +228,7 @@ impl<TNetwork: Network, TValidatorNetwork: ValidatorNetwork>
             fork_event_rx,
 
             epoch_state: None,
+            active: Arc::new(AtomicBool::new(false)),
             blockchain_state,
             validator_state: None,
             automatic_reactivate,
@@ -222,6 +244,8 @@ impl<TNetwork: Network, TValidatorNetwork: ValidatorNetwork>
             mempool_monitor: TaskMonitor::new(),
             #[cfg(feature = "metrics")]
             control_mempool_monitor: TaskMonitor::new(),
+            #[cfg(feature = "metrics")]
+            metrics: Default::default(),
         };
         this.init();
 
@@ -247,6 +271,12 @@ impl<TNetwork: Network, TValidatorNetwork: ValidatorNetwork>
         self.control_mempool_monitor.clone()
     }
 
+    /// Returns the validator metrics
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> Arc<ValidatorMetrics> {
+        Arc::clone(&self.metrics)
+    }
+
     fn init(&mut self) {
         self.init_epoch();
         self.init_block_producer(None);
@@ -309,6 +339,8 @@ impl<TNetwork: Network, TValidatorNetwork: ValidatorNetwork>
                 break;
             }
         }
+        self.active
+            .store(self.epoch_state.is_some(), Ordering::Release);
 
         let voting_keys: Vec<LazyPublicKey> = validators
             .iter()
@@ -370,6 +402,9 @@ impl<TNetwork: Network, TValidatorNetwork: ValidatorNetwork>
                     Arc::clone(&self.blockchain),
                     Arc::clone(&self.network),
                     block_producer,
+                    Arc::clone(&self.slashing_protection),
+                    #[cfg(feature = "metrics")]
+                    Arc::clone(&self.metrics),
                     self.validator_slot_band(),
                     active_validators,
                     head.seed().clone(),
@@ -395,12 +430,15 @@ impl<TNetwork: Network, TValidatorNetwork: ValidatorNetwork>
                     Arc::clone(&self.mempool),
                     Arc::clone(&self.network),
                     block_producer,
+                    Arc::clone(&self.slashing_protection),
                     self.validator_slot_band(),
                     fork_proofs,
                     prev_seed,
                     next_block_number,
                     Self::PRODUCER_TIMEOUT,
                     Self::BLOCK_SEPARATION_TIME,
+                    #[cfg(feature = "metrics")]
+                    Arc::clone(&self.metrics),
                 ));
             }
         }
@@ -434,10 +472,66 @@ impl<TNetwork: Network, TValidatorNetwork: ValidatorNetwork>
             .get_block(hash, true)
             .expect("Head block not found");
 
+        #[cfg(feature = "metrics")]
+        self.update_slot_metrics(&block);
+
         // Update mempool and blockchain state
         self.blockchain_state.fork_proofs.apply_block(&block);
         self.mempool
             .mempool_update(&vec![(hash.clone(), block)], [].as_ref());
+
+        #[cfg(feature = "metrics")]
+        self.update_balance_metrics();
+    }
+
+    /// Counts the micro block slot of a finished micro or skip block if it was assigned to this
+    /// validator, no matter whether this node was running when the slot came up.
+    #[cfg(feature = "metrics")]
+    fn update_slot_metrics(&self, block: &Block) {
+        if !block.is_micro() {
+            return;
+        }
+
+        let block_number = block.block_number();
+        let slot_owner = self
+            .blockchain
+            .read()
+            .get_slot_owner_at(block_number, block_number, None);
+
+        match slot_owner {
+            Ok((validator, _)) => {
+                if validator.address == self.validator_address() {
+                    self.metrics.note_micro_block_slot();
+                }
+            }
+            Err(error) => {
+                warn!(%error, block_number, "Failed to find the slot owner of a micro block")
+            }
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    fn update_balance_metrics(&self) {
+        let blockchain = self.blockchain.read();
+        let balance = |address: &Address| {
+            blockchain
+                .get_account(address)
+                .map(|account| account.balance())
+                .unwrap_or(Coin::ZERO)
+        };
+
+        let reward_address = {
+            let accounts_tree = &blockchain.state().accounts.tree;
+            let db_txn = blockchain.read_transaction();
+            StakingContract::get_validator(accounts_tree, &db_txn, &self.validator_address())
+                .map(|validator| validator.reward_address)
+        };
+
+        let reward_address_balance = reward_address.as_ref().map(balance).unwrap_or(Coin::ZERO);
+        let fee_address_balance = balance(&Address::from(&self.fee_key()));
+
+        self.metrics
+            .set_balances(reward_address_balance, fee_address_balance);
     }
 
     fn on_blockchain_rebranched(
@@ -450,9 +544,15 @@ impl<TNetwork: Network, TValidatorNetwork: ValidatorNetwork>
             self.blockchain_state.fork_proofs.revert_block(block);
         }
         for (_hash, block) in new_chain.iter() {
+            #[cfg(feature = "metrics")]
+            self.update_slot_metrics(block);
+
             self.blockchain_state.fork_proofs.apply_block(block);
         }
         self.mempool.mempool_update(new_chain, old_chain);
+
+        #[cfg(feature = "metrics")]
+        self.update_balance_metrics();
     }
 
     fn on_fork_event(&mut self, event: ForkEvent) {
@@ -470,6 +570,8 @@ impl<TNetwork: Network, TValidatorNetwork: ValidatorNetwork>
                 }
                 TendermintReturn::Result(block) => {
                     trace!("Tendermint returned block {}", block);
+                    #[cfg(feature = "metrics")]
+                    self.metrics.note_macro_block_round(block.header.round);
                     // If the event is a result meaning the next macro block was produced we push it onto our local chain
                     let block_copy = block.clone();
 
@@ -725,6 +827,10 @@ impl<TNetwork: Network, TValidatorNetwork: ValidatorNetwork>
             voting_key: Arc::clone(&self.voting_key),
             fee_key: Arc::clone(&self.fee_key),
             automatic_reactivate: Arc::clone(&self.automatic_reactivate),
+            slashing_protection: Arc::clone(&self.slashing_protection),
+            active: Arc::clone(&self.active),
+            #[cfg(feature = "metrics")]
+            metrics: Arc::clone(&self.metrics),
         }
     }
 }
@@ -818,7 +924,13 @@ impl<TNetwork: Network, TValidatorNetwork: ValidatorNetwork> Future
         // Once consensus is established, check the validator staking state.
         if self.consensus.is_established() {
             let blockchain = self.blockchain.read();
-            match self.get_staking_state(&blockchain) {
+            let staking_state = self.get_staking_state(&blockchain);
+
+            #[cfg(feature = "metrics")]
+            self.metrics
+                .set_parked(staking_state == ValidatorStakingState::Parked);
+
+            match staking_state {
                 ValidatorStakingState::Parked => match self.validator_state {
                     Some(ValidatorState::ParkingState { .. }) => {}
                     _ => {
@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::metrics::histogram::Histogram;
+use prometheus_client::registry::Registry;
+
+use nimiq_primitives::coin::Coin;
+
+pub struct ValidatorMetrics {
+    micro_block_slots: Counter,
+    micro_blocks_produced: Counter,
+    skip_block_contributions: Counter,
+    tendermint_rounds: Histogram,
+    tendermint_timeouts: Counter,
+    skip_block_aggregation_durations: Histogram,
+    tendermint_aggregation_durations: Histogram,
+    reward_address_balance: Gauge,
+    fee_address_balance: Gauge,
+    parked: Gauge,
+}
+
+impl Default for ValidatorMetrics {
+    fn default() -> Self {
+        ValidatorMetrics {
+            micro_block_slots: Default::default(),
+            micro_blocks_produced: Default::default(),
+            skip_block_contributions: Default::default(),
+            tendermint_rounds: Histogram::new([0.0, 1.0, 2.0, 3.0, 5.0, 10.0].into_iter()),
+            tendermint_timeouts: Default::default(),
+            skip_block_aggregation_durations: Histogram::new(
+                [0.1, 0.2, 0.5, 1.0, 2.0, 5.0, 10.0].into_iter(),
+            ),
+            tendermint_aggregation_durations: Histogram::new(
+                [0.1, 0.2, 0.5, 1.0, 2.0, 5.0, 10.0].into_iter(),
+            ),
+            reward_address_balance: Default::default(),
+            fee_address_balance: Default::default(),
+            parked: Default::default(),
+        }
+    }
+}
+
+impl ValidatorMetrics {
+    pub fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "micro_block_slots",
+            "Number of micro block slots assigned to the validator",
+            Box::new(self.micro_block_slots.clone()),
+        );
+
+        registry.register(
+            "micro_blocks_produced",
+            "Number of micro blocks produced by the validator",
+            Box::new(self.micro_blocks_produced.clone()),
+        );
+
+        registry.register(
+            "skip_block_contributions",
+            "Number of skip blocks the validator contributed to",
+            Box::new(self.skip_block_contributions.clone()),
+        );
+
+        registry.register(
+            "tendermint_rounds",
+            "Round in which a macro block was produced",
+            Box::new(self.tendermint_rounds.clone()),
+        );
+
+        registry.register(
+            "tendermint_timeouts",
+            "Number of Tendermint rounds in which no valid proposal was received in time",
+            Box::new(self.tendermint_timeouts.clone()),
+        );
+
+        registry.register(
+            "skip_block_aggregation_durations",
+            "Time needed to aggregate a skip block",
+            Box::new(self.skip_block_aggregation_durations.clone()),
+        );
+
+        registry.register(
+            "tendermint_aggregation_durations",
+            "Time needed to aggregate a Tendermint vote",
+            Box::new(self.tendermint_aggregation_durations.clone()),
+        );
+
+        registry.register(
+            "reward_address_balance",
+            "Balance of the reward address in Luna",
+            Box::new(self.reward_address_balance.clone()),
+        );
+
+        registry.register(
+            "fee_address_balance",
+            "Balance of the fee address in Luna",
+            Box::new(self.fee_address_balance.clone()),
+        );
+
+        registry.register(
+            "parked",
+            "Whether the validator is currently parked",
+            Box::new(self.parked.clone()),
+        );
+    }
+
+    pub(crate) fn note_micro_block_slot(&self) {
+        self.micro_block_slots.inc();
+    }
+
+    pub(crate) fn note_micro_block_produced(&self) {
+        self.micro_blocks_produced.inc();
+    }
+
+    pub(crate) fn note_skip_block_aggregation(&self, duration: Duration) {
+        self.skip_block_contributions.inc();
+        self.skip_block_aggregation_durations
+            .observe(duration.as_secs_f64());
+    }
+
+    pub(crate) fn note_macro_block_round(&self, round: u32) {
+        self.tendermint_rounds.observe(round as f64);
+    }
+
+    pub(crate) fn note_tendermint_timeout(&self) {
+        self.tendermint_timeouts.inc();
+    }
+
+    pub(crate) fn note_tendermint_aggregation(&self, duration: Duration) {
+        self.tendermint_aggregation_durations
+            .observe(duration.as_secs_f64());
+    }
+
+    pub(crate) fn set_balances(&self, reward_address_balance: Coin, fee_address_balance: Coin) {
+        self.reward_address_balance
+            .set(u64::from(reward_address_balance));
+        self.fee_address_balance.set(u64::from(fee_address_balance));
+    }
+
+    pub(crate) fn set_parked(&self, parked: bool) {
+        self.parked.set(parked as u64);
+    }
+}
@@ -0,0 +1,113 @@
+use nimiq_database::volatile::VolatileEnvironment;
+use nimiq_hash::{Blake2bHash, Blake2sHash};
+use nimiq_tendermint::Step;
+use nimiq_test_log::test;
+use nimiq_validator::slashing_protection::{SignedBlock, SignedTendermintVote, SlashingProtection};
+
+fn vote(round: u32, step: Step, proposal_hash: Option<Blake2sHash>) -> SignedTendermintVote {
+    SignedTendermintVote {
+        block_number: 32,
+        round,
+        step,
+        proposal_hash,
+    }
+}
+
+#[test]
+fn it_refuses_conflicting_messages() {
+    let env = VolatileEnvironment::new(1).unwrap();
+    let protection = SlashingProtection::new(env.clone());
+
+    let hash1 = Blake2bHash::from([1u8; 32]);
+    let hash2 = Blake2bHash::from([2u8; 32]);
+
+    assert!(protection.sign_micro_block(1, hash1.clone()));
+    assert!(protection.sign_micro_block(1, hash1.clone()));
+    assert!(!protection.sign_micro_block(1, hash2.clone()));
+    assert!(!protection.sign_micro_block(0, hash2.clone()));
+    assert!(protection.sign_micro_block(2, hash2.clone()));
+
+    assert!(protection.sign_skip_block(3, hash1.clone()));
+    assert!(!protection.sign_skip_block(3, hash2.clone()));
+
+    let proposal = Some(Blake2sHash::from([3u8; 32]));
+    assert!(protection.sign_tendermint_vote(vote(0, Step::Prevote, proposal.clone())));
+    assert!(protection.sign_tendermint_vote(vote(0, Step::Prevote, proposal.clone())));
+    assert!(!protection.sign_tendermint_vote(vote(0, Step::Prevote, None)));
+    assert!(protection.sign_tendermint_vote(vote(0, Step::Precommit, None)));
+    assert!(!protection.sign_tendermint_vote(vote(0, Step::Prevote, None)));
+
+    // The state survives a restart.
+    let protection = SlashingProtection::new(env);
+    assert!(!protection.sign_micro_block(2, hash1.clone()));
+    assert!(!protection.sign_skip_block(3, hash2));
+    assert!(!protection.sign_tendermint_vote(vote(
+        0,
+        Step::Precommit,
+        Some(Blake2sHash::from([3u8; 32]))
+    )));
+}
+
+#[test]
+fn it_allows_rebroadcasting_earlier_votes_after_a_restart() {
+    let env = VolatileEnvironment::new(1).unwrap();
+    let protection = SlashingProtection::new(env.clone());
+
+    let proposal = Some(Blake2sHash::from([3u8; 32]));
+    assert!(protection.sign_tendermint_vote(vote(0, Step::Prevote, proposal.clone())));
+    assert!(protection.sign_tendermint_vote(vote(0, Step::Precommit, proposal.clone())));
+    assert!(protection.sign_tendermint_vote(vote(1, Step::Prevote, None)));
+
+    // After a restart, Tendermint rebroadcasts the votes of earlier rounds and steps.
+    let protection = SlashingProtection::new(env);
+    assert!(protection.sign_tendermint_vote(vote(0, Step::Prevote, proposal.clone())));
+    assert!(protection.sign_tendermint_vote(vote(0, Step::Precommit, proposal)));
+    assert!(protection.sign_tendermint_vote(vote(1, Step::Prevote, None)));
+
+    // Votes that differ from the recorded ones, or that were never cast, are still refused.
+    assert!(!protection.sign_tendermint_vote(vote(0, Step::Precommit, None)));
+    assert!(!protection.sign_tendermint_vote(vote(1, Step::Propose, None)));
+
+    // Votes for the next block replace the recorded ones.
+    assert!(protection.sign_tendermint_vote(SignedTendermintVote {
+        block_number: 33,
+        round: 0,
+        step: Step::Prevote,
+        proposal_hash: None,
+    }));
+    assert_eq!(protection.export().tendermint_votes.len(), 1);
+    assert!(!protection.sign_tendermint_vote(vote(1, Step::Prevote, None)));
+}
+
+#[test]
+fn it_imports_only_later_messages() {
+    let protection = SlashingProtection::new(VolatileEnvironment::new(1).unwrap());
+    let other = SlashingProtection::new(VolatileEnvironment::new(1).unwrap());
+
+    let hash1 = Blake2bHash::from([1u8; 32]);
+    let hash2 = Blake2bHash::from([2u8; 32]);
+
+    assert!(protection.sign_micro_block(5, hash1.clone()));
+    assert!(other.sign_micro_block(4, hash2.clone()));
+    assert!(other.sign_skip_block(6, hash2.clone()));
+
+    protection.import(other.export());
+
+    let state = protection.export();
+    assert_eq!(
+        state.micro_block,
+        Some(SignedBlock {
+            block_number: 5,
+            hash: hash1
+        })
+    );
+    assert_eq!(
+        state.skip_block,
+        Some(SignedBlock {
+            block_number: 6,
+            hash: hash2.clone()
+        })
+    );
+    assert!(!protection.sign_skip_block(6, Blake2bHash::default()));
+    assert!(protection.sign_skip_block(6, hash2));
+}
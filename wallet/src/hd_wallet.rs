@@ -0,0 +1,157 @@
+use std::io;
+
+use rand::rngs::OsRng;
+use rand::RngCore;
+use thiserror::Error;
+
+use beserial::{Deserialize, Serialize};
+use nimiq_database_value::{FromDatabaseValue, IntoDatabaseValue};
+use nimiq_key_derivation::ExtendedPrivateKey;
+use nimiq_keys::{Address, KeyPair};
+use nimiq_mnemonic::{Entropy, Mnemonic, MnemonicType, WORDLIST_EN};
+use nimiq_utils::otp::{Locked, Verify};
+
+use crate::wallet_account::WalletAccount;
+
+/// The derivation path of the first account of a wallet. The n-th account is derived at
+/// `m/44'/242'/0'/n'`, the same as in the Nimiq Keyguard and the Nimiq Ledger app.
+pub const DEFAULT_DERIVATION_PATH_PREFIX: &str = "m/44'/242'/0'";
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum HdWalletError {
+    #[error("Invalid mnemonic")]
+    InvalidMnemonic,
+    #[error("Invalid derivation path: {0}")]
+    InvalidPath(String),
+    #[error("Failed to compute the seed of the mnemonic")]
+    SeedComputation,
+}
+
+/// A hierarchical-deterministic wallet. It holds the seed of a mnemonic, from which any number of
+/// accounts can be derived.
+///
+/// The wallet is identified by the address of its master key, which never holds any funds.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct HdWallet {
+    pub id: Address,
+    #[beserial(len_type(u8))]
+    seed: Vec<u8>,
+}
+
+impl Verify for HdWallet {
+    fn verify(&self) -> bool {
+        // Check that the id corresponds to the seed.
+        self.master_key().to_address() == self.id
+    }
+}
+
+impl HdWallet {
+    /// Generates a new wallet from a random BIP39 mnemonic. The mnemonic is returned as well, it
+    /// is the only backup of the wallet.
+    pub fn generate() -> (Self, Mnemonic) {
+        let mnemonic = loop {
+            let mut entropy = [0u8; Entropy::SIZE];
+            OsRng.fill_bytes(&mut entropy);
+            let entropy = Entropy::from(entropy);
+
+            // Avoid mnemonics that are valid in both the BIP39 and the legacy format.
+            if !entropy.is_colliding_checksum() {
+                break entropy.to_mnemonic(WORDLIST_EN);
+            }
+        };
+
+        let wallet =
+            HdWallet::from_mnemonic(&mnemonic, None).expect("Generated mnemonic must be valid");
+        (wallet, mnemonic)
+    }
+
+    /// Restores a wallet from a mnemonic in the BIP39 or the legacy format. The optional
+    /// `password` is the BIP39 passphrase, not the passphrase used to encrypt the wallet.
+    pub fn from_mnemonic(
+        mnemonic: &Mnemonic,
+        password: Option<&str>,
+    ) -> Result<Self, HdWalletError> {
+        if mnemonic.get_type(WORDLIST_EN) == MnemonicType::INVALID {
+            return Err(HdWalletError::InvalidMnemonic);
+        }
+
+        let seed = mnemonic
+            .to_seed(password)
+            .map_err(|_| HdWalletError::SeedComputation)?;
+        let id = ExtendedPrivateKey::from_seed(seed.clone()).to_address();
+
+        Ok(HdWallet { id, seed })
+    }
+
+    /// Returns the derivation path of the account with the given `index`.
+    pub fn derivation_path(index: u32) -> String {
+        format!("{}/{}'", DEFAULT_DERIVATION_PATH_PREFIX, index)
+    }
+
+    /// Derives the account at the given derivation `path`, e.g. `m/44'/242'/0'/0'`.
+    pub fn derive_account(&self, path: &str) -> Result<WalletAccount, HdWalletError> {
+        let key = self
+            .master_key()
+            .derive_path(path)
+            .ok_or_else(|| HdWalletError::InvalidPath(path.to_string()))?;
+
+        Ok(WalletAccount::from(KeyPair::from(key.into_private_key())))
+    }
+
+    fn master_key(&self) -> ExtendedPrivateKey {
+        ExtendedPrivateKey::from_seed(self.seed.clone())
+    }
+}
+
+/// An account that was derived from an HD wallet.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct HdWalletAccount {
+    #[beserial(len_type(u8))]
+    pub path: String,
+    pub address: Address,
+}
+
+/// An HD wallet as it is persisted in the [`WalletStore`](crate::WalletStore): the encrypted
+/// wallet together with the accounts that were derived from it so far.
+#[derive(Serialize, Deserialize)]
+pub struct LockedHdWallet {
+    pub wallet: Locked<HdWallet>,
+    #[beserial(len_type(u16))]
+    pub accounts: Vec<HdWalletAccount>,
+}
+
+impl LockedHdWallet {
+    pub fn new(wallet: Locked<HdWallet>) -> Self {
+        LockedHdWallet {
+            wallet,
+            accounts: vec![],
+        }
+    }
+
+    /// Records a derived account, unless it has been derived before.
+    pub fn add_account(&mut self, account: HdWalletAccount) {
+        if !self.accounts.contains(&account) {
+            self.accounts.push(account);
+        }
+    }
+}
+
+impl IntoDatabaseValue for LockedHdWallet {
+    fn database_byte_size(&self) -> usize {
+        self.serialized_size()
+    }
+
+    fn copy_into_database(&self, mut bytes: &mut [u8]) {
+        Serialize::serialize(&self, &mut bytes).unwrap();
+    }
+}
+
+impl FromDatabaseValue for LockedHdWallet {
+    fn copy_from_database(bytes: &[u8]) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut cursor = io::Cursor::new(bytes);
+        Ok(Deserialize::deserialize(&mut cursor)?)
+    }
+}
@@ -0,0 +1,130 @@
+use beserial::{
+    Deserialize, DeserializeWithLength, ReadBytesExt, Serialize, SerializeWithLength,
+    SerializingError, WriteBytesExt,
+};
+use nimiq_hash::argon2kdf::Argon2Error;
+use nimiq_keys::Address;
+use nimiq_utils::otp::{Locked, Unlocked};
+use thiserror::Error;
+
+use crate::wallet_account::WalletAccount;
+
+#[derive(Debug, Error)]
+pub enum KeystoreError {
+    #[error("Unsupported keystore version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("Wrong passphrase")]
+    WrongPassphrase,
+    #[error("Keystore entry does not match its address: {0}")]
+    AddressMismatch(Address),
+    #[error("Invalid hex: {0}")]
+    Hex(#[from] hex::FromHexError),
+    #[error("{0}")]
+    Serialization(#[from] SerializingError),
+    #[error("{0}")]
+    Argon2(#[from] Argon2Error),
+}
+
+/// An account in a keystore. The address is stored in plain text, which allows to list the
+/// accounts of a keystore without unlocking it and to check the integrity of each account once it
+/// is unlocked.
+#[derive(Serialize, Deserialize)]
+struct KeystoreEntry {
+    address: Address,
+    account: Locked<WalletAccount>,
+}
+
+/// A portable backup of one or more accounts, encrypted with a single passphrase.
+///
+/// The encryption key is derived from the passphrase with Argon2 and a random salt per account.
+/// The serialized keystore starts with a version byte, such that the format can be changed in the
+/// future without breaking existing backups.
+#[derive(Default)]
+pub struct Keystore {
+    entries: Vec<KeystoreEntry>,
+}
+
+impl Keystore {
+    pub const VERSION: u8 = 1;
+
+    /// Adds an account to the keystore, encrypted with the given `passphrase`.
+    pub fn add_account(
+        &mut self,
+        account: WalletAccount,
+        passphrase: &[u8],
+    ) -> Result<(), KeystoreError> {
+        let address = account.address.clone();
+        let account = Locked::with_defaults(account, passphrase)?;
+        self.entries.push(KeystoreEntry { address, account });
+        Ok(())
+    }
+
+    /// Returns the addresses of all accounts in the keystore.
+    pub fn addresses(&self) -> Vec<Address> {
+        self.entries
+            .iter()
+            .map(|entry| entry.address.clone())
+            .collect()
+    }
+
+    /// Decrypts all accounts in the keystore. Fails if the `passphrase` is wrong or if an account
+    /// does not match the address it is stored with.
+    pub fn unlock(self, passphrase: &[u8]) -> Result<Vec<WalletAccount>, KeystoreError> {
+        self.entries
+            .into_iter()
+            .map(|entry| {
+                let account = entry
+                    .account
+                    .unlock(passphrase)
+                    .map_err(|_locked| KeystoreError::WrongPassphrase)?;
+                let account = Unlocked::into_unlocked_data(account);
+
+                if account.address != entry.address {
+                    return Err(KeystoreError::AddressMismatch(entry.address));
+                }
+                Ok(account)
+            })
+            .collect()
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.serialize_to_vec())
+    }
+
+    pub fn from_hex(s: &str) -> Result<Self, KeystoreError> {
+        let bytes = hex::decode(s.trim())?;
+        match bytes.first() {
+            Some(&Self::VERSION) => Ok(Deserialize::deserialize_from_vec(&bytes)?),
+            Some(&version) => Err(KeystoreError::UnsupportedVersion(version)),
+            None => Err(SerializingError::InvalidEncoding.into()),
+        }
+    }
+}
+
+impl Serialize for Keystore {
+    fn serialize<W: WriteBytesExt>(&self, writer: &mut W) -> Result<usize, SerializingError> {
+        let mut size = 0;
+        size += Serialize::serialize(&Self::VERSION, writer)?;
+        size += SerializeWithLength::serialize::<u16, _>(&self.entries, writer)?;
+        Ok(size)
+    }
+
+    fn serialized_size(&self) -> usize {
+        let mut size = 0;
+        size += Serialize::serialized_size(&Self::VERSION);
+        size += SerializeWithLength::serialized_size::<u16>(&self.entries);
+        size
+    }
+}
+
+impl Deserialize for Keystore {
+    fn deserialize<R: ReadBytesExt>(reader: &mut R) -> Result<Self, SerializingError> {
+        let version: u8 = Deserialize::deserialize(reader)?;
+        if version != Self::VERSION {
+            return Err(SerializingError::InvalidValue);
+        }
+
+        let entries = DeserializeWithLength::deserialize::<u16, _>(reader)?;
+        Ok(Keystore { entries })
+    }
+}
@@ -1,5 +1,16 @@
+pub use hd_wallet::{
+    HdWallet, HdWalletAccount, HdWalletError, LockedHdWallet, DEFAULT_DERIVATION_PATH_PREFIX,
+};
+pub use keystore::{Keystore, KeystoreError};
+pub use multisig_account::{
+    MultiSigAccount, MultiSigError, PendingCommitments, MAX_MULTISIG_PUBLIC_KEYS,
+    MAX_MULTISIG_SIGNER_SETS, MAX_PENDING_COMMITMENTS,
+};
 pub use wallet_account::WalletAccount;
 pub use wallet_store::WalletStore;
 
+mod hd_wallet;
+mod keystore;
+mod multisig_account;
 mod wallet_account;
 mod wallet_store;
@@ -0,0 +1,292 @@
+use std::collections::{HashMap, VecDeque};
+
+use beserial::Serialize;
+use nimiq_hash::Blake2bHasher;
+use nimiq_keys::multisig::{
+    aggregate_public_keys, Commitment, CommitmentPair, PartialSignature, RandomSecret,
+};
+use nimiq_keys::{Address, KeyPair, PublicKey, SecureGenerate};
+use nimiq_transaction::{SignatureProof, Transaction};
+use nimiq_utils::merkle::{compute_root_from_content, Blake2bMerklePath};
+use thiserror::Error;
+
+/// The maximum number of distinct public keys of a multisig account.
+pub const MAX_MULTISIG_PUBLIC_KEYS: usize = 16;
+
+/// The maximum number of sets of `min_signatures` signers of a multisig account. The address of
+/// the account is computed over the aggregated public key of each of these sets.
+pub const MAX_MULTISIG_SIGNER_SETS: u64 = 1024;
+
+/// The maximum number of commitments whose random secrets are kept for the second signing round.
+pub const MAX_PENDING_COMMITMENTS: usize = 1000;
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum MultiSigError {
+    #[error(
+        "At least one signer is required and at most as many signers as there are public keys"
+    )]
+    InvalidMinSignatures,
+    #[error(
+        "Got {0} public keys, but a multisig account can have at most {}",
+        MAX_MULTISIG_PUBLIC_KEYS
+    )]
+    TooManyPublicKeys(usize),
+    #[error(
+        "Got {0} possible sets of signers, but a multisig account can have at most {}",
+        MAX_MULTISIG_SIGNER_SETS
+    )]
+    TooManySignerSets(u64),
+    #[error("Expected {0} signers, but got {1}")]
+    WrongNumberOfSigners(usize, usize),
+    #[error("Signer is not one of the public keys of the multisig: {0}")]
+    UnknownSigner(PublicKey),
+    #[error("Own public key is not one of the signers")]
+    NotASigner,
+    #[error("Each signer has to provide exactly one commitment and one partial signature")]
+    MissingContributions,
+    #[error("No random secret for the commitment, it is unknown or has already been used")]
+    UnknownCommitment,
+    #[error("The transaction is not sent from the multisig address")]
+    WrongSender,
+    #[error("The aggregated signature is invalid")]
+    InvalidSignature,
+}
+
+/// A multisig account whose transactions need to be signed by at least `min_signatures` of its
+/// `public_keys`.
+///
+/// Signing a transaction is done in two rounds. First, each signer creates a commitment with
+/// [`MultiSigAccount::create_commitment`] and shares the commitment (but not the random secret)
+/// with the other signers. Then, each signer creates a partial signature over the transaction
+/// with [`MultiSigAccount::partially_sign_transaction`]. Finally, the partial signatures and the
+/// commitments are aggregated into a [`SignatureProof`].
+///
+/// The address of the account is the root of a Merkle tree over the aggregated public keys of all
+/// possible sets of `min_signatures` signers.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MultiSigAccount {
+    pub min_signatures: usize,
+    pub public_keys: Vec<PublicKey>,
+    pub address: Address,
+    multisig_keys: Vec<PublicKey>,
+}
+
+impl MultiSigAccount {
+    pub fn new(min_signatures: usize, public_keys: &[PublicKey]) -> Result<Self, MultiSigError> {
+        let mut public_keys = public_keys.to_vec();
+        public_keys.sort();
+        public_keys.dedup();
+
+        if min_signatures == 0 || min_signatures > public_keys.len() {
+            return Err(MultiSigError::InvalidMinSignatures);
+        }
+        if public_keys.len() > MAX_MULTISIG_PUBLIC_KEYS {
+            return Err(MultiSigError::TooManyPublicKeys(public_keys.len()));
+        }
+        let signer_sets = binomial(public_keys.len() as u64, min_signatures as u64);
+        if signer_sets > MAX_MULTISIG_SIGNER_SETS {
+            return Err(MultiSigError::TooManySignerSets(signer_sets));
+        }
+
+        let multisig_keys: Vec<PublicKey> = combinations(&public_keys, min_signatures)
+            .iter()
+            .map(|signers| aggregate_public_keys(signers))
+            .collect();
+        let address = Address::from(compute_root_from_content::<Blake2bHasher, _>(
+            &multisig_keys,
+        ));
+
+        Ok(MultiSigAccount {
+            min_signatures,
+            public_keys,
+            address,
+            multisig_keys,
+        })
+    }
+
+    /// Creates the partial signature of `key_pair` over the `transaction`, given the public keys
+    /// of all `signers` and all of their commitments. `commitment` is the signer's own commitment,
+    /// which must have been created by `pending_commitments`. Its random secret is removed, such
+    /// that it can't be used for a second signature.
+    pub fn partially_sign_transaction(
+        &self,
+        transaction: &Transaction,
+        key_pair: &KeyPair,
+        signers: &[PublicKey],
+        commitment: &Commitment,
+        commitments: &[Commitment],
+        pending_commitments: &mut PendingCommitments,
+    ) -> Result<PartialSignature, MultiSigError> {
+        if transaction.sender != self.address {
+            return Err(MultiSigError::WrongSender);
+        }
+        let signers = self.sorted_signers(signers)?;
+        if !signers.contains(&key_pair.public) {
+            return Err(MultiSigError::NotASigner);
+        }
+        if commitments.len() != signers.len() || !commitments.contains(commitment) {
+            return Err(MultiSigError::MissingContributions);
+        }
+
+        let random_secret = pending_commitments
+            .take_random_secret(commitment)
+            .ok_or(MultiSigError::UnknownCommitment)?;
+
+        let (partial_signature, _, _) = key_pair.partial_sign(
+            &signers,
+            &random_secret,
+            commitments,
+            transaction.serialize_content().as_slice(),
+        );
+        Ok(partial_signature)
+    }
+
+    /// Aggregates the commitments and partial signatures of all `signers` into a signature proof
+    /// for the `transaction`.
+    pub fn create_signature_proof(
+        &self,
+        transaction: &Transaction,
+        signers: &[PublicKey],
+        commitments: &[Commitment],
+        partial_signatures: &[PartialSignature],
+    ) -> Result<SignatureProof, MultiSigError> {
+        let signers = self.sorted_signers(signers)?;
+        if commitments.len() != signers.len() || partial_signatures.len() != signers.len() {
+            return Err(MultiSigError::MissingContributions);
+        }
+
+        let public_key = aggregate_public_keys(&signers);
+        let aggregated_commitment: Commitment = commitments.iter().sum();
+        let partial_signature: PartialSignature = partial_signatures.iter().sum();
+
+        let proof = SignatureProof {
+            public_key,
+            merkle_path: Blake2bMerklePath::new::<Blake2bHasher, _>(
+                &self.multisig_keys,
+                &public_key,
+            ),
+            signature: partial_signature.to_signature(&aggregated_commitment),
+        };
+
+        if !proof.is_signed_by(&transaction.sender) {
+            return Err(MultiSigError::WrongSender);
+        }
+        if !proof.verify(transaction.serialize_content().as_slice()) {
+            return Err(MultiSigError::InvalidSignature);
+        }
+
+        Ok(proof)
+    }
+
+    /// Aggregates the commitments and partial signatures of all `signers` and attaches the
+    /// resulting signature proof to the `transaction`.
+    pub fn sign_transaction(
+        &self,
+        transaction: &mut Transaction,
+        signers: &[PublicKey],
+        commitments: &[Commitment],
+        partial_signatures: &[PartialSignature],
+    ) -> Result<(), MultiSigError> {
+        let proof =
+            self.create_signature_proof(transaction, signers, commitments, partial_signatures)?;
+        transaction.proof = proof.serialize_to_vec();
+        Ok(())
+    }
+
+    /// Checks that the signers are `min_signatures` distinct keys of the multisig and returns them
+    /// in the order that is used for the signature.
+    fn sorted_signers(&self, signers: &[PublicKey]) -> Result<Vec<PublicKey>, MultiSigError> {
+        let mut signers = signers.to_vec();
+        signers.sort();
+        signers.dedup();
+
+        if signers.len() != self.min_signatures {
+            return Err(MultiSigError::WrongNumberOfSigners(
+                self.min_signatures,
+                signers.len(),
+            ));
+        }
+        if let Some(signer) = signers.iter().find(|key| !self.public_keys.contains(key)) {
+            return Err(MultiSigError::UnknownSigner(*signer));
+        }
+
+        Ok(signers)
+    }
+}
+
+/// The random secrets of the commitments a signer handed out for the first signing round.
+///
+/// Using the same random secret for two different partial signatures reveals the private key of
+/// the signer. Thus, each random secret is removed once it has been used. If too many commitments
+/// are pending, the oldest ones are discarded.
+#[derive(Default)]
+pub struct PendingCommitments {
+    random_secrets: HashMap<[u8; Commitment::SIZE], RandomSecret>,
+    order: VecDeque<[u8; Commitment::SIZE]>,
+}
+
+impl PendingCommitments {
+    /// Creates a new commitment for the first signing round and keeps its random secret. Only
+    /// the commitment is shared with the other signers.
+    pub fn create_commitment(&mut self) -> Commitment {
+        let pair = CommitmentPair::generate_default_csprng();
+        let key = pair.commitment().to_bytes();
+
+        if self.order.len() >= MAX_PENDING_COMMITMENTS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.random_secrets.remove(&oldest);
+            }
+        }
+        self.random_secrets.insert(key, *pair.random_secret());
+        self.order.push_back(key);
+
+        *pair.commitment()
+    }
+
+    /// Removes and returns the random secret of the given commitment.
+    pub fn take_random_secret(&mut self, commitment: &Commitment) -> Option<RandomSecret> {
+        let key = commitment.to_bytes();
+        let random_secret = self.random_secrets.remove(&key)?;
+        self.order.retain(|pending| pending != &key);
+        Some(random_secret)
+    }
+
+    pub fn len(&self) -> usize {
+        self.random_secrets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.random_secrets.is_empty()
+    }
+}
+
+/// Computes the binomial coefficient `n` choose `k`, saturating at `u64::MAX`.
+fn binomial(n: u64, k: u64) -> u64 {
+    let k = k.min(n - k);
+    let mut result: u64 = 1;
+    for i in 0..k {
+        // The intermediate result is always divisible, since it is `(n choose i) * (n - i)`.
+        result = result.saturating_mul(n - i) / (i + 1);
+    }
+    result
+}
+
+/// Returns all combinations of `k` elements of `values`, preserving their order.
+fn combinations<T: Clone>(values: &[T], k: usize) -> Vec<Vec<T>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+    if values.len() < k {
+        return vec![];
+    }
+
+    let mut result: Vec<Vec<T>> = combinations(&values[1..], k - 1)
+        .into_iter()
+        .map(|mut rest| {
+            rest.insert(0, values[0].clone());
+            rest
+        })
+        .collect();
+    result.extend(combinations(&values[1..], k));
+    result
+}
@@ -3,20 +3,28 @@ use nimiq_database::{Database, Environment, ReadTransaction, Transaction, WriteT
 use nimiq_keys::Address;
 use nimiq_utils::otp::Locked;
 
+use crate::hd_wallet::LockedHdWallet;
 use crate::wallet_account::WalletAccount;
 
 #[derive(Debug)]
 pub struct WalletStore {
     env: Environment,
     wallet_db: Database,
+    hd_wallet_db: Database,
 }
 
 impl WalletStore {
     const WALLET_DB_NAME: &'static str = "Wallet";
+    const HD_WALLET_DB_NAME: &'static str = "HdWallet";
 
     pub fn new(env: Environment) -> Self {
         let wallet_db = env.open_database(Self::WALLET_DB_NAME.to_string());
-        WalletStore { env, wallet_db }
+        let hd_wallet_db = env.open_database(Self::HD_WALLET_DB_NAME.to_string());
+        WalletStore {
+            env,
+            wallet_db,
+            hd_wallet_db,
+        }
     }
 
     pub fn create_read_transaction(&self) -> ReadTransaction {
@@ -68,4 +76,41 @@ impl WalletStore {
     ) {
         txn.put_reserve(&self.wallet_db, address, wallet);
     }
+
+    pub fn list_hd_wallets(&self, txn_option: Option<&Transaction>) -> Vec<Address> {
+        let read_txn: ReadTransaction;
+        let txn = match txn_option {
+            Some(txn) => txn,
+            None => {
+                read_txn = ReadTransaction::new(&self.env);
+                &read_txn
+            }
+        };
+
+        let mut wallets = Vec::new();
+        let mut cursor = txn.cursor(&self.hd_wallet_db);
+        let mut wallet: Option<(Address, LockedHdWallet)> = cursor.first();
+
+        while let Some((id, _)) = wallet {
+            wallets.push(id);
+            wallet = cursor.next();
+        }
+
+        wallets
+    }
+
+    pub fn get_hd_wallet(
+        &self,
+        id: &Address,
+        txn_option: Option<&Transaction>,
+    ) -> Option<LockedHdWallet> {
+        match txn_option {
+            Some(txn) => txn.get(&self.hd_wallet_db, id),
+            None => ReadTransaction::new(&self.env).get(&self.hd_wallet_db, id),
+        }
+    }
+
+    pub fn put_hd_wallet(&self, id: &Address, wallet: &LockedHdWallet, txn: &mut WriteTransaction) {
+        txn.put_reserve(&self.hd_wallet_db, id, wallet);
+    }
 }
@@ -0,0 +1,89 @@
+use nimiq_database::volatile::VolatileEnvironment;
+use nimiq_mnemonic::Mnemonic;
+use nimiq_test_log::test;
+use nimiq_utils::otp::{Locked, Unlocked};
+use nimiq_wallet::{HdWallet, HdWalletAccount, HdWalletError, LockedHdWallet, WalletStore};
+
+#[test]
+fn it_can_restore_a_wallet_from_its_mnemonic() {
+    let (wallet, mnemonic) = HdWallet::generate();
+    let restored = HdWallet::from_mnemonic(&mnemonic, None).unwrap();
+
+    assert_eq!(wallet.id, restored.id);
+    assert_eq!(
+        wallet
+            .derive_account(&HdWallet::derivation_path(0))
+            .unwrap(),
+        restored.derive_account("m/44'/242'/0'/0'").unwrap()
+    );
+    assert_ne!(
+        wallet
+            .derive_account(&HdWallet::derivation_path(0))
+            .unwrap(),
+        wallet
+            .derive_account(&HdWallet::derivation_path(1))
+            .unwrap()
+    );
+
+    let with_password = HdWallet::from_mnemonic(&mnemonic, Some("password")).unwrap();
+    assert_ne!(wallet.id, with_password.id);
+}
+
+#[test]
+fn it_rejects_invalid_mnemonics_and_paths() {
+    let mnemonic: Mnemonic = "not a valid mnemonic".parse().unwrap();
+    assert_eq!(
+        HdWallet::from_mnemonic(&mnemonic, None).err(),
+        Some(HdWalletError::InvalidMnemonic)
+    );
+
+    let (wallet, _) = HdWallet::generate();
+    assert_eq!(
+        wallet.derive_account("m/44/0").err(),
+        Some(HdWalletError::InvalidPath("m/44/0".to_string()))
+    );
+}
+
+#[test]
+fn it_can_persist_an_encrypted_wallet() {
+    let env = VolatileEnvironment::new(10).unwrap();
+    let store = WalletStore::new(env);
+
+    let (wallet, _) = HdWallet::generate();
+    let id = wallet.id.clone();
+    let path = HdWallet::derivation_path(0);
+    let account = wallet.derive_account(&path).unwrap();
+
+    let mut locked_wallet = LockedHdWallet::new(Locked::with_defaults(wallet, b"test").unwrap());
+    locked_wallet.add_account(HdWalletAccount {
+        path: path.clone(),
+        address: account.address.clone(),
+    });
+
+    let mut txn = store.create_write_transaction();
+    store.put_hd_wallet(&id, &locked_wallet, &mut txn);
+    txn.commit();
+
+    assert_eq!(store.list_hd_wallets(None), vec![id.clone()]);
+
+    let stored = store.get_hd_wallet(&id, None).unwrap();
+    assert_eq!(
+        stored.accounts,
+        vec![HdWalletAccount {
+            path: path.clone(),
+            address: account.address.clone()
+        }]
+    );
+
+    let stored = store.get_hd_wallet(&id, None).unwrap();
+    assert!(stored.wallet.unlock(b"wrong").is_err());
+
+    let stored = store.get_hd_wallet(&id, None).unwrap();
+    let unlocked = stored.wallet.unlock(b"test").ok().unwrap();
+    assert_eq!(
+        Unlocked::unlocked_data(&unlocked)
+            .derive_account(&path)
+            .unwrap(),
+        account
+    );
+}
@@ -0,0 +1,56 @@
+use nimiq_test_log::test;
+use nimiq_wallet::{Keystore, KeystoreError, WalletAccount};
+
+fn keystore(accounts: &[WalletAccount]) -> Keystore {
+    let mut keystore = Keystore::default();
+    for account in accounts {
+        keystore.add_account(account.clone(), b"backup").unwrap();
+    }
+    keystore
+}
+
+#[test]
+fn it_can_export_and_import_accounts() {
+    let accounts = vec![WalletAccount::generate(), WalletAccount::generate()];
+    let exported = keystore(&accounts).to_hex();
+
+    let imported = Keystore::from_hex(&exported).unwrap();
+    assert_eq!(
+        imported.addresses(),
+        accounts
+            .iter()
+            .map(|account| account.address.clone())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(imported.unlock(b"backup").unwrap(), accounts);
+}
+
+#[test]
+fn it_rejects_a_wrong_passphrase() {
+    let exported = keystore(&[WalletAccount::generate()]).to_hex();
+
+    assert!(matches!(
+        Keystore::from_hex(&exported).unwrap().unlock(b"wrong"),
+        Err(KeystoreError::WrongPassphrase)
+    ));
+}
+
+#[test]
+fn it_rejects_unsupported_versions_and_tampered_addresses() {
+    let mut bytes = hex::decode(keystore(&[WalletAccount::generate()]).to_hex()).unwrap();
+
+    // The version byte is followed by the number of entries and the address of the first entry.
+    bytes[3] ^= 0xff;
+    assert!(matches!(
+        Keystore::from_hex(&hex::encode(&bytes))
+            .unwrap()
+            .unlock(b"backup"),
+        Err(KeystoreError::AddressMismatch(_))
+    ));
+
+    bytes[0] = Keystore::VERSION + 1;
+    assert!(matches!(
+        Keystore::from_hex(&hex::encode(&bytes)),
+        Err(KeystoreError::UnsupportedVersion(version)) if version == Keystore::VERSION + 1
+    ));
+}
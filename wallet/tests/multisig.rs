@@ -0,0 +1,283 @@
+use nimiq_keys::multisig::{Commitment, PartialSignature};
+use nimiq_keys::{Address, KeyPair, PublicKey, SecureGenerate};
+use nimiq_primitives::coin::Coin;
+use nimiq_primitives::networks::NetworkId;
+use nimiq_test_log::test;
+use nimiq_transaction::Transaction;
+use nimiq_wallet::{
+    MultiSigAccount, MultiSigError, PendingCommitments, MAX_MULTISIG_PUBLIC_KEYS,
+    MAX_MULTISIG_SIGNER_SETS, MAX_PENDING_COMMITMENTS,
+};
+
+fn key_pairs(n: usize) -> Vec<KeyPair> {
+    (0..n).map(|_| KeyPair::generate_default_csprng()).collect()
+}
+
+fn transaction(sender: &Address) -> Transaction {
+    Transaction::new_basic(
+        sender.clone(),
+        Address::from([1u8; Address::SIZE]),
+        Coin::from_u64_unchecked(42),
+        Coin::ZERO,
+        0,
+        NetworkId::Main,
+    )
+}
+
+/// Creates a commitment for every signer, each one kept by the signer's own pending commitments.
+fn commitments(signers: &[KeyPair]) -> (Vec<PendingCommitments>, Vec<Commitment>) {
+    let mut pending: Vec<PendingCommitments> = signers
+        .iter()
+        .map(|_| PendingCommitments::default())
+        .collect();
+    let commitments = pending
+        .iter_mut()
+        .map(|pending| pending.create_commitment())
+        .collect();
+    (pending, commitments)
+}
+
+#[test]
+fn it_derives_the_same_address_regardless_of_key_order() {
+    let key_pairs = key_pairs(3);
+    let mut public_keys: Vec<PublicKey> = key_pairs.iter().map(|kp| kp.public).collect();
+
+    let account = MultiSigAccount::new(2, &public_keys).unwrap();
+    public_keys.reverse();
+    let reversed = MultiSigAccount::new(2, &public_keys).unwrap();
+
+    assert_eq!(account.address, reversed.address);
+    assert_ne!(
+        account.address,
+        MultiSigAccount::new(3, &public_keys).unwrap().address
+    );
+}
+
+#[test]
+fn it_can_sign_a_transaction_with_two_of_three_keys() {
+    let key_pairs = key_pairs(3);
+    let public_keys: Vec<PublicKey> = key_pairs.iter().map(|kp| kp.public).collect();
+    let account = MultiSigAccount::new(2, &public_keys).unwrap();
+
+    let mut transaction = transaction(&account.address);
+
+    let signers = &key_pairs[1..];
+    let signer_keys: Vec<PublicKey> = signers.iter().map(|kp| kp.public).collect();
+    let (mut pending, commitments) = commitments(signers);
+
+    let partial_signatures: Vec<PartialSignature> = signers
+        .iter()
+        .zip(pending.iter_mut())
+        .zip(commitments.iter())
+        .map(|((key_pair, pending), commitment)| {
+            account
+                .partially_sign_transaction(
+                    &transaction,
+                    key_pair,
+                    &signer_keys,
+                    commitment,
+                    &commitments,
+                    pending,
+                )
+                .unwrap()
+        })
+        .collect();
+
+    account
+        .sign_transaction(
+            &mut transaction,
+            &signer_keys,
+            &commitments,
+            &partial_signatures,
+        )
+        .unwrap();
+
+    assert_eq!(Ok(()), transaction.verify(NetworkId::Main));
+}
+
+#[test]
+fn it_uses_a_commitment_for_a_single_signature_only() {
+    let key_pairs = key_pairs(2);
+    let public_keys: Vec<PublicKey> = key_pairs.iter().map(|kp| kp.public).collect();
+    let account = MultiSigAccount::new(2, &public_keys).unwrap();
+    let (mut pending, commitments) = commitments(&key_pairs);
+
+    let transaction = transaction(&account.address);
+    assert!(account
+        .partially_sign_transaction(
+            &transaction,
+            &key_pairs[0],
+            &public_keys,
+            &commitments[0],
+            &commitments,
+            &mut pending[0],
+        )
+        .is_ok());
+    assert!(pending[0].is_empty());
+
+    // Signing a different transaction with the same commitment would reveal the private key.
+    let mut other_transaction = transaction.clone();
+    other_transaction.value = Coin::from_u64_unchecked(43);
+    assert_eq!(
+        account.partially_sign_transaction(
+            &other_transaction,
+            &key_pairs[0],
+            &public_keys,
+            &commitments[0],
+            &commitments,
+            &mut pending[0],
+        ),
+        Err(MultiSigError::UnknownCommitment)
+    );
+
+    // A commitment of another signer can't be used either.
+    assert_eq!(
+        account.partially_sign_transaction(
+            &transaction,
+            &key_pairs[0],
+            &public_keys,
+            &commitments[1],
+            &commitments,
+            &mut pending[0],
+        ),
+        Err(MultiSigError::UnknownCommitment)
+    );
+}
+
+#[test]
+fn it_rejects_invalid_signers() {
+    let key_pairs = key_pairs(3);
+    let public_keys: Vec<PublicKey> = key_pairs.iter().map(|kp| kp.public).collect();
+    let account = MultiSigAccount::new(2, &public_keys).unwrap();
+    let transaction = transaction(&account.address);
+
+    let mut pending = PendingCommitments::default();
+    let commitment = pending.create_commitment();
+    let outsider = KeyPair::generate_default_csprng();
+
+    assert_eq!(
+        account.partially_sign_transaction(
+            &transaction,
+            &key_pairs[0],
+            &public_keys,
+            &commitment,
+            &[commitment],
+            &mut pending,
+        ),
+        Err(MultiSigError::WrongNumberOfSigners(2, 3))
+    );
+    assert_eq!(
+        account.partially_sign_transaction(
+            &transaction,
+            &key_pairs[0],
+            &[key_pairs[0].public, outsider.public],
+            &commitment,
+            &[commitment, commitment],
+            &mut pending,
+        ),
+        Err(MultiSigError::UnknownSigner(outsider.public))
+    );
+    assert_eq!(
+        account.partially_sign_transaction(
+            &self::transaction(&Address::from([2u8; Address::SIZE])),
+            &key_pairs[0],
+            &public_keys[..2],
+            &commitment,
+            &[commitment, commitment],
+            &mut pending,
+        ),
+        Err(MultiSigError::WrongSender)
+    );
+    assert_eq!(pending.len(), 1);
+}
+
+#[test]
+fn it_rejects_invalid_accounts() {
+    let key_pairs = key_pairs(3);
+    let public_keys: Vec<PublicKey> = key_pairs.iter().map(|kp| kp.public).collect();
+
+    assert_eq!(
+        MultiSigAccount::new(4, &public_keys),
+        Err(MultiSigError::InvalidMinSignatures)
+    );
+
+    // Duplicate keys don't count towards the number of owners.
+    let duplicate_keys = [public_keys[0], public_keys[0], public_keys[1]];
+    assert_eq!(
+        MultiSigAccount::new(3, &duplicate_keys),
+        Err(MultiSigError::InvalidMinSignatures)
+    );
+
+    let many_keys: Vec<PublicKey> = self::key_pairs(MAX_MULTISIG_PUBLIC_KEYS + 1)
+        .iter()
+        .map(|kp| kp.public)
+        .collect();
+    assert_eq!(
+        MultiSigAccount::new(2, &many_keys),
+        Err(MultiSigError::TooManyPublicKeys(
+            MAX_MULTISIG_PUBLIC_KEYS + 1
+        ))
+    );
+
+    // 16 choose 8 sets of signers.
+    assert_eq!(MAX_MULTISIG_PUBLIC_KEYS, 16);
+    assert!(12870 > MAX_MULTISIG_SIGNER_SETS);
+    assert_eq!(
+        MultiSigAccount::new(8, &many_keys[..16]),
+        Err(MultiSigError::TooManySignerSets(12870))
+    );
+    assert!(MultiSigAccount::new(2, &many_keys[..16]).is_ok());
+}
+
+#[test]
+fn it_discards_the_oldest_pending_commitments() {
+    let mut pending = PendingCommitments::default();
+    let oldest = pending.create_commitment();
+    for _ in 0..MAX_PENDING_COMMITMENTS {
+        pending.create_commitment();
+    }
+
+    assert_eq!(pending.len(), MAX_PENDING_COMMITMENTS);
+    assert!(pending.take_random_secret(&oldest).is_none());
+}
+
+#[test]
+fn it_rejects_signatures_for_a_different_sender() {
+    let key_pairs = key_pairs(2);
+    let public_keys: Vec<PublicKey> = key_pairs.iter().map(|kp| kp.public).collect();
+    let account = MultiSigAccount::new(2, &public_keys).unwrap();
+    let (mut pending, commitments) = commitments(&key_pairs);
+
+    let transaction = transaction(&account.address);
+    let partial_signatures: Vec<PartialSignature> = key_pairs
+        .iter()
+        .zip(pending.iter_mut())
+        .zip(commitments.iter())
+        .map(|((key_pair, pending), commitment)| {
+            account
+                .partially_sign_transaction(
+                    &transaction,
+                    key_pair,
+                    &public_keys,
+                    commitment,
+                    &commitments,
+                    pending,
+                )
+                .unwrap()
+        })
+        .collect();
+
+    let mut other_sender = transaction.clone();
+    other_sender.sender = Address::from([2u8; Address::SIZE]);
+    assert_eq!(
+        account
+            .create_signature_proof(
+                &other_sender,
+                &public_keys,
+                &commitments,
+                &partial_signatures,
+            )
+            .err(),
+        Some(MultiSigError::WrongSender)
+    );
+}
@@ -21,7 +21,7 @@ use nimiq_network_interface::network::Network;
 use nimiq_network_interface::request::{Handle, RequestError};
 use nimiq_network_interface::{
     network::Topic,
-    request::{RequestCommon, RequestMarker},
+    request::{request_type_name, RequestCommon, RequestMarker},
 };
 use parking_lot::RwLock;
 use std::borrow::Cow;
@@ -501,6 +501,12 @@ impl RequestCommon for RequestZKP {
     const MAX_REQUESTS: u32 = MAX_REQUEST_RESPONSE_ZKP;
 }
 
+/// Returns the names of the request types that the ZKP component handles. Their rate limits are
+/// configured by these names.
+pub fn request_type_names() -> Vec<&'static str> {
+    vec![request_type_name::<RequestZKP>()]
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum RequestZKPResponse {